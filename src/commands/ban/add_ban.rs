@@ -1,14 +1,15 @@
 use serenity::all::{
     CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, EditMember,
-    ResolvedOption, ResolvedValue, UserId,
+    GuildId, ResolvedOption, ResolvedValue, UserId,
 };
 
 use crate::{
     modules::{
-        func::{check_permission, ensure_file_exists},
-        reminder::TW,
+        func::{check_permission, error_output},
+        reminder::guild_timezone,
+        storage::storage,
     },
-    BanList,
+    BanList, GuildSettingsMap,
 };
 
 // Register the ban command
@@ -35,6 +36,7 @@ pub async fn run<'a>(
     ctx: &Context,
     command: &CommandInteraction,
     ban_list: BanList,
+    guild_settings: GuildSettingsMap,
     options: &[ResolvedOption<'a>],
 ) -> String {
     // Check if the user has permission to use the command
@@ -48,24 +50,42 @@ pub async fn run<'a>(
     let member = options.iter().find(|option| option.name == "member");
     let mins = options.iter().find(|option| option.name == "mins");
 
-    // Extract member ID and name
-    // 提取成員 ID 和名稱
-    let (member_id, member_name) = if let Some(get_member) = member {
-        if let ResolvedValue::User(member, _option) = get_member.value {
-            (member.id, member.name.clone())
+    // Extract member ID, name and roles
+    // 提取成員 ID、名稱和身分組
+    let (member_id, member_name, member_roles) = if let Some(get_member) = member {
+        if let ResolvedValue::User(member, partial_member) = get_member.value {
+            let roles = partial_member.map(|m| m.roles.clone()).unwrap_or_default();
+            (member.id, member.name.clone(), roles)
         } else {
-            (UserId::default(), "".to_string())
+            (UserId::default(), "".to_string(), Vec::new())
         }
     } else {
-        (UserId::default(), "".to_string())
+        (UserId::default(), "".to_string(), Vec::new())
     };
 
-    // Prevent banning a specific user (服主)
-    // 防止封禁特定用戶（服主）
-    if member_id == UserId::from(412803325768237066) {
+    // Prevent banning the guild owner (服主)
+    // 防止封禁服主
+    let guild_id = command.guild_id.unwrap();
+    if guild_owner(ctx, guild_id).await == Some(member_id) {
         return "你不能封禁服主".to_string();
     }
 
+    // Prevent banning the protected member and members with the protected role set with /config
+    // 防止封禁以 /config 設定的受保護成員及擁有受保護身分組的成員
+    let (protected_user, protected_role) = guild_settings
+        .read()
+        .await
+        .get(&guild_id)
+        .map_or((None, None), |s| {
+            (s.ban_protected_user, s.ban_protected_role)
+        });
+    if protected_user == Some(member_id) {
+        return "你不能封禁受保護的成員".to_string();
+    }
+    if let Some(role_id) = protected_role.filter(|role_id| member_roles.contains(role_id)) {
+        return format!("你不能封禁擁有 <@&{}> 身分組的成員", role_id);
+    }
+
     // Extract ban duration
     // 提取封禁時間
     let mins = if let Some(get_mins) = mins {
@@ -85,24 +105,41 @@ pub async fn run<'a>(
     if ban_list.iter().any(|(id, _)| *id == member_id) {
         return format!("{}已經在封禁名單中", member_name);
     }
-    let now = chrono::Utc::now().with_timezone(&*TW).time();
+
+    // Mute the member in the guild first, so a failed mute leaves the ban list unchanged
+    // 先在伺服器中將成員靜音，靜音失敗時不會更動封禁列表
+    let builder = EditMember::new().mute(true);
+    if let Err(err) = guild_id.edit_member(ctx, member_id, builder).await {
+        println!("{} Failed to mute {}: {}", error_output(), member_id, err);
+        return format!("無法將{}靜音：{}", member_name, err);
+    }
+
+    let tz = guild_timezone(&*guild_settings.read().await, &guild_id);
+    let now = chrono::Utc::now().with_timezone(&tz).time();
     let ban_time = now + chrono::Duration::minutes(mins);
     ban_list.push((member_id, ban_time));
 
-    // Save the updated ban list to file
-    // 將更新後的封禁列表保存到文件
-    ensure_file_exists("assets/ban_list.json").unwrap();
-    let list_json = serde_json::to_string(&*ban_list).unwrap();
-    std::fs::write("assets/ban_list.json", list_json).unwrap();
+    // Save the updated ban list to storage
+    // 將更新後的封禁列表保存到存儲
+    if let Err(err) = storage().save_bans(&ban_list) {
+        println!("{} Failed to save ban list: {}", error_output(), err);
+    }
 
     println!("ban id: {}", member_id);
-
-    // Mute the member in the guild
-    // 在伺服器中將成員靜音
-    let guild_id = command.guild_id.unwrap();
-    let builder = EditMember::new().mute(true);
-    guild_id.edit_member(ctx, member_id, builder).await.unwrap();
     drop(ban_list);
 
     format!("成功將{}加入封禁名單，封禁時間為{}分鐘", member_name, mins)
+}
+
+// Get the owner of a guild from the cache, fetching the guild when it is not cached
+// 從快取取得伺服器擁有者，快取中沒有時向 Discord 取得伺服器
+async fn guild_owner(ctx: &Context, guild_id: GuildId) -> Option<UserId> {
+    if let Some(owner_id) = ctx.cache.guild(guild_id).map(|guild| guild.owner_id) {
+        return Some(owner_id);
+    }
+    guild_id
+        .to_partial_guild(&ctx.http)
+        .await
+        .ok()
+        .map(|guild| guild.owner_id)
 }
\ No newline at end of file
@@ -3,7 +3,13 @@ use serenity::all::{
     ResolvedOption, ResolvedValue,
 };
 
-use crate::{modules::func::check_permission, FraudBotList};
+use crate::{
+    modules::{
+        func::{check_permission, error_output},
+        storage::storage,
+    },
+    FraudBotList,
+};
 
 pub fn register() -> CreateCommand {
     CreateCommand::new("remove_block")
@@ -42,7 +48,13 @@ pub async fn run<'a>(
         return "無法解析使用者訊息".to_string();
     } ;
     
-    fraud_bot_list.write().await.remove(&user_id);
+    let mut list = fraud_bot_list.write().await;
+    if list.remove(&user_id) {
+        if let Err(err) = storage().save_fraud_list(&list) {
+            println!("{} Failed to save fraud list: {}", error_output(), err);
+        }
+    }
+    drop(list);
     
     format!("成功移除使用者 <@{}>",user_id)
 }
@@ -2,7 +2,13 @@ use serenity::all::{
     CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, EditMember, ResolvedOption, ResolvedValue, UserId
 };
 
-use crate::{modules::func::check_permission, BanList};
+use crate::{
+    modules::{
+        func::{check_permission, error_output},
+        storage::storage,
+    },
+    BanList,
+};
 
 // Register the unban command
 // 註冊解封命令
@@ -69,7 +75,10 @@ pub async fn run<'a>(
         // 在伺服器中取消成員的靜音
         let guild_id = command.guild_id.unwrap();
         let builder = EditMember::new().mute(false);
-        guild_id.edit_member(ctx, member_id, builder).await.unwrap();
+        if let Err(err) = guild_id.edit_member(ctx, member_id, builder).await {
+            println!("{} Failed to unmute {}: {}", error_output(), member_id, err);
+            return format!("已將{}移出封禁名單，但無法取消靜音：{}", member_name, err);
+        }
         
         format!("已將{}移出封禁名單", member_name)
     } else {
@@ -82,14 +91,10 @@ pub async fn run<'a>(
 pub async fn unban(ban_list: BanList, member_id: UserId) {
     let mut ban_list_guard = ban_list.write().await;
     ban_list_guard.retain(|user| user.0 != member_id);
-    let json = serde_json::to_string(&*ban_list_guard).unwrap();
-    drop(ban_list_guard);
-    
-    // Write the updated ban list to file
-    // 將更新後的封禁列表寫入文件
-    tokio::spawn(async move {
-        if let Err(e) = tokio::fs::write("assets/ban_list.json", json).await {
-            eprintln!("寫入 ban_list.json 時發生錯誤: {}", e);
-        }
-    });
+
+    // Write the updated ban list to storage
+    // 將更新後的封禁列表寫入存儲
+    if let Err(err) = storage().save_bans(&ban_list_guard) {
+        println!("{} Failed to save ban list: {}", error_output(), err);
+    }
 }
@@ -10,6 +10,22 @@ pub mod ping;
 // 引入查看更新日誌的模塊
 pub mod update;
 
+// import timezone module
+// 引入設定伺服器時區的模塊
+pub mod timezone;
+
+// import mention_everyone module
+// 引入設定提醒是否可提及 @everyone 的模塊
+pub mod mention_everyone;
+
+// import reminder_manager module
+// 引入設定提醒管理身分組的模塊
+pub mod reminder_manager;
+
+// import config module
+// 引入查看與變更伺服器設定的模塊
+pub mod config;
+
 
 
 // This module file defines the basic command structure of the bot
@@ -17,6 +33,10 @@ pub mod update;
 // - info: handles commands for displaying bot or server information
 // - ping: handles commands for checking bot response time
 // - update: handles commands for viewing update logs
+// - timezone: handles commands for viewing or setting the guild timezone
+// - mention_everyone: handles commands for allowing reminders to ping @everyone
+// - reminder_manager: handles commands for setting the role that may manage every reminder
+// - config: handles commands for viewing, setting and resetting the settings of a guild
 
 
 // These basic commands are typically used for:
@@ -29,6 +49,10 @@ pub mod update;
 // - info: 處理顯示機器人或伺服器信息的命令
 // - ping: 處理檢測機器人響應時間的命令
 // - update: 處理查看更新日誌的命令
+// - timezone: 處理查看或設定伺服器時區的命令
+// - mention_everyone: 處理設定提醒是否可提及 @everyone 的命令
+// - reminder_manager: 處理設定可以管理所有提醒的身分組的命令
+// - config: 處理查看、設定與重設伺服器設定的命令
 
 // 這些基礎命令通常用於：
 // 1. 檢查機器人是否在線和響應正常（ping）
@@ -0,0 +1,462 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use serenity::all::{
+    ChannelId, CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, GuildId,
+    ResolvedOption, ResolvedValue, RoleId, UserId,
+};
+use tokio::sync::Notify;
+
+use crate::{
+    modules::{
+        config::config,
+        func::{save_guild_settings, save_reminders},
+        reminder::TW,
+    },
+    GuildSettings, GuildSettingsMap, Recurrence, Reminders,
+};
+
+// Settings managed with /config and their names shown to members
+// 以 /config 管理的設定及顯示給成員的名稱
+const SETTINGS: [(&str, &str); 9] = [
+    ("timezone", "時區"),
+    ("allow_everyone", "允許提醒提及 @everyone 與 @here"),
+    ("manager_role", "提醒管理身分組"),
+    ("honeypot_channel", "誘捕頻道"),
+    ("voice_log_channel", "語音紀錄頻道"),
+    ("voice_ban_channel", "語音封禁頻道"),
+    ("tiktok_refuse", "回覆 TikTok 鏈接"),
+    ("ban_protected_role", "受保護身分組"),
+    ("ban_protected_user", "受保護成員"),
+];
+
+// Values given for a setting, each setting reads the one matching its kind
+// 為設定提供的值，每項設定讀取符合其類型的值
+#[derive(Default)]
+pub struct SettingValues<'a> {
+    pub zone: Option<&'a str>,
+    pub channel: Option<ChannelId>,
+    pub role: Option<RoleId>,
+    pub user: Option<UserId>,
+    pub enabled: Option<bool>,
+}
+
+// Register the config command
+// 註冊 config 命令
+pub fn register() -> CreateCommand {
+    let mut setting = CreateCommandOption::new(
+        CommandOptionType::String,
+        "setting",
+        "the setting to set or reset, every setting when resetting without one",
+    )
+    .description_localized("zh-TW", "要設定或重設的項目，重設時未選擇則重設所有項目");
+    for (name, label) in SETTINGS {
+        setting = setting.add_string_choice_localized(name, name, [("zh-TW", label)]);
+    }
+
+    CreateCommand::new("config")
+        .description("view, set or reset the settings of this server")
+        .description_localized("zh-TW", "查看、設定或重設此伺服器的設定")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "action", "what to do")
+                .description_localized("zh-TW", "要做的操作")
+                .add_string_choice_localized("view", "view", [("zh-TW", "查看")])
+                .add_string_choice_localized("set", "set", [("zh-TW", "設定")])
+                .add_string_choice_localized("reset", "reset", [("zh-TW", "重設")])
+                .required(true),
+        )
+        .add_option(setting)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "zone",
+                "value of the timezone, an IANA name such as Asia/Taipei",
+            )
+            .description_localized("zh-TW", "時區的值，IANA 時區名稱，例如 Asia/Taipei"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "value of the channel settings",
+            )
+            .description_localized("zh-TW", "頻道類設定的值"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Role,
+                "role",
+                "value of the role settings",
+            )
+            .description_localized("zh-TW", "身分組類設定的值"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::User,
+                "user",
+                "value of the member settings",
+            )
+            .description_localized("zh-TW", "成員類設定的值"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "enabled",
+                "value of the on/off settings",
+            )
+            .description_localized("zh-TW", "開關類設定的值"),
+        )
+}
+
+// Run the config command, only administrators reach it
+// 執行 config 命令，只有管理員可以使用
+pub async fn run<'a>(
+    command: &CommandInteraction,
+    guild_settings: GuildSettingsMap,
+    reminders: Reminders,
+    notify: &Arc<Notify>,
+    options: &'a [ResolvedOption<'a>],
+) -> String {
+    let guild_id = command.guild_id.unwrap();
+
+    // Find every option from the command
+    // 從命令中找到所有選項
+    let get_string = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| match opt.value {
+                ResolvedValue::String(s) => Some(s),
+                _ => None,
+            })
+    };
+    let action = get_string("action").unwrap_or("view");
+    let setting = get_string("setting");
+    let values = SettingValues {
+        zone: get_string("zone").map(str::trim),
+        channel: options
+            .iter()
+            .find(|opt| opt.name == "channel")
+            .and_then(|opt| match opt.value {
+                ResolvedValue::Channel(channel) => Some(channel.id),
+                _ => None,
+            }),
+        role: options
+            .iter()
+            .find(|opt| opt.name == "role")
+            .and_then(|opt| match opt.value {
+                ResolvedValue::Role(role) => Some(role.id),
+                _ => None,
+            }),
+        user: options
+            .iter()
+            .find(|opt| opt.name == "user")
+            .and_then(|opt| match opt.value {
+                ResolvedValue::User(user, _) => Some(user.id),
+                _ => None,
+            }),
+        enabled: options
+            .iter()
+            .find(|opt| opt.name == "enabled")
+            .and_then(|opt| match opt.value {
+                ResolvedValue::Boolean(b) => Some(b),
+                _ => None,
+            }),
+    };
+
+    let result = match (action, setting) {
+        ("view", _) => {
+            let settings = guild_settings.read().await;
+            return format_settings(&settings.get(&guild_id).cloned().unwrap_or_default());
+        }
+        ("set", None) => return ">> 請選擇要設定的項目".to_string(),
+        ("set", Some(setting)) => set(&guild_settings, guild_id, setting, values).await,
+        ("reset", setting) => reset(&guild_settings, guild_id, setting).await,
+        _ => return ">> 未知的操作".to_string(),
+    };
+    let msg = match result {
+        Ok(msg) => msg,
+        Err(msg) => return msg,
+    };
+
+    // A changed timezone moves the local times of the reminders
+    // 時區變更會改變提醒的當地時間
+    if setting.is_none_or(|setting| setting == "timezone") {
+        if let Err(msg) = reschedule(&reminders, notify, guild_id).await {
+            return msg;
+        }
+    }
+    msg
+}
+
+// Change one setting of a guild and save it, returning the reply or why it was not changed.
+// The older commands for single settings change them through here as well
+// 變更伺服器的一項設定並保存，返回回覆訊息或未變更的原因。
+// 舊的單項設定命令也透過此處變更設定
+pub async fn set(
+    guild_settings: &GuildSettingsMap,
+    guild_id: GuildId,
+    setting: &str,
+    values: SettingValues<'_>,
+) -> Result<String, String> {
+    let mut settings = guild_settings.write().await;
+    let mut updated = settings.clone();
+    let msg = set_setting(updated.entry(guild_id).or_default(), setting, values)?;
+    // Save the changed copy first, so a failed save leaves the settings in memory untouched
+    // 先保存修改後的副本，保存失敗時記憶體中的設定保持不變
+    save_guild_settings(&updated).map_err(|err| format!(">> 儲存設定時發生錯誤: {}", err))?;
+    *settings = updated;
+    Ok(msg)
+}
+
+// Put one setting of a guild, or every setting without one, back to its default and save it
+// 將伺服器的一項設定（未指定時為所有設定）恢復為預設值並保存
+pub async fn reset(
+    guild_settings: &GuildSettingsMap,
+    guild_id: GuildId,
+    setting: Option<&str>,
+) -> Result<String, String> {
+    let mut settings = guild_settings.write().await;
+    let mut updated = settings.clone();
+    let guild = updated.entry(guild_id).or_default();
+    let msg = match setting {
+        Some(setting) => {
+            if !SETTINGS.iter().any(|(name, _)| *name == setting) {
+                return Err(">> 未知的設定項目".to_string());
+            }
+            reset_setting(guild, setting);
+            format!(">> 已重設{}", setting_label(setting))
+        }
+        None => {
+            for (name, _) in SETTINGS {
+                reset_setting(guild, name);
+            }
+            ">> 已重設所有設定".to_string()
+        }
+    };
+    save_guild_settings(&updated).map_err(|err| format!(">> 儲存設定時發生錯誤: {}", err))?;
+    *settings = updated;
+    Ok(msg)
+}
+
+// Reschedule the reminders of a guild after its timezone changed; personal reminders and
+// scheduled one-shots, which may have been set as a duration from now, keep their instant
+// 伺服器時區變更後重新排程其提醒；個人提醒與已排程的單次提醒可能是以相對時間設定，保留原本的時間點
+pub async fn reschedule(
+    reminders: &Reminders,
+    notify: &Arc<Notify>,
+    guild_id: GuildId,
+) -> Result<(), String> {
+    {
+        let mut reminders = reminders.write().await;
+        let mut updated = reminders.clone();
+        if let Some(guild_reminder) = updated.get_mut(&guild_id) {
+            for reminder in guild_reminder
+                .values_mut()
+                .flatten()
+                .filter(|r| r.dm_user.is_none())
+                .filter(|r| {
+                    !matches!(r.recurrence, Recurrence::Once { .. }) || r.next_due.is_none()
+                })
+            {
+                reminder.next_due = None;
+            }
+            save_reminders(&updated).map_err(|err| format!(">> 儲存提醒時發生錯誤: {}", err))?;
+            *reminders = updated;
+        }
+    }
+    notify.notify_one();
+    Ok(())
+}
+
+// Change one setting, returning the reply or why the value is missing
+// 變更一項設定，返回回覆訊息或缺少值的原因
+fn set_setting(
+    settings: &mut GuildSettings,
+    setting: &str,
+    values: SettingValues,
+) -> Result<String, String> {
+    let label = setting_label(setting);
+    match setting {
+        "timezone" => {
+            let zone = values
+                .zone
+                .ok_or(format!(">> 請以 zone 選項提供{}", label))?;
+            let tz: Tz = zone
+                .parse()
+                .map_err(|_| format!(">> 無效的時區：{}，ex. Asia/Taipei", zone))?;
+            settings.timezone = Some(tz.name().to_string());
+            Ok(format!(
+                ">> 已將時區設定為 {} (當地時間 {})",
+                tz.name(),
+                Utc::now().with_timezone(&tz).format("%Y-%m-%d %H:%M")
+            ))
+        }
+        "allow_everyone" => {
+            let enabled = values
+                .enabled
+                .ok_or(format!(">> 請以 enabled 選項設定是否{}", label))?;
+            settings.allow_everyone = enabled;
+            Ok(if enabled {
+                ">> 已允許提醒提及 @everyone 與 @here".to_string()
+            } else {
+                ">> 已禁止提醒提及 @everyone 與 @here".to_string()
+            })
+        }
+        "manager_role" => {
+            let role = values
+                .role
+                .ok_or(format!(">> 請以 role 選項提供{}", label))?;
+            settings.manager_role = Some(role);
+            Ok(format!(
+                ">> 已將提醒管理身分組設為 <@&{}>，擁有此身分組的成員可以管理所有提醒",
+                role
+            ))
+        }
+        "honeypot_channel" | "voice_log_channel" | "voice_ban_channel" => {
+            let channel = values
+                .channel
+                .ok_or(format!(">> 請以 channel 選項提供{}", label))?;
+            let slot = match setting {
+                "honeypot_channel" => &mut settings.honeypot_channel,
+                "voice_log_channel" => &mut settings.voice_log_channel,
+                _ => &mut settings.voice_ban_channel,
+            };
+            *slot = Some(channel);
+            Ok(format!(">> 已將{}設為 <#{}>", label, channel))
+        }
+        "tiktok_refuse" => {
+            let enabled = values
+                .enabled
+                .ok_or(format!(">> 請以 enabled 選項設定{}", label))?;
+            settings.tiktok_refuse = enabled;
+            Ok(format!(
+                ">> 已{}{}",
+                if enabled { "開啟" } else { "關閉" },
+                label
+            ))
+        }
+        "ban_protected_role" => {
+            let role = values
+                .role
+                .ok_or(format!(">> 請以 role 選項提供{}", label))?;
+            settings.ban_protected_role = Some(role);
+            Ok(format!(">> 已將{}設為 <@&{}>", label, role))
+        }
+        "ban_protected_user" => {
+            let user = values
+                .user
+                .ok_or(format!(">> 請以 user 選項提供{}", label))?;
+            settings.ban_protected_user = Some(user);
+            Ok(format!(">> 已將{}設為 <@{}>", label, user))
+        }
+        _ => Err(">> 未知的設定項目".to_string()),
+    }
+}
+
+// Put one setting back to its default
+// 將一項設定恢復為預設值
+fn reset_setting(settings: &mut GuildSettings, setting: &str) {
+    match setting {
+        "timezone" => settings.timezone = None,
+        "allow_everyone" => settings.allow_everyone = false,
+        "manager_role" => settings.manager_role = None,
+        "honeypot_channel" => settings.honeypot_channel = None,
+        "voice_log_channel" => settings.voice_log_channel = None,
+        "voice_ban_channel" => settings.voice_ban_channel = None,
+        "tiktok_refuse" => settings.tiktok_refuse = false,
+        "ban_protected_role" => settings.ban_protected_role = None,
+        "ban_protected_user" => settings.ban_protected_user = None,
+        _ => {}
+    }
+}
+
+// Name of a setting shown to members
+// 顯示給成員的設定名稱
+fn setting_label(setting: &str) -> &str {
+    SETTINGS
+        .iter()
+        .find(|(name, _)| *name == setting)
+        .map_or(setting, |(_, label)| label)
+}
+
+// Show every setting of the guild, noting those whose feature is turned off in the
+// configuration file
+// 顯示伺服器的所有設定，並註明功能已在設定文件中關閉的項目
+fn format_settings(settings: &GuildSettings) -> String {
+    let features = &config().features;
+    let on_off = |enabled: bool| if enabled { "開啟" } else { "關閉" }.to_string();
+    let channel = |channel: Option<ChannelId>| {
+        channel.map_or("未設定".to_string(), |id| format!("<#{}>", id))
+    };
+    let role =
+        |role: Option<RoleId>| role.map_or("未設定".to_string(), |id| format!("<@&{}>", id));
+    let timezone = match &settings.timezone {
+        Some(name) => name.clone(),
+        None => format!("未設定 (使用 {})", TW.name()),
+    };
+    let lines = [
+        ("timezone", timezone, true),
+        (
+            "allow_everyone",
+            on_off(settings.allow_everyone),
+            features.reminders,
+        ),
+        (
+            "manager_role",
+            role(settings.manager_role),
+            features.reminders,
+        ),
+        (
+            "honeypot_channel",
+            channel(settings.honeypot_channel),
+            features.fraud_guard,
+        ),
+        (
+            "voice_log_channel",
+            channel(settings.voice_log_channel),
+            features.voice,
+        ),
+        (
+            "voice_ban_channel",
+            channel(settings.voice_ban_channel),
+            features.voice,
+        ),
+        (
+            "tiktok_refuse",
+            on_off(settings.tiktok_refuse),
+            features.anti_tiktok,
+        ),
+        (
+            "ban_protected_role",
+            role(settings.ban_protected_role),
+            features.ban,
+        ),
+        (
+            "ban_protected_user",
+            settings
+                .ban_protected_user
+                .map_or("未設定".to_string(), |id| format!("<@{}>", id)),
+            features.ban,
+        ),
+    ];
+
+    let mut msg = ">> 伺服器設定：".to_string();
+    for (name, value, enabled) in lines {
+        msg.push_str(&format!("\n{}：{}", setting_label(name), value));
+        if !enabled {
+            msg.push_str("（此功能已在設定文件中關閉）");
+        }
+    }
+    // Holidays are a list, managed with /holiday
+    // 假日是一份清單，以 /holiday 管理
+    msg.push_str(&format!(
+        "\n假日：{} 天 (以 /holiday 管理)",
+        settings.holidays.len()
+    ));
+    if !features.reminders {
+        msg.push_str("（此功能已在設定文件中關閉）");
+    }
+    msg
+}
@@ -0,0 +1,73 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, ResolvedOption,
+    ResolvedValue,
+};
+
+use super::config::{self, SettingValues};
+use crate::{
+    modules::{func::has_admin_permission, reminder::guild_allows_everyone},
+    GuildSettingsMap,
+};
+
+// Register the mention_everyone command
+// 註冊 mention_everyone 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("mention_everyone")
+        .description("view or set whether reminders may ping @everyone and @here")
+        .description_localized("zh-TW", "查看或設定提醒是否可以提及 @everyone 與 @here")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "enabled",
+                "allow reminders to ping @everyone and @here",
+            )
+            .description_localized("zh-TW", "是否允許提醒提及 @everyone 與 @here"),
+        )
+}
+
+// Run the mention_everyone command
+// 執行 mention_everyone 命令
+pub async fn run<'a>(
+    command: &CommandInteraction,
+    guild_settings: GuildSettingsMap,
+    options: &'a [ResolvedOption<'a>],
+) -> String {
+    let guild_id = command.guild_id.unwrap();
+
+    // Find the enabled option from the command
+    // 從命令中找到 enabled 選項
+    let enabled = options
+        .iter()
+        .find(|opt| opt.name == "enabled")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Boolean(b) => Some(b),
+            _ => None,
+        });
+
+    // Without the option, show the current setting
+    // 未提供選項時，顯示目前設定
+    let Some(enabled) = enabled else {
+        let settings = guild_settings.read().await;
+        return if guild_allows_everyone(&settings, &guild_id) {
+            ">> 目前允許提醒提及 @everyone 與 @here".to_string()
+        } else {
+            ">> 目前不允許提醒提及 @everyone 與 @here".to_string()
+        };
+    };
+
+    // Only administrators can change the setting
+    // 只有管理員可以更改設定
+    if !has_admin_permission(command) {
+        return "你沒有許可權使用指令".to_string();
+    }
+
+    // Save the new setting as /config does, reminders read it when they are sent
+    // 與 /config 相同保存新的設定，提醒會在發送時讀取
+    let values = SettingValues {
+        enabled: Some(enabled),
+        ..Default::default()
+    };
+    config::set(&guild_settings, guild_id, "allow_everyone", values)
+        .await
+        .unwrap_or_else(|msg| msg)
+}
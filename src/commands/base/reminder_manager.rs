@@ -0,0 +1,88 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, ResolvedOption,
+    ResolvedValue,
+};
+
+use super::config::{self, SettingValues};
+use crate::{modules::func::has_admin_permission, GuildSettingsMap};
+
+// Register the reminder_manager command
+// 註冊 reminder_manager 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("reminder_manager")
+        .description("view or set the role that may manage every reminder")
+        .description_localized("zh-TW", "查看或設定可以管理所有提醒的身分組")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Role,
+                "role",
+                "role whose members may create and remove reminders",
+            )
+            .description_localized("zh-TW", "可以建立與移除提醒的身分組"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "clear",
+                "remove the reminder manager role",
+            )
+            .description_localized("zh-TW", "移除提醒管理身分組"),
+        )
+}
+
+// Run the reminder_manager command
+// 執行 reminder_manager 命令
+pub async fn run<'a>(
+    command: &CommandInteraction,
+    guild_settings: GuildSettingsMap,
+    options: &'a [ResolvedOption<'a>],
+) -> String {
+    let guild_id = command.guild_id.unwrap();
+
+    // Find the role and clear options from the command
+    // 從命令中找到 role 與 clear 選項
+    let role = options
+        .iter()
+        .find(|opt| opt.name == "role")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Role(role) => Some(role.id),
+            _ => None,
+        });
+    let clear = options
+        .iter()
+        .find(|opt| opt.name == "clear")
+        .is_some_and(|opt| matches!(opt.value, ResolvedValue::Boolean(true)));
+
+    // Without options, show the current setting
+    // 未提供選項時，顯示目前設定
+    if role.is_none() && !clear {
+        let settings = guild_settings.read().await;
+        return match settings.get(&guild_id).and_then(|s| s.manager_role) {
+            Some(role_id) => format!(">> 目前的提醒管理身分組：<@&{}>", role_id),
+            None => ">> 尚未設定提醒管理身分組，只有管理員可以管理所有提醒".to_string(),
+        };
+    }
+    if role.is_some() && clear {
+        return ">> role 與 clear 只能擇一設定".to_string();
+    }
+
+    // Only administrators can change the setting
+    // 只有管理員可以更改設定
+    if !has_admin_permission(command) {
+        return "你沒有許可權使用指令".to_string();
+    }
+
+    // Change the setting as /config does
+    // 與 /config 相同變更設定
+    let result = match role {
+        Some(role) => {
+            let values = SettingValues {
+                role: Some(role),
+                ..Default::default()
+            };
+            config::set(&guild_settings, guild_id, "manager_role", values).await
+        }
+        None => config::reset(&guild_settings, guild_id, Some("manager_role")).await,
+    };
+    result.unwrap_or_else(|msg| msg)
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, ResolvedOption,
+    ResolvedValue,
+};
+use tokio::sync::Notify;
+
+use super::config::{self, SettingValues};
+use crate::{
+    modules::{func::has_admin_permission, reminder::guild_timezone},
+    GuildSettingsMap, Reminders,
+};
+
+// Register the timezone command
+// 註冊 timezone 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("timezone")
+        .description("view or set the timezone used by reminders and bans")
+        .description_localized("zh-TW", "查看或設定提醒與封禁使用的時區")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "zone",
+                "IANA timezone name, e.g. Asia/Taipei or Europe/Berlin",
+            )
+            .description_localized("zh-TW", "IANA 時區名稱，例如 Asia/Taipei 或 Europe/Berlin"),
+        )
+}
+
+// Run the timezone command
+// 執行 timezone 命令
+pub async fn run<'a>(
+    command: &CommandInteraction,
+    guild_settings: GuildSettingsMap,
+    reminders: Reminders,
+    notify: &Arc<Notify>,
+    options: &'a [ResolvedOption<'a>],
+) -> String {
+    let guild_id = command.guild_id.unwrap();
+
+    // Find the zone option from the command
+    // 從命令中找到時區選項
+    let zone = options
+        .iter()
+        .find(|opt| opt.name == "zone")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::String(s) => Some(s.trim()),
+            _ => None,
+        });
+
+    // Without a zone, show the current setting
+    // 未提供時區時，顯示目前設定
+    let Some(zone) = zone else {
+        let settings = guild_settings.read().await;
+        let tz = guild_timezone(&settings, &guild_id);
+        let now = Utc::now().with_timezone(&tz);
+        return format!(
+            ">> 目前時區：{} (當地時間 {})",
+            tz.name(),
+            now.format("%Y-%m-%d %H:%M")
+        );
+    };
+
+    // Only administrators can change the timezone
+    // 只有管理員可以更改時區
+    if !has_admin_permission(command) {
+        return "你沒有許可權使用指令".to_string();
+    }
+
+    // Save the new timezone and reschedule the reminders, as /config does
+    // 與 /config 相同，保存新的時區並重新排程提醒
+    let values = SettingValues {
+        zone: Some(zone),
+        ..Default::default()
+    };
+    let msg = match config::set(&guild_settings, guild_id, "timezone", values).await {
+        Ok(msg) => msg,
+        Err(msg) => return msg,
+    };
+    if let Err(msg) = config::reschedule(&reminders, notify, guild_id).await {
+        return msg;
+    }
+    msg
+}
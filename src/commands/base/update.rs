@@ -8,7 +8,7 @@ use serenity::all::{
     ResolvedOption, ResolvedValue,
 };
 
-use crate::modules::func::interaction_response;
+use crate::modules::{config::config, func::interaction_response};
 
 // Register the update command
 // 註冊 update 命令
@@ -59,13 +59,13 @@ pub async fn run<'a>(
         false
     };
 
-    let update_dir = "assets/update";
+    let update_dir = config().data_path("update");
 
     // Handle the "show all" option
     // 處理 "顯示所有" 選項
     if show_all {
         interaction_response(ctx, command, "查看所有更新日誌".to_string(), !is_public).await;
-        let all_logs = read_all_update_logs(update_dir);
+        let all_logs = read_all_update_logs(&update_dir);
         for log in all_logs {
             command.channel_id.say(ctx, log).await?;
         }
@@ -73,7 +73,7 @@ pub async fn run<'a>(
 
     // Read and display the latest update log
     // 讀取並顯示最新的更新日誌
-    let all_logs = read_latest_update_log(update_dir);
+    let all_logs = read_latest_update_log(&update_dir);
     interaction_response(ctx, command, all_logs[0].clone(), !is_public).await;
 
     Ok(())
@@ -1,58 +1,14 @@
-use std::{
-    collections::HashMap,
-    error::Error,
-    fs,
-    ops::{Deref, DerefMut},
-};
+use std::error::Error;
 
-use serde::{Deserialize, Serialize};
 use serenity::all::{
     CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption,
-    ResolvedValue, UserId,
+    CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue,
 };
 
-// Define Cash struct to store individual debt information
-// 定義 Cash 結構體，用於存儲單筆欠款信息
-#[derive(Deserialize, Serialize)]
-struct Cash {
-    creator: UserId,
-    debtor: String,
-    creditor: String,
-    debt: usize,
-    ps: String,
-}
-
-// Define CashList struct to store all debt information
-// 定義 CashList 結構體，用於存儲所有欠款信息
-#[derive(Deserialize, Serialize)]
-struct CashList(HashMap<GuildId, Vec<Cash>>);
-
-impl CashList {
-    // Create a new CashList instance
-    // 創建新的 CashList 實例
-    fn new() -> Self {
-        let list: HashMap<GuildId, Vec<Cash>> = HashMap::new();
-        CashList(list)
-    }
-}
-
-// Implement Deref trait to allow direct use of HashMap methods on CashList
-// 實現 Deref trait，允許 CashList 直接使用 HashMap 的方法
-impl Deref for CashList {
-    type Target = HashMap<GuildId, Vec<Cash>>;
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-// Implement DerefMut trait to allow direct use of mutable HashMap methods on CashList
-// 實現 DerefMut trait，允許 CashList 直接使用 HashMap 的可變方法
-impl DerefMut for CashList {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
+use crate::{
+    modules::storage::{storage, CashMap},
+    Cash, CashLists,
+};
 
 // Register the cash command
 // 註冊 cash 命令
@@ -98,6 +54,7 @@ pub fn register() -> CreateCommand {
 pub async fn run<'a>(
     ctx: &Context,
     command: &CommandInteraction,
+    cash_lists: CashLists,
     options: &'a [ResolvedOption<'a>],
 ) {
     // 從選項中獲取各個參數的值
@@ -157,7 +114,7 @@ pub async fn run<'a>(
     // Execute the corresponding operation based on the command type
     // 根據命令類型執行相應的操作
     match command_type {
-        "look" => look(ctx, command).await,
+        "look" => look(ctx, command, &cash_lists).await,
         "add" => {
             let creator = command.user.id;
             let cash = Cash {
@@ -167,11 +124,11 @@ pub async fn run<'a>(
                 debt,
                 ps,
             };
-            add(ctx, command, cash).await
+            add(ctx, command, &cash_lists, cash).await
         }
         "del" => {
             if let Some(index) = index {
-                del(ctx, command, index).await
+                del(ctx, command, &cash_lists, index).await
             } else {
                 let content = String::from(">> 請輸入索引");
                 let data = CreateInteractionResponseMessage::new()
@@ -194,12 +151,9 @@ pub async fn run<'a>(
 
 // View the debt list
 // 查看欠款列表
-async fn look(ctx: &Context, command: &CommandInteraction) {
-    let cash_lists = match load_cash_data() {
-        Ok(cash_lists) => cash_lists,
-        Err(_) => CashList::new(),
-    };
+async fn look(ctx: &Context, command: &CommandInteraction, cash_lists: &CashLists) {
     let guild_id = command.guild_id.unwrap();
+    let cash_lists = cash_lists.read().await;
     let cash: Vec<Cash> = Vec::new();
     let cash_list = cash_lists.get(&guild_id).unwrap_or(&cash);
 
@@ -228,6 +182,7 @@ async fn look(ctx: &Context, command: &CommandInteraction) {
         }
         content
     };
+    drop(cash_lists);
 
     let data = CreateInteractionResponseMessage::new()
         .content(content)
@@ -238,106 +193,64 @@ async fn look(ctx: &Context, command: &CommandInteraction) {
 
 // Add a new debt record
 // 添加新的欠款記錄
-async fn add(ctx: &Context, command: &CommandInteraction, cash: Cash) {
+async fn add(ctx: &Context, command: &CommandInteraction, cash_lists: &CashLists, cash: Cash) {
     let guild_id = command.guild_id.unwrap();
-    let mut cash_lists = match load_cash_data().ok() {
-        Some(cash_lists) => cash_lists,
-        None => CashList::new(),
+    // The list stays locked from the change until it is saved, so concurrent commands cannot
+    // lose each other's updates. It only changes once the save succeeded
+    // 從變更到保存期間列表保持鎖定，同時執行的命令不會遺失彼此的更新。保存成功後才會變更列表
+    let mut cash_lists = cash_lists.write().await;
+    let mut updated = cash_lists.clone();
+    updated.entry(guild_id).or_default().push(cash);
+    let content = match save_cash_data(&updated) {
+        Ok(()) => {
+            *cash_lists = updated;
+            String::from(">> 已加入欠債")
+        }
+        Err(e) => format!(">> 儲存資料時發生錯誤: {}", e),
     };
+    drop(cash_lists);
 
-    let cash_list = cash_lists.entry(guild_id).or_insert(Vec::new());
-    cash_list.push(cash);
-    if let Err(e) = save_cash_data(&cash_lists) {
-        let content = format!(">> 儲存資料時發生錯誤: {}", e);
-        let data = CreateInteractionResponseMessage::new()
-            .content(content)
-            .ephemeral(true);
-        let builder = CreateInteractionResponse::Message(data);
-        command.create_response(&ctx.http, builder).await.unwrap();
-    } else {
-        let content = String::from(">> 已加入欠債");
-        let data = CreateInteractionResponseMessage::new()
-            .content(content)
-            .ephemeral(true);
-        let builder = CreateInteractionResponse::Message(data);
-        command.create_response(&ctx.http, builder).await.unwrap();
-    }
+    let data = CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    command.create_response(&ctx.http, builder).await.ok();
 }
 
 // Delete a specified debt record
 // 刪除指定的欠款記錄
-async fn del(ctx: &Context, command: &CommandInteraction, index: usize) {
-    let mut cash_lists = match load_cash_data().ok() {
-        Some(cash_lists) => cash_lists,
-        None => {
-            let content = String::from(">> 沒有可刪除的債務");
-            let data = CreateInteractionResponseMessage::new()
-                .content(content)
-                .ephemeral(true);
-            let builder = CreateInteractionResponse::Message(data);
-            command.create_response(&ctx.http, builder).await.ok();
-            return;
-        }
-    };
+async fn del(ctx: &Context, command: &CommandInteraction, cash_lists: &CashLists, index: usize) {
     let guild_id = command.guild_id.unwrap();
-
-    if let Some(cash_list) = cash_lists.get_mut(&guild_id) {
-        if cash_list[index - 1].creator != command.user.id {
-            let content = String::from(">> 你沒有刪除此債務的權力");
-            let data = CreateInteractionResponseMessage::new()
-                .content(content)
-                .ephemeral(true);
-            let builder = CreateInteractionResponse::Message(data);
-            command.create_response(&ctx.http, builder).await.unwrap();
-            return;
+    let mut cash_lists = cash_lists.write().await;
+    let mut updated = cash_lists.clone();
+    let content = match updated.get_mut(&guild_id) {
+        None => String::from(">> 沒有可刪除的債務"),
+        Some(cash_list) if index == 0 || index > cash_list.len() => String::from(">> 索引超出範圍"),
+        Some(cash_list) if cash_list[index - 1].creator != command.user.id => {
+            String::from(">> 你沒有刪除此債務的權力")
         }
-        if index > 0 && index <= cash_list.len() {
+        Some(cash_list) => {
             cash_list.remove(index - 1);
-            if let Err(e) = save_cash_data(&cash_lists) {
-                let content = format!(">> 儲存資料時發生錯誤: {}", e);
-                let data = CreateInteractionResponseMessage::new()
-                    .content(content)
-                    .ephemeral(true);
-                let builder = CreateInteractionResponse::Message(data);
-                command.create_response(&ctx.http, builder).await.unwrap();
-            } else {
-                let content = String::from(">> 已刪除所選債務");
-                let data = CreateInteractionResponseMessage::new()
-                    .content(content)
-                    .ephemeral(true);
-                let builder = CreateInteractionResponse::Message(data);
-                command.create_response(&ctx.http, builder).await.ok();
+            match save_cash_data(&updated) {
+                Ok(()) => {
+                    *cash_lists = updated;
+                    String::from(">> 已刪除所選債務")
+                }
+                Err(e) => format!(">> 儲存資料時發生錯誤: {}", e),
             }
-        } else {
-            let content = String::from(">> 索引超出範圍");
-            let data = CreateInteractionResponseMessage::new()
-                .content(content)
-                .ephemeral(true);
-            let builder = CreateInteractionResponse::Message(data);
-            command.create_response(&ctx.http, builder).await.ok();
         }
-    } else {
-        let content = String::from(">> 沒有可刪除的債務");
-        let data = CreateInteractionResponseMessage::new()
-            .content(content)
-            .ephemeral(true);
-        let builder = CreateInteractionResponse::Message(data);
-        command.create_response(&ctx.http, builder).await.ok();
-    }
-}
+    };
+    drop(cash_lists);
 
-// Save debt data to file
-// 保存欠款數據到文件
-fn save_cash_data(cash_list: &CashList) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let json_content = serde_json::to_string(cash_list)?;
-    fs::write("./cash.json", json_content)?;
-    Ok(())
+    let data = CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    command.create_response(&ctx.http, builder).await.ok();
 }
 
-// Load debt data from file
-// 從文件加載欠款數據
-fn load_cash_data() -> Result<CashList, Box<dyn Error + Send + Sync>> {
-    let json_content = fs::read_to_string("./cash.json")?;
-    let cash: CashList = serde_json::from_str(&json_content)?;
-    Ok(cash)
+// Save debt data to storage
+// 保存欠款數據到存儲
+fn save_cash_data(cash_lists: &CashMap) -> Result<(), Box<dyn Error + Send + Sync>> {
+    storage().save_cash(cash_lists)
 }
@@ -2,10 +2,54 @@
 // 引入設置提醒的功能模塊
 pub mod remind;
 
+// import remind_once module
+// 引入設置單次提醒的功能模塊
+pub mod remind_once;
+
+// import remindme module
+// 引入設置個人私訊提醒的功能模塊
+pub mod remindme;
+
 // import rm_remind module
 // 引入移除提醒的功能模塊
 pub mod rm_remind;
 
+// import edit_remind module
+// 引入修改提醒的功能模塊
+pub mod edit_remind;
+
+// import pause_remind module
+// 引入暫停提醒的功能模塊
+pub mod pause_remind;
+
+// import resume_remind module
+// 引入恢復提醒的功能模塊
+pub mod resume_remind;
+
+// import holiday module
+// 引入管理伺服器假日的功能模塊
+pub mod holiday;
+
+// import export_ics module
+// 引入匯出 .ics 行事曆的功能模塊
+pub mod export_ics;
+
+// import import_ics module
+// 引入從 .ics 行事曆匯入提醒的功能模塊
+pub mod import_ics;
+
+// import delivery_log module
+// 引入查看提醒發送紀錄的功能模塊
+pub mod delivery_log;
+
+// import autocomplete module
+// 引入提醒 ID 自動完成的功能模塊
+pub mod autocomplete;
+
+// import buttons module
+// 引入處理提醒按鈕的功能模塊
+pub mod buttons;
+
 // import look module
 // 引入查看提醒的功能模塊
 pub mod look;
@@ -13,11 +57,29 @@ pub mod look;
 // This module file defines the command structure related to the reminder functionality
 // Each sub-module contains commands for specific functionalities:
 // - remind: handles the functionality of setting new reminders
+// - remind_once: handles the functionality of setting one-shot reminders on a specific date
+// - remindme: handles the functionality of setting personal reminders delivered by DM
 // - rm_remind: handles the functionality of removing existing reminders
+// - edit_remind: handles the functionality of editing an existing reminder by ID
+// - pause_remind / resume_remind: handles pausing and resuming a reminder by ID
+// - holiday: handles the functionality of managing guild holidays skipped by recurring reminders
+// - export_ics / import_ics: handles exporting reminders to and importing them from .ics calendars
+// - delivery_log: handles viewing the recent delivery attempts and failures of reminders
+// - autocomplete: handles suggesting reminders while typing the ID option of reminder commands
+// - buttons: handles the Done and Snooze buttons attached to delivered reminders
 // - look: handles the functionality of viewing the list of currently set reminders
 
 // 這個模塊文件定義了與提醒功能相關的命令結構
 // 每個子模塊包含特定功能的命令：
 // - remind: 處理設置新提醒的功能
+// - remind_once: 處理設置指定日期單次提醒的功能
+// - remindme: 處理設置以私訊發送的個人提醒的功能
 // - rm_remind: 處理移除現有提醒的功能
+// - edit_remind: 處理依 ID 修改現有提醒的功能
+// - pause_remind / resume_remind: 處理依 ID 暫停與恢復提醒的功能
+// - holiday: 處理管理伺服器假日的功能，週期提醒會在假日跳過
+// - export_ics / import_ics: 處理將提醒匯出為 .ics 行事曆與從行事曆匯入提醒的功能
+// - delivery_log: 處理查看提醒最近的發送紀錄與失敗原因的功能
+// - autocomplete: 處理輸入提醒指令的 ID 選項時建議提醒的功能
+// - buttons: 處理已發送提醒上的完成與延後按鈕
 // - look: 處理查看當前設置的提醒列表的功能
\ No newline at end of file
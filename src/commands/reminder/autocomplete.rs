@@ -0,0 +1,140 @@
+use std::{collections::HashMap, error::Error};
+
+use serenity::all::{
+    AutocompleteChoice, ChannelId, CommandInteraction, Context, CreateAutocompleteResponse,
+    CreateInteractionResponse,
+};
+
+use super::look::format_recurrence;
+use crate::{
+    modules::{func::is_reminder_manager, reminder::owns_reminder, template::truncate},
+    GuildSettingsMap, Recurrence, Reminder, Reminders,
+};
+
+// Discord 每次最多顯示的建議數量與建議名稱的長度上限
+const MAX_CHOICES: usize = 25;
+const MAX_NAME_CHARS: usize = 100;
+// 建議中顯示的訊息預覽長度
+const PREVIEW_CHARS: usize = 40;
+
+// 回應提醒 ID 選項的自動完成，依輸入的文字篩選提醒
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (Some(focused), Some(guild_id)) = (command.data.autocomplete(), command.guild_id) else {
+        return Ok(());
+    };
+    if focused.name != "id" {
+        return Ok(());
+    }
+    let query = focused.value.trim().to_lowercase();
+
+    // 已選擇頻道時只建議該頻道的提醒，自動完成時頻道選項尚未解析，只有 ID
+    let channel = command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == "channel")
+        .and_then(|opt| opt.value.as_channel_id());
+    // 一般成員只會看到自己設定的提醒
+    let member = {
+        let settings = guild_settings.read().await;
+        (!is_reminder_manager(command, &settings)).then_some(command.user.id)
+    };
+
+    // 從快取取得頻道與討論串名稱，找不到時顯示頻道 ID
+    let channel_names: HashMap<ChannelId, String> = match guild_id.to_guild_cached(&ctx.cache) {
+        Some(guild) => guild
+            .channels
+            .values()
+            .map(|channel| (channel.id, channel.name.clone()))
+            .chain(
+                guild
+                    .threads
+                    .iter()
+                    .map(|thread| (thread.id, thread.name.clone())),
+            )
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let mut choices = Vec::new();
+    {
+        let reminders = reminders.read().await;
+        let mut channels: Vec<_> = reminders
+            .get(&guild_id)
+            .map(|guild_reminder| guild_reminder.iter().collect())
+            .unwrap_or_default();
+        channels.sort_by_key(|(channel_id, _)| **channel_id);
+
+        for (channel_id, reminder_list) in channels {
+            if channel.is_some_and(|channel| channel != *channel_id) {
+                continue;
+            }
+            for reminder in reminder_list {
+                // 個人提醒只建議給設定的成員
+                let visible = match (member, reminder.dm_user) {
+                    (Some(user_id), _) => owns_reminder(reminder, user_id),
+                    (None, Some(dm_user)) => dm_user == command.user.id,
+                    (None, None) => true,
+                };
+                if !visible {
+                    continue;
+                }
+                let channel_name = match channel_names.get(channel_id) {
+                    Some(name) => format!("#{}", name),
+                    None => format!("#{}", channel_id),
+                };
+                let label = choice_label(&channel_name, reminder);
+                if query.is_empty()
+                    || reminder.id.contains(&query)
+                    || label.to_lowercase().contains(&query)
+                {
+                    choices.push(AutocompleteChoice::new(
+                        truncate(&label, MAX_NAME_CHARS),
+                        reminder.id.clone(),
+                    ));
+                }
+                if choices.len() == MAX_CHOICES {
+                    break;
+                }
+            }
+            if choices.len() == MAX_CHOICES {
+                break;
+            }
+        }
+    }
+
+    let data = CreateAutocompleteResponse::new().set_choices(choices);
+    command
+        .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(data))
+        .await?;
+    Ok(())
+}
+
+// 建議的顯示名稱，例如「#公告 · 週期: 週一、週三 08:30 · 開會」
+fn choice_label(channel_name: &str, reminder: &Reminder) -> String {
+    let channel = match reminder.dm_user {
+        Some(_) => "私訊",
+        None => channel_name,
+    };
+    let schedule = match &reminder.recurrence {
+        Recurrence::Cron { expr } => format!("Cron: {}", expr),
+        recurrence => format!(
+            "{} {}",
+            format_recurrence(recurrence),
+            reminder.time.format("%H:%M")
+        ),
+    };
+    let message = reminder.message.trim().lines().next().unwrap_or_default();
+    format!(
+        "{}{} · {} · {}",
+        channel,
+        if reminder.paused { " (已暫停)" } else { "" },
+        schedule,
+        truncate(message, PREVIEW_CHARS)
+    )
+}
@@ -0,0 +1,216 @@
+use std::{error::Error, sync::Arc};
+
+use chrono::{Duration, Timelike, Utc};
+use serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, Message,
+};
+use tokio::sync::Notify;
+
+use super::remindme::{personal_reminder_count, MAX_PERSONAL_REMINDERS};
+use crate::{
+    modules::{
+        func::save_reminders,
+        reminder::{find_reminder_mut, guild_timezone, new_reminder_id, DELAYED_MARKER},
+    },
+    Acknowledgement, GuildSettingsMap, Recurrence, Reminder, ReminderEmbed, ReminderMentions,
+    Reminders,
+};
+
+// 提醒按鈕 custom_id 的前綴
+pub const BUTTON_PREFIX: &str = "reminder";
+// 延後按鈕的分鐘數
+const SNOOZE_MINUTES: [i64; 2] = [10, 60];
+
+// 按鈕對應的操作
+enum ButtonAction {
+    Done,
+    Snooze(i64),
+}
+
+// 建立附加在已發送提醒上的「完成」與「延後」按鈕，
+// custom_id 帶有伺服器、頻道與提醒 ID，私訊中也能找到提醒
+pub fn reminder_buttons(guild_id: GuildId, channel_id: ChannelId, id: &str) -> CreateActionRow {
+    let target = format!("{}:{}:{}", guild_id, channel_id, id);
+    let mut buttons = vec![
+        CreateButton::new(format!("{}:done:{}", BUTTON_PREFIX, target))
+            .label("完成")
+            .emoji('✅')
+            .style(ButtonStyle::Success),
+    ];
+    for minutes in SNOOZE_MINUTES {
+        let label = match minutes {
+            m if m % 60 == 0 => format!("延後 {} 小時", m / 60),
+            m => format!("延後 {} 分鐘", m),
+        };
+        buttons.push(
+            CreateButton::new(format!("{}:snooze{}:{}", BUTTON_PREFIX, minutes, target))
+                .label(label)
+                .emoji('⏰')
+                .style(ButtonStyle::Secondary),
+        );
+    }
+    CreateActionRow::Buttons(buttons)
+}
+
+// 解析按鈕的 custom_id
+fn parse_button_id(custom_id: &str) -> Option<(ButtonAction, GuildId, ChannelId, String)> {
+    let mut parts = custom_id.split(':');
+    if parts.next()? != BUTTON_PREFIX {
+        return None;
+    }
+    let action = match parts.next()? {
+        "done" => ButtonAction::Done,
+        snooze => ButtonAction::Snooze(snooze.strip_prefix("snooze")?.parse().ok()?),
+    };
+    let guild_id = GuildId::new(parts.next()?.parse().ok()?);
+    let channel_id = ChannelId::new(parts.next()?.parse().ok()?);
+    let id = parts.next()?.to_string();
+    Some((action, guild_id, channel_id, id))
+}
+
+// 處理提醒上的按鈕
+pub async fn run(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+    notify: &Arc<Notify>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some((action, guild_id, channel_id, id)) = parse_button_id(&component.data.custom_id)
+    else {
+        return Ok(());
+    };
+    let user_id = component.user.id;
+    let now = Utc::now();
+    let tz = guild_timezone(&*guild_settings.read().await, &guild_id);
+
+    let note = match action {
+        // 記錄確認的成員，提醒已結束時只更新訊息
+        ButtonAction::Done => {
+            let mut reminders = reminders.write().await;
+            let mut updated = reminders.clone();
+            let acknowledged = updated
+                .get_mut(&guild_id)
+                .and_then(|guild_reminder| find_reminder_mut(guild_reminder, &id))
+                .map(|(_, reminder)| {
+                    reminder.acknowledged = Some(Acknowledgement {
+                        user: user_id,
+                        at: now,
+                    });
+                })
+                .is_some();
+            // 先保存修改後的副本，成功後才更新記憶體中的提醒
+            if acknowledged {
+                save_reminders(&updated)?;
+                *reminders = updated;
+            }
+            format!(">> ✅ 已由 <@{}> 完成", user_id)
+        }
+        // 在同一頻道（或私訊）建立單次提醒，重新發送這則訊息；
+        // 同一則訊息只保留一個延後的提醒，再次延後會取代先前的提醒
+        ButtonAction::Snooze(minutes) => {
+            let fire_at = now + Duration::minutes(minutes);
+            let local = fire_at.with_timezone(&tz);
+            let message_id = component.message.id;
+            {
+                let mut reminders = reminders.write().await;
+                let mut updated = reminders.clone();
+                for reminder_list in updated.values_mut().flat_map(|g| g.values_mut()) {
+                    reminder_list.retain(|r| r.snoozed_from != Some(message_id));
+                }
+
+                // 延後的提醒與個人提醒共用每位成員的數量上限
+                if personal_reminder_count(&updated, user_id) >= MAX_PERSONAL_REMINDERS {
+                    component
+                        .create_response(
+                            &ctx.http,
+                            CreateInteractionResponse::Message(
+                                CreateInteractionResponseMessage::new()
+                                    .content(format!(
+                                        ">> 你最多只能同時設定 {} 個個人提醒或延後的提醒",
+                                        MAX_PERSONAL_REMINDERS
+                                    ))
+                                    .ephemeral(true),
+                            ),
+                        )
+                        .await?;
+                    return Ok(());
+                }
+
+                let guild_reminder = updated.entry(guild_id).or_default();
+                let snoozed = Reminder {
+                    id: new_reminder_id(Some(guild_reminder)),
+                    creator: Some(user_id),
+                    created_at: Some(now),
+                    time: local.time().with_nanosecond(0).unwrap_or(local.time()),
+                    recurrence: Recurrence::Once {
+                        date: local.date_naive(),
+                    },
+                    next_due: Some(fire_at),
+                    dm_user: component.guild_id.is_none().then_some(user_id),
+                    snoozed_from: Some(message_id),
+                    ..snoozed_reminder(&component.message)
+                };
+                // 伺服器中的提醒在按下按鈕的頻道重新發送，討論串或論壇貼文中的提醒會留在原處
+                let target = match component.guild_id {
+                    Some(_) => component.channel_id,
+                    None => channel_id,
+                };
+                guild_reminder.entry(target).or_default().push(snoozed);
+                save_reminders(&updated)?;
+                *reminders = updated;
+            }
+            notify.notify_one();
+            format!(
+                ">> ⏰ 已由 <@{}> 延後至 {}",
+                user_id,
+                local.format("%Y-%m-%d %H:%M")
+            )
+        }
+    };
+
+    // 移除按鈕並在原訊息下方註記，避免重複操作
+    let content = match component.message.content.trim() {
+        "" => note,
+        content => format!("{}\n{}", content, note),
+    };
+    let data = CreateInteractionResponseMessage::new()
+        .content(content)
+        .components(Vec::new());
+    component
+        .create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(data))
+        .await?;
+    Ok(())
+}
+
+// 以已發送的訊息內容建立延後的提醒，佔位符已在第一次發送時替換
+fn snoozed_reminder(message: &Message) -> Reminder {
+    let content = message.content.trim();
+    let content = content
+        .strip_prefix(DELAYED_MARKER)
+        .map(str::trim_start)
+        .unwrap_or(content);
+
+    match message.embeds.first() {
+        // 嵌入訊息的內容在描述中，訊息本文只有提及
+        Some(embed) => Reminder {
+            message: embed.description.clone().unwrap_or_default(),
+            mentions: ReminderMentions {
+                roles: message.mention_roles.clone(),
+                users: message.mentions.iter().map(|user| user.id).collect(),
+                everyone: false,
+            },
+            embed: Some(ReminderEmbed {
+                title: embed.title.clone(),
+                color: embed.colour.map(|colour| colour.0),
+                image_url: embed.image.as_ref().map(|image| image.url.clone()),
+            }),
+            ..Default::default()
+        },
+        None => Reminder {
+            message: content.to_string(),
+            ..Default::default()
+        },
+    }
+}
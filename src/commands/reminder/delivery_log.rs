@@ -0,0 +1,126 @@
+use chrono_tz::Tz;
+use serenity::{
+    all::{CommandOptionType, GuildId, ResolvedOption, ResolvedValue},
+    builder::{CreateCommand, CreateCommandOption},
+};
+
+use crate::{modules::reminder::guild_timezone, DeliveryLog, DeliveryRecord, GuildSettingsMap};
+
+// 未指定時顯示的紀錄數量與可顯示的上限
+const DEFAULT_COUNT: usize = 10;
+const MAX_COUNT: usize = 50;
+// 回覆內容的長度上限，保留空間避免超過 Discord 的 2000 字元限制
+const MAX_REPLY_CHARS: usize = 1900;
+// 每筆紀錄顯示的錯誤訊息長度上限
+const MAX_ERROR_CHARS: usize = 120;
+
+// 註冊 delivery_log 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("delivery_log")
+        .description("查看伺服器最近的提醒發送紀錄")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "count",
+                format!("顯示的紀錄數量，預設 {} 筆", DEFAULT_COUNT),
+            )
+            .min_int_value(1)
+            .max_int_value(MAX_COUNT as u64),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "failures",
+            "只顯示發送失敗的紀錄",
+        ))
+}
+
+// 執行 delivery_log 命令的主函數，由新到舊列出紀錄
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    delivery_log: DeliveryLog,
+    guild_settings: GuildSettingsMap,
+    guild_id: GuildId,
+) -> String {
+    // 從選項中獲取 count 和 failures 的值
+    let count = options
+        .iter()
+        .find(|opt| opt.name == "count")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Integer(n) => usize::try_from(n).ok(),
+            _ => None,
+        })
+        .unwrap_or(DEFAULT_COUNT)
+        .clamp(1, MAX_COUNT);
+    let failures_only = options
+        .iter()
+        .find(|opt| opt.name == "failures")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Boolean(b) => Some(b),
+            _ => None,
+        })
+        .unwrap_or(false);
+
+    let tz = guild_timezone(&*guild_settings.read().await, &guild_id);
+    let log = delivery_log.read().await;
+    let records: Vec<&DeliveryRecord> = log
+        .get(&guild_id)
+        .map(|records| {
+            records
+                .iter()
+                .rev()
+                .filter(|record| !failures_only || record.error.is_some())
+                .take(count)
+                .collect()
+        })
+        .unwrap_or_default();
+    if records.is_empty() {
+        return if failures_only {
+            ">> 此伺服器沒有發送失敗的紀錄".to_string()
+        } else {
+            ">> 此伺服器沒有提醒發送紀錄".to_string()
+        };
+    }
+
+    let mut output = if failures_only {
+        format!(">> 最近 {} 筆發送失敗的紀錄：\n", records.len())
+    } else {
+        format!(">> 最近 {} 筆提醒發送紀錄：\n", records.len())
+    };
+    for (index, record) in records.iter().enumerate() {
+        let line = format_record(record, &tz);
+        if output.chars().count() + line.chars().count() > MAX_REPLY_CHARS {
+            output.push_str(&format!("...還有 {} 筆", records.len() - index));
+            break;
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+// 將一筆紀錄格式化為一行，時間以伺服器時區顯示
+fn format_record(record: &DeliveryRecord, tz: &Tz) -> String {
+    let target = if record.dm {
+        "私訊".to_string()
+    } else {
+        format!("<#{}>", record.channel_id)
+    };
+    let mut line = format!(
+        "`{}` {} [{}] {}",
+        record.at.with_timezone(tz).format("%m-%d %H:%M"),
+        if record.error.is_some() { "❌" } else { "✅" },
+        record.reminder_id,
+        target
+    );
+    if record.lead {
+        line.push_str(" (預告)");
+    }
+    if record.attempt > 1 {
+        line.push_str(&format!(" 第 {} 次", record.attempt));
+    }
+    if let Some(error) = &record.error {
+        let error: String = error.chars().take(MAX_ERROR_CHARS).collect();
+        line.push_str(&format!("：{}", error));
+    }
+    line
+}
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serenity::{
+    all::{CommandOptionType, GuildId, Http, ResolvedOption, ResolvedValue, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+};
+use tokio::sync::Notify;
+
+use super::remind::{event_duration_option, parse_event};
+use crate::{
+    modules::{
+        func::save_reminders,
+        reminder::{find_reminder_mut, guild_holidays, guild_timezone, next_fire, owns_reminder},
+        scheduled_event::delete_event,
+        time_parser::{parse_leads, parse_time, parse_weekdays},
+    },
+    GuildSettingsMap, Recurrence, ReminderEvent, Reminders,
+};
+
+// 註冊 edit_remind 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("edit_remind")
+        .description("修改指定的提醒")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "id",
+                "提醒的 ID：輸入頻道、時間或訊息搜尋，或參照\"/look\"顯示的 ID",
+            )
+            .required(true)
+            .set_autocomplete(true),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "weekdays",
+            "新的日期：以 \"1,4,7\"、\"mon-fri\" 或 \"每週三\" 格式表示，設定後改為每週提醒",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "date",
+            "新的日期：以 \"YYYY-MM-DD\" 格式表示，設定後改為單次提醒",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "time",
+            "新的時間：以 \"HH:MM\"、\"9pm\" 或 \"下午三點\" 格式表示",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "message",
+            "新的提醒訊息",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "before",
+            "新的觸發前預告：ex. \"30m 準備集合; 5m\"，輸入 \"none\" 取消預告",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "skip",
+            "要跳過的日期：以 \"YYYY-MM-DD\" 格式表示，多個日期以逗號分隔",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "unskip",
+            "取消跳過的日期：以 \"YYYY-MM-DD\" 格式表示，多個日期以逗號分隔",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "event",
+            "是否同時建立 Discord 活動，關閉時會刪除已建立的活動",
+        ))
+        .add_option(event_duration_option())
+}
+
+// 執行 edit_remind 命令的主函數，member 為一般成員時只能修改自己設定的提醒
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+    guild_id: GuildId,
+    member: Option<UserId>,
+    http: &Http,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    // 從選項中獲取各個參數的值
+    let get_option = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| match opt.value {
+                ResolvedValue::String(s) => Some(s.trim()),
+                _ => None,
+            })
+    };
+    let id = get_option("id").unwrap_or("");
+    let weekdays = get_option("weekdays");
+    let date = get_option("date");
+    let time = get_option("time");
+    let message = get_option("message");
+    let before = get_option("before");
+    let skip = get_option("skip");
+    let unskip = get_option("unskip");
+    let event_enabled = options
+        .iter()
+        .find(|opt| opt.name == "event")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Boolean(b) => Some(b),
+            _ => None,
+        });
+    let event = parse_event(options);
+
+    if weekdays.is_some() && date.is_some() {
+        return Ok(">> weekdays 與 date 只能擇一設定".to_string());
+    }
+    if weekdays.is_none()
+        && date.is_none()
+        && time.is_none()
+        && message.is_none()
+        && skip.is_none()
+        && unskip.is_none()
+        && before.is_none()
+        && event_enabled.is_none()
+        && event.is_none()
+    {
+        return Ok(">> 請至少提供一個要修改的項目".to_string());
+    }
+
+    // 解析新的星期幾、日期與時間
+    let weekdays = match weekdays.map(parse_weekdays).transpose() {
+        Ok(days) => days,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    let date = match date
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+        .transpose()
+    {
+        Ok(d) => d,
+        Err(_) => return Ok(">> 錯誤的日期格式：ex. 2026-11-03".to_string()),
+    };
+    let time = match time.map(parse_time).transpose() {
+        Ok(t) => t,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    let skip = match skip.map(parse_dates).transpose() {
+        Ok(d) => d.unwrap_or_default(),
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    let unskip = match unskip.map(parse_dates).transpose() {
+        Ok(d) => d.unwrap_or_default(),
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    let leads = match before {
+        Some(b) if b.eq_ignore_ascii_case("none") || b == "無" => Some(Vec::new()),
+        Some(b) => match parse_leads(b) {
+            Ok(leads) => Some(leads),
+            Err(err) => return Ok(format!(">> {}", err)),
+        },
+        None => None,
+    };
+
+    let (tz, holidays) = {
+        let settings = guild_settings.read().await;
+        (
+            guild_timezone(&settings, &guild_id),
+            guild_holidays(&settings, &guild_id).clone(),
+        )
+    };
+    let (reminder_id, removed_event) = {
+        let mut reminders_lock = reminders.write().await;
+        let mut updated = reminders_lock.clone();
+        let Some(guild_reminder) = updated.get_mut(&guild_id) else {
+            return Ok(">> 該公會沒有設置任何提醒".to_string());
+        };
+        let Some((_, reminder)) = find_reminder_mut(guild_reminder, id) else {
+            return Ok(format!(">> 找不到 ID 為 '{}' 的提醒", id));
+        };
+        if member.is_some_and(|user_id| !owns_reminder(reminder, user_id)) {
+            return Ok(">> 你只能管理自己設定的提醒".to_string());
+        }
+
+        // 在副本上套用修改，確認有效後再寫回
+        let mut edited = reminder.clone();
+        if let Some(weekdays) = weekdays {
+            edited.recurrence = Recurrence::Weekly {
+                weekdays,
+                interval: 1,
+                anchor: None,
+            };
+        }
+        if let Some(date) = date {
+            edited.recurrence = Recurrence::Once { date };
+            edited.until = None;
+            edited.remaining = None;
+        }
+        if let Some(time) = time {
+            edited.time = time;
+        }
+        if let Some(message) = message {
+            edited.message = message.to_string();
+        }
+        if let Some(leads) = leads {
+            // 論壇無法直接發送預告訊息
+            if edited.thread.as_ref().is_some_and(|thread| thread.forum) && !leads.is_empty() {
+                return Ok(">> 論壇頻道的提醒無法設定觸發前預告".to_string());
+            }
+            edited.leads = leads;
+        }
+        // 關閉活動時移除設定，已建立的活動在釋放鎖之後刪除；
+        // 只修改長度時保留已建立的活動，由排程器更新
+        let mut removed_event = None;
+        match (event_enabled, event) {
+            (Some(false), _) => removed_event = edited.event.take().and_then(|e| e.id),
+            (_, Some(event)) => {
+                if edited.dm_user.is_some() {
+                    return Ok(">> 個人提醒無法建立 Discord 活動".to_string());
+                }
+                let existing = edited.event.take();
+                edited.event = Some(match existing {
+                    Some(existing) => ReminderEvent {
+                        duration: event.duration,
+                        ..existing
+                    },
+                    None => event,
+                });
+            }
+            _ => {}
+        }
+        edited.skip_dates.extend(skip);
+        for date in &unskip {
+            edited.skip_dates.remove(date);
+        }
+
+        // 不允許修改為不會再觸發的提醒
+        if next_fire(&edited, &tz, &holidays, Utc::now()).is_none() {
+            return Ok(">> 提醒時間已經過去，請設定未來的時間".to_string());
+        }
+
+        // 修改後需要重新排程
+        edited.next_due = None;
+        *reminder = edited;
+        let reminder_id = reminder.id.clone();
+
+        // 先保存修改後的副本，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+        save_reminders(&updated)?;
+        *reminders_lock = updated;
+        (reminder_id, removed_event)
+    };
+    if let Some(event_id) = removed_event {
+        delete_event(http, guild_id, event_id).await;
+    }
+
+    // 通知提醒處理器重新排程
+    notify.notify_one();
+
+    Ok(format!(">> 提醒 '{}' 已更新", reminder_id))
+}
+
+// 解析以逗號分隔的日期列表
+fn parse_dates(input: &str) -> Result<Vec<NaiveDate>, String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map_err(|_| format!("錯誤的日期格式：'{}'，ex. 2026-11-03", d))
+        })
+        .collect()
+}
@@ -0,0 +1,247 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serenity::all::{
+    ChannelId, CommandInteraction, Context, CreateAttachment, CreateCommand,
+    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId,
+};
+
+use crate::{
+    modules::{
+        ics::{escape, format_utc, weekday_code, CalendarWriter},
+        reminder::{
+            guild_holidays, guild_timezone, local_to_utc, next_fire, occurs_on, upcoming_fires,
+        },
+        template::format_lead_time,
+    },
+    GuildSettingsMap, Recurrence, Reminder, Reminders,
+};
+
+// 結束日期當天的最後一秒，作為 UNTIL 的時間
+const LAST_SECOND: NaiveTime = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+// 回覆中最多列出的略過提醒數量，避免超過訊息長度限制
+const MAX_SKIPPED_LINES: usize = 20;
+
+// 註冊 export_ics 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("export_ics").description("將伺服器的提醒匯出為 .ics 行事曆文件")
+}
+
+// 執行 export_ics 命令，以附件回覆行事曆文件
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = command.guild_id.unwrap();
+    let (tz, holidays) = {
+        let settings = guild_settings.read().await;
+        (
+            guild_timezone(&settings, &guild_id),
+            guild_holidays(&settings, &guild_id).clone(),
+        )
+    };
+    let now = Utc::now();
+    let name = guild_id
+        .name(&ctx.cache)
+        .map(|name| format!("{} 提醒", name))
+        .unwrap_or_else(|| "Discord 提醒".to_string());
+
+    let mut writer = CalendarWriter::new(&name, tz.name());
+    let mut exported = 0;
+    let mut skipped = Vec::new();
+    {
+        let reminders = reminders.read().await;
+        let mut channels: Vec<_> = reminders
+            .get(&guild_id)
+            .map(|guild_reminder| guild_reminder.iter().collect())
+            .unwrap_or_default();
+        channels.sort_by_key(|(channel_id, _)| **channel_id);
+
+        // 個人提醒只屬於設定的成員，不會匯出
+        for (channel_id, channel_reminders) in channels {
+            for reminder in channel_reminders.iter().filter(|r| r.dm_user.is_none()) {
+                match event_lines(reminder, guild_id, *channel_id, &tz, &holidays, now) {
+                    Ok(lines) => {
+                        for line in lines {
+                            writer.line(&line);
+                        }
+                        exported += 1;
+                    }
+                    Err(reason) => skipped.push(format!("- {}：{}", reminder.id, reason)),
+                }
+            }
+        }
+    }
+
+    if exported == 0 && skipped.is_empty() {
+        let data = CreateInteractionResponseMessage::new()
+            .content(">> 此伺服器沒有可匯出的提醒")
+            .ephemeral(true);
+        command
+            .create_response(&ctx.http, CreateInteractionResponse::Message(data))
+            .await?;
+        return Ok(());
+    }
+
+    let mut content = format!(">> 已匯出 {} 個提醒", exported);
+    if !skipped.is_empty() {
+        content.push_str(&format!("，略過 {} 個：\n", skipped.len()));
+        content.push_str(&skipped[..skipped.len().min(MAX_SKIPPED_LINES)].join("\n"));
+        if skipped.len() > MAX_SKIPPED_LINES {
+            content.push_str(&format!(
+                "\n...還有 {} 個",
+                skipped.len() - MAX_SKIPPED_LINES
+            ));
+        }
+    }
+    let mut data = CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true);
+    if exported > 0 {
+        data = data.add_file(CreateAttachment::bytes(
+            writer.finish().into_bytes(),
+            format!("reminders-{}.ics", guild_id),
+        ));
+    }
+    command
+        .create_response(&ctx.http, CreateInteractionResponse::Message(data))
+        .await?;
+    Ok(())
+}
+
+// 將一個提醒轉換為 VEVENT 的內容行，無法轉換時返回原因
+pub fn event_lines(
+    reminder: &Reminder,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, String> {
+    if reminder.paused {
+        return Err("已暫停".to_string());
+    }
+    let rule = recurrence_rule(reminder, tz, holidays, now)?;
+
+    // 以下一次觸發的時間作為事件開始，週期規則從這一次開始展開
+    let start = reminder
+        .next_due
+        .or_else(|| next_fire(reminder, tz, holidays, now))
+        .ok_or_else(|| "不會再觸發".to_string())?
+        .with_timezone(tz);
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@discord-bot-rs", reminder.id, guild_id),
+        format!("DTSTAMP:{}", format_utc(now)),
+        format!(
+            "DTSTART;TZID={}:{}",
+            tz.name(),
+            start.format("%Y%m%dT%H%M%S")
+        ),
+        format!("SUMMARY:{}", escape(&reminder.message)),
+        format!(
+            "URL:https://discord.com/channels/{}/{}",
+            guild_id, channel_id
+        ),
+    ];
+
+    if let Some(rule) = rule {
+        lines.push(format!("RRULE:{}", rule));
+        // 跳過的日期與伺服器假日以 EXDATE 排除
+        let last = reminder.until.unwrap_or(NaiveDate::MAX);
+        let start_date = start.date_naive();
+        let excluded: BTreeSet<NaiveDate> = reminder
+            .skip_dates
+            .iter()
+            .chain(holidays.keys())
+            .filter(|date| **date > start_date && **date <= last)
+            .filter(|date| occurs_on(&reminder.recurrence, **date))
+            .copied()
+            .collect();
+        for date in excluded {
+            lines.push(format!(
+                "EXDATE;TZID={}:{}",
+                tz.name(),
+                date.and_time(reminder.time).format("%Y%m%dT%H%M%S")
+            ));
+        }
+    }
+
+    for lead in &reminder.leads {
+        let description = match lead.message.as_str() {
+            "" => format!("{}後：{}", format_lead_time(lead.minutes), reminder.message),
+            message => message.to_string(),
+        };
+        lines.extend([
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", escape(&description)),
+            format!("TRIGGER:-PT{}M", lead.minutes),
+            "END:VALARM".to_string(),
+        ]);
+    }
+
+    lines.push("END:VEVENT".to_string());
+    Ok(lines)
+}
+
+// 將提醒的觸發規則轉換為 RRULE，單次提醒返回 None
+fn recurrence_rule(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, String> {
+    let mut rule = match &reminder.recurrence {
+        Recurrence::Once { .. } => return Ok(None),
+        Recurrence::Cron { .. } => return Err("cron 提醒無法轉換為 RRULE".to_string()),
+        Recurrence::Weekly {
+            weekdays, interval, ..
+        } => {
+            if weekdays.is_empty() {
+                return Err("沒有設定星期".to_string());
+            }
+            let mut weekdays = weekdays.clone();
+            weekdays.sort_by_key(|day| day.num_days_from_monday());
+            let days: Vec<_> = weekdays.iter().map(|day| weekday_code(*day)).collect();
+            format!(
+                "FREQ=WEEKLY;INTERVAL={};WKST=MO;BYDAY={}",
+                interval,
+                days.join(",")
+            )
+        }
+        Recurrence::Daily { interval, .. } => format!("FREQ=DAILY;INTERVAL={}", interval),
+        // 沒有該日的月份使用當月最後一天，以 BYSETPOS=-1 選出當月存在的最後一天
+        Recurrence::MonthlyDay { day } if *day <= 28 => {
+            format!("FREQ=MONTHLY;BYMONTHDAY={}", day)
+        }
+        Recurrence::MonthlyDay { day } => {
+            let days: Vec<_> = (28..=*day).map(|d| d.to_string()).collect();
+            format!("FREQ=MONTHLY;BYMONTHDAY={};BYSETPOS=-1", days.join(","))
+        }
+        Recurrence::MonthlyWeekday { nth, weekday } => {
+            format!("FREQ=MONTHLY;BYDAY={}{}", nth, weekday_code(*weekday))
+        }
+    };
+
+    // 有次數限制時以最後一次觸發的時間作為 UNTIL，跳過的日期不會佔用次數
+    let until = match reminder.remaining {
+        Some(remaining) => upcoming_fires(reminder, tz, holidays, now, remaining as usize)
+            .last()
+            .copied(),
+        None => reminder
+            .until
+            .map(|until| local_to_utc(tz, until, LAST_SECOND)),
+    };
+    if let Some(until) = until {
+        rule.push_str(&format!(";UNTIL={}", format_utc(until)));
+    }
+    Ok(Some(rule))
+}
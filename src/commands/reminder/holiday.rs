@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, error::Error, sync::Arc};
+
+use chrono::{NaiveDate, Utc};
+use serenity::{
+    all::{Attachment, GuildId, ResolvedValue},
+    builder::{CreateCommand, CreateCommandOption},
+    model::application::{CommandOptionType, ResolvedOption},
+};
+use tokio::sync::Notify;
+
+use crate::{
+    modules::{
+        func::{download_text, save_guild_settings, save_reminders},
+        ics,
+        reminder::{guild_holidays, guild_timezone},
+    },
+    GuildSettingsMap, Recurrence, Reminders,
+};
+
+// 匯入文件的大小上限
+const MAX_IMPORT_BYTES: u32 = 256 * 1024;
+// 列表最多顯示的假日數量，避免超過訊息長度限制
+const MAX_LIST_ENTRIES: usize = 40;
+
+// 註冊 holiday 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("holiday")
+        .description("管理伺服器的假日，週期提醒會在假日跳過")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "action", "要做的操作")
+                .add_string_choice("list", "list")
+                .add_string_choice("add", "add")
+                .add_string_choice("remove", "remove")
+                .add_string_choice("import", "import")
+                .required(true),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "date",
+            "日期：以 \"YYYY-MM-DD\" 格式表示",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "name", "假日名稱").max_length(100),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Attachment,
+            "file",
+            "匯入的文件：每行一個日期 (可接名稱) 的文字檔，或 .ics 行事曆",
+        ))
+}
+
+// 執行 holiday 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    guild_settings: GuildSettingsMap,
+    reminders: Reminders,
+    guild_id: GuildId,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    // 從選項中獲取各個參數的值
+    let get_option = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| match opt.value {
+                ResolvedValue::String(s) => Some(s.trim()),
+                _ => None,
+            })
+    };
+    let action = get_option("action").unwrap_or("list");
+    let date = get_option("date");
+    let name = get_option("name").unwrap_or("");
+    let file = options
+        .iter()
+        .find(|opt| opt.name == "file")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Attachment(attachment) => Some(attachment),
+            _ => None,
+        });
+
+    let date = match date
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+        .transpose()
+    {
+        Ok(d) => d,
+        Err(_) => return Ok(">> 錯誤的日期格式：ex. 2026-12-25".to_string()),
+    };
+
+    let msg = match action {
+        "list" => {
+            let settings = guild_settings.read().await;
+            let today = Utc::now()
+                .with_timezone(&guild_timezone(&settings, &guild_id))
+                .date_naive();
+            return Ok(format_holidays(guild_holidays(&settings, &guild_id), today));
+        }
+        "add" => {
+            let Some(date) = date else {
+                return Ok(">> 請提供要新增的日期".to_string());
+            };
+            let mut settings = guild_settings.write().await;
+            let mut updated = settings.clone();
+            let holidays = &mut updated.entry(guild_id).or_default().holidays;
+            holidays.insert(date, name.to_string());
+            save_guild_settings(&updated)?;
+            *settings = updated;
+            format!(">> 已新增假日 {} {}", date, name)
+        }
+        "remove" => {
+            let Some(date) = date else {
+                return Ok(">> 請提供要移除的日期".to_string());
+            };
+            let mut settings = guild_settings.write().await;
+            let mut updated = settings.clone();
+            let holidays = &mut updated.entry(guild_id).or_default().holidays;
+            if holidays.remove(&date).is_none() {
+                return Ok(format!(">> {} 不是已設定的假日", date));
+            }
+            save_guild_settings(&updated)?;
+            *settings = updated;
+            format!(">> 已移除假日 {}", date)
+        }
+        "import" => {
+            let Some(file) = file else {
+                return Ok(">> 請附上要匯入的文件".to_string());
+            };
+            let (dates, skipped) = match read_import_file(file).await {
+                Ok(result) => result,
+                Err(err) => return Ok(format!(">> 匯入失敗：{}", err)),
+            };
+            if dates.is_empty() {
+                return Ok(">> 文件中沒有可匯入的日期".to_string());
+            }
+            let count = dates.len();
+            let mut settings = guild_settings.write().await;
+            let mut updated = settings.clone();
+            updated.entry(guild_id).or_default().holidays.extend(dates);
+            save_guild_settings(&updated)?;
+            *settings = updated;
+            if skipped > 0 {
+                format!(">> 已匯入 {} 個假日，略過 {} 個非全天事件", count, skipped)
+            } else {
+                format!(">> 已匯入 {} 個假日", count)
+            }
+        }
+        _ => return Ok(">> 未知的操作".to_string()),
+    };
+
+    // 假日變更後重新排程伺服器內的週期提醒；
+    // 設定與提醒都先保存修改後的副本，成功後才更新記憶體中的資料
+    {
+        let mut reminders = reminders.write().await;
+        let mut updated = reminders.clone();
+        if let Some(guild_reminder) = updated.get_mut(&guild_id) {
+            for reminder in guild_reminder
+                .values_mut()
+                .flatten()
+                .filter(|r| r.dm_user.is_none() && !matches!(r.recurrence, Recurrence::Once { .. }))
+            {
+                reminder.next_due = None;
+            }
+            save_reminders(&updated)?;
+            *reminders = updated;
+        }
+    }
+    notify.notify_one();
+
+    Ok(msg)
+}
+
+// 格式化今天以後的假日列表
+fn format_holidays(holidays: &BTreeMap<NaiveDate, String>, today: NaiveDate) -> String {
+    let upcoming: Vec<_> = holidays.range(today..).collect();
+    if upcoming.is_empty() {
+        return ">> 尚未設定之後的假日".to_string();
+    }
+
+    let mut output = String::from(">> 之後的假日：\n");
+    for (date, name) in upcoming.iter().take(MAX_LIST_ENTRIES) {
+        output.push_str(&format!("{} {}\n", date, name));
+    }
+    if upcoming.len() > MAX_LIST_ENTRIES {
+        output.push_str(&format!(
+            "...還有 {} 個\n",
+            upcoming.len() - MAX_LIST_ENTRIES
+        ));
+    }
+    output
+}
+
+// 下載並解析匯入的文件，返回日期與略過的事件數量
+async fn read_import_file(file: &Attachment) -> Result<(Vec<(NaiveDate, String)>, usize), String> {
+    let content = download_text(file, MAX_IMPORT_BYTES).await?;
+
+    if file.filename.to_lowercase().ends_with(".ics") || ics::is_calendar(&content) {
+        let events = ics::parse_events(&content)?;
+        ics::all_day_dates(&events)
+    } else {
+        parse_date_lines(&content).map(|dates| (dates, 0))
+    }
+}
+
+// 解析每行一個日期的文字，日期後可接名稱，空行與 # 開頭的行會被忽略；
+// 任一行錯誤時整份文件都不會匯入
+fn parse_date_lines(content: &str) -> Result<Vec<(NaiveDate, String)>, String> {
+    let mut dates = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (date, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("第 {} 行的日期格式錯誤：'{}'", index + 1, date))?;
+        dates.push((date, name.trim().to_string()));
+    }
+    Ok(dates)
+}
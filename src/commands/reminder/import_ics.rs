@@ -0,0 +1,606 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    sync::Arc,
+};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use serenity::{
+    all::{GuildId, ResolvedValue, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+    model::{
+        application::{CommandOptionType, ResolvedOption},
+        id::ChannelId,
+    },
+};
+use tokio::sync::Notify;
+
+use crate::{
+    modules::{
+        func::{download_text, save_reminders},
+        ics::{self, Event, Property},
+        reminder::{guild_holidays, guild_timezone, new_reminder_id, next_fire},
+    },
+    GuildSettingsMap, LeadAlert, Recurrence, Reminder, Reminders,
+};
+
+// 匯入文件的大小上限
+const MAX_IMPORT_BYTES: u32 = 512 * 1024;
+// 一次最多匯入的事件數量
+const MAX_IMPORT_EVENTS: usize = 100;
+// 回覆中最多列出的略過事件數量，避免超過訊息長度限制
+const MAX_SKIPPED_LINES: usize = 20;
+// 與 before 選項相同的預告限制
+const MAX_LEADS: usize = 5;
+const MAX_LEAD_MINUTES: i64 = 7 * 1440;
+// 可以轉換的 RRULE 部分，其他部分 (例如 BYMONTH、BYHOUR) 無法以提醒表示
+const SUPPORTED_RULE_PARTS: [&str; 8] = [
+    "FREQ",
+    "INTERVAL",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYSETPOS",
+    "UNTIL",
+    "COUNT",
+    "WKST",
+];
+
+// 註冊 import_ics 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("import_ics")
+        .description("從 .ics 行事曆匯入提醒到此頻道")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Attachment, "file", ".ics 行事曆文件")
+                .required(true),
+        )
+}
+
+// 執行 import_ics 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+    channel_id: ChannelId,
+    guild_id: GuildId,
+    user_id: UserId,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let Some(file) = options
+        .iter()
+        .find(|opt| opt.name == "file")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Attachment(attachment) => Some(attachment),
+            _ => None,
+        })
+    else {
+        return Ok(">> 請附上要匯入的 .ics 文件".to_string());
+    };
+
+    let content = match download_text(file, MAX_IMPORT_BYTES).await {
+        Ok(content) => content,
+        Err(err) => return Ok(format!(">> 匯入失敗：{}", err)),
+    };
+    if !ics::is_calendar(&content) {
+        return Ok(">> 文件不是 iCalendar (.ics) 格式".to_string());
+    }
+    let events = match ics::parse_events(&content) {
+        Ok(events) => events,
+        Err(err) => return Ok(format!(">> 匯入失敗：{}", err)),
+    };
+    if events.is_empty() {
+        return Ok(">> 文件中沒有任何事件".to_string());
+    }
+    if events.len() > MAX_IMPORT_EVENTS {
+        return Ok(format!(
+            ">> 文件中有 {} 個事件，一次最多匯入 {} 個",
+            events.len(),
+            MAX_IMPORT_EVENTS
+        ));
+    }
+
+    let (tz, holidays) = {
+        let settings = guild_settings.read().await;
+        (
+            guild_timezone(&settings, &guild_id),
+            guild_holidays(&settings, &guild_id).clone(),
+        )
+    };
+    let now = Utc::now();
+
+    // 轉換事件，無法轉換的事件記錄名稱與原因
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for event in &events {
+        let name = event.summary().unwrap_or_else(|| "(無標題)".to_string());
+        match event_to_reminder(event, &tz, &holidays, now) {
+            Ok(reminder) => imported.push((exported_id(event, guild_id), reminder)),
+            Err(reason) => skipped.push(format!("- {}：{}", name, reason)),
+        }
+    }
+
+    // 添加新的提醒，略過從此伺服器匯出且仍然存在的提醒
+    let mut count = 0;
+    if !imported.is_empty() {
+        let mut reminders = reminders.write().await;
+        let mut updated = reminders.clone();
+        let guild_reminder = updated.entry(guild_id).or_insert_with(HashMap::new);
+        let existing: HashSet<String> = guild_reminder
+            .values()
+            .flatten()
+            .map(|r| r.id.clone())
+            .collect();
+        for (exported_id, reminder) in imported {
+            if let Some(id) = exported_id.filter(|id| existing.contains(id)) {
+                skipped.push(format!("- {}：提醒 {} 已存在", reminder.message, id));
+                continue;
+            }
+            let id = new_reminder_id(Some(guild_reminder));
+            guild_reminder
+                .entry(channel_id)
+                .or_insert_with(Vec::new)
+                .push(Reminder {
+                    id,
+                    creator: Some(user_id),
+                    created_at: Some(now),
+                    ..reminder
+                });
+            count += 1;
+        }
+        if count > 0 {
+            // 先保存加入新提醒的副本，成功後才更新記憶體中的提醒
+            save_reminders(&updated)?;
+            *reminders = updated;
+        }
+    }
+    if count > 0 {
+        // 通知提醒處理器有新的提醒
+        notify.notify_one();
+    }
+
+    let mut msg = format!(">> 已匯入 {} 個提醒", count);
+    if !skipped.is_empty() {
+        msg.push_str(&format!("，略過 {} 個事件：\n", skipped.len()));
+        msg.push_str(&skipped[..skipped.len().min(MAX_SKIPPED_LINES)].join("\n"));
+        if skipped.len() > MAX_SKIPPED_LINES {
+            msg.push_str(&format!(
+                "\n...還有 {} 個",
+                skipped.len() - MAX_SKIPPED_LINES
+            ));
+        }
+    }
+    Ok(msg)
+}
+
+// 從 /export_ics 產生的 UID 取得原本的提醒 ID
+fn exported_id(event: &Event, guild_id: GuildId) -> Option<String> {
+    let uid = event.get("UID")?.value.trim();
+    let id = uid.strip_suffix(&format!("-{}@discord-bot-rs", guild_id))?;
+    Some(id.to_string())
+}
+
+// 將一個 VEVENT 轉換為提醒，無法轉換時返回原因
+fn event_to_reminder(
+    event: &Event,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    now: DateTime<Utc>,
+) -> Result<Reminder, String> {
+    let message = event.summary().unwrap_or_default();
+    if message.trim().is_empty() {
+        return Err("沒有標題 (SUMMARY)".to_string());
+    }
+    if event.get("RECURRENCE-ID").is_some() {
+        return Err("不支援單獨修改過的重複事件".to_string());
+    }
+    if event.get("RDATE").is_some() || event.get_all("RRULE").count() > 1 {
+        return Err("不支援 RDATE 或多個 RRULE".to_string());
+    }
+
+    let start = event
+        .get("DTSTART")
+        .ok_or_else(|| "沒有開始時間 (DTSTART)".to_string())?;
+    if ics::parse_date(start).is_some() {
+        return Err("全天事件沒有提醒時間，可使用 /holiday 匯入假日".to_string());
+    }
+    let start = ics::parse_date_time(start, tz)
+        .ok_or_else(|| format!("無法解析開始時間 '{}'", start.value))?;
+    let time = start.time().with_nanosecond(0).unwrap_or(start.time());
+    let today = now.with_timezone(tz).date_naive();
+
+    let mut reminder = Reminder {
+        message: message.trim().to_string(),
+        time,
+        recurrence: Recurrence::Once {
+            date: start.date_naive(),
+        },
+        leads: parse_triggers(event),
+        ..Default::default()
+    };
+
+    let Some(rule) = event.get("RRULE") else {
+        return next_fire(&reminder, tz, holidays, now)
+            .map(|_| reminder)
+            .ok_or_else(|| "時間已經過去".to_string());
+    };
+    let parts = ics::rule_parts(&rule.value);
+    reminder.recurrence = parse_rule(&parts, start)?;
+    if let Recurrence::Cron { .. } = reminder.recurrence {
+        reminder.time = NaiveTime::MIN;
+    }
+    reminder.until = match parts.get("UNTIL") {
+        Some(until) => Some(parse_until(until, tz)?),
+        None => None,
+    };
+    reminder.skip_dates = event
+        .get_all("EXDATE")
+        .flat_map(|prop| exception_dates(prop, tz))
+        .filter(|date| *date >= today)
+        .collect();
+
+    // 尚未開始的週期事件等到開始時間才觸發
+    let first = start.with_timezone(&Utc) - Duration::seconds(1);
+    if start > now {
+        reminder.next_due = next_fire(&reminder, tz, holidays, first);
+    }
+
+    // COUNT 包含已經過去的次數，只保留剩餘的次數
+    if let Some(count) = parts.get("COUNT") {
+        let count: u32 = count
+            .parse()
+            .map_err(|_| format!("無效的 COUNT '{}'", count))?;
+        let no_holidays = BTreeMap::new();
+        let mut past = 0;
+        let mut after = first;
+        while past < count {
+            match next_fire(&reminder, tz, &no_holidays, after) {
+                Some(fire_at) if fire_at <= now => {
+                    past += 1;
+                    after = fire_at;
+                }
+                _ => break,
+            }
+        }
+        reminder.remaining = Some(count - past);
+    }
+
+    reminder
+        .next_due
+        .or_else(|| next_fire(&reminder, tz, holidays, now))
+        .map(|_| reminder)
+        .ok_or_else(|| "重複規則已經結束".to_string())
+}
+
+// 將 RRULE 轉換為提醒的觸發規則
+fn parse_rule(parts: &HashMap<String, String>, start: DateTime<Tz>) -> Result<Recurrence, String> {
+    if let Some(part) = parts
+        .keys()
+        .find(|key| !SUPPORTED_RULE_PARTS.contains(&key.as_str()))
+    {
+        return Err(format!("不支援 RRULE 的 {}", part));
+    }
+    let interval = match parts.get("INTERVAL") {
+        Some(interval) => interval
+            .parse::<u32>()
+            .ok()
+            .filter(|interval| *interval >= 1)
+            .ok_or_else(|| format!("無效的 INTERVAL '{}'", interval))?,
+        None => 1,
+    };
+    let by_day = parts.get("BYDAY").map(String::as_str);
+    let by_month_day = parts.get("BYMONTHDAY").map(String::as_str);
+    let by_set_pos = parts.get("BYSETPOS").map(String::as_str);
+    let freq = parts.get("FREQ").map(String::as_str).unwrap_or("");
+    if freq != "MONTHLY" && (by_month_day.is_some() || by_set_pos.is_some()) {
+        return Err(format!("不支援 {} 搭配 BYMONTHDAY 或 BYSETPOS", freq));
+    }
+
+    let date = start.date_naive();
+    match freq {
+        "WEEKLY" => Ok(Recurrence::Weekly {
+            weekdays: match by_day {
+                Some(days) => parse_weekdays(days)?,
+                None => vec![start.weekday()],
+            },
+            interval,
+            anchor: (interval > 1).then_some(date),
+        }),
+        "DAILY" => match by_day {
+            Some(days) if interval == 1 => Ok(Recurrence::Weekly {
+                weekdays: parse_weekdays(days)?,
+                interval: 1,
+                anchor: None,
+            }),
+            Some(_) => Err("不支援間隔多天並指定星期的重複".to_string()),
+            None => Ok(Recurrence::Daily {
+                interval,
+                anchor: date,
+            }),
+        },
+        "MONTHLY" if interval == 1 => parse_monthly(by_day, by_month_day, by_set_pos, date),
+        "MONTHLY" => Err("不支援每隔多個月的重複".to_string()),
+        // 每年重複以 cron 表示
+        "YEARLY" if interval == 1 && by_day.is_none() => Ok(Recurrence::Cron {
+            expr: format!(
+                "{} {} {} {} *",
+                start.minute(),
+                start.hour(),
+                start.day(),
+                start.month()
+            ),
+        }),
+        "YEARLY" => Err("不支援每隔多年或指定星期的每年重複".to_string()),
+        "" => Err("RRULE 沒有 FREQ".to_string()),
+        freq => Err(format!("不支援 FREQ={}", freq)),
+    }
+}
+
+// 將每月重複的 RRULE 轉換為每月指定日或每月第 n 個星期
+fn parse_monthly(
+    by_day: Option<&str>,
+    by_month_day: Option<&str>,
+    by_set_pos: Option<&str>,
+    date: NaiveDate,
+) -> Result<Recurrence, String> {
+    match (by_day, by_month_day, by_set_pos) {
+        // 「BYDAY=2TU」或「BYDAY=TU;BYSETPOS=2」：每月第 n 個星期
+        (Some(day), None, set_pos) => {
+            let (nth, code) = match set_pos {
+                Some(pos) => (pos, day),
+                None => day.split_at(day.len().saturating_sub(2)),
+            };
+            let nth = nth
+                .trim_start_matches('+')
+                .parse::<i8>()
+                .ok()
+                .filter(|nth| (1..=5).contains(&nth.unsigned_abs()))
+                .ok_or_else(|| format!("不支援 BYDAY={}", day))?;
+            let weekday =
+                ics::parse_weekday_code(code).ok_or_else(|| format!("不支援 BYDAY={}", day))?;
+            Ok(Recurrence::MonthlyWeekday { nth, weekday })
+        }
+        // 「BYMONTHDAY=28,29,30;BYSETPOS=-1」：當月存在的最後一天，即 /export_ics 的格式
+        (None, Some(days), Some("-1")) => {
+            let values = days
+                .split(',')
+                .map(|day| day.parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("無效的 BYMONTHDAY '{}'", days))?;
+            match values.iter().max() {
+                Some(max) if values.iter().all(|day| (28..=31).contains(day)) => {
+                    Ok(Recurrence::MonthlyDay { day: *max })
+                }
+                _ => Err(format!("不支援 BYMONTHDAY={} 搭配 BYSETPOS", days)),
+            }
+        }
+        (None, Some(day), None) => match day.parse::<i32>() {
+            Ok(-1) => Ok(Recurrence::MonthlyDay { day: 31 }),
+            Ok(day) => month_day(day as u32),
+            Err(_) => Err(format!("不支援 BYMONTHDAY={}", day)),
+        },
+        (None, None, None) => month_day(date.day()),
+        _ => Err("不支援此每月重複的組合".to_string()),
+    }
+}
+
+// 每月指定日，29 號以後在較短的月份不會觸發，與提醒改用最後一天的行為不同
+fn month_day(day: u32) -> Result<Recurrence, String> {
+    match day {
+        1..=28 => Ok(Recurrence::MonthlyDay { day }),
+        _ => Err(format!("每月 {} 號在較短的月份會略過，無法轉換", day)),
+    }
+}
+
+// 解析 BYDAY 的星期列表，例如「MO,WE,FR」
+fn parse_weekdays(days: &str) -> Result<Vec<Weekday>, String> {
+    let mut weekdays = Vec::new();
+    for code in days.split(',') {
+        let weekday =
+            ics::parse_weekday_code(code).ok_or_else(|| format!("不支援 BYDAY={}", days))?;
+        if !weekdays.contains(&weekday) {
+            weekdays.push(weekday);
+        }
+    }
+    Ok(weekdays)
+}
+
+// 解析 UNTIL，可以是日期或 UTC 時間，轉換為伺服器時區的日期
+fn parse_until(value: &str, tz: &Tz) -> Result<NaiveDate, String> {
+    let prop = Property {
+        name: "UNTIL".to_string(),
+        params: Vec::new(),
+        value: value.to_string(),
+    };
+    ics::parse_date(&prop)
+        .or_else(|| ics::parse_date_time(&prop, tz).map(|until| until.date_naive()))
+        .ok_or_else(|| format!("無效的 UNTIL '{}'", value))
+}
+
+// 解析 EXDATE 中以逗號分隔的日期或時間，轉換為伺服器時區的日期
+fn exception_dates(prop: &Property, tz: &Tz) -> Vec<NaiveDate> {
+    prop.value
+        .split(',')
+        .filter_map(|value| {
+            let prop = Property {
+                value: value.trim().to_string(),
+                ..prop.clone()
+            };
+            ics::parse_date(&prop)
+                .or_else(|| ics::parse_date_time(&prop, tz).map(|date| date.date_naive()))
+        })
+        .collect()
+}
+
+// 將 VALARM 中開始前的 TRIGGER 轉換為觸發前預告
+fn parse_triggers(event: &Event) -> Vec<LeadAlert> {
+    let mut minutes: Vec<u32> = event
+        .get_all("TRIGGER")
+        .filter(|prop| {
+            prop.param("RELATED")
+                .is_none_or(|related| related.eq_ignore_ascii_case("START"))
+        })
+        .filter_map(|prop| ics::parse_duration(&prop.value))
+        .filter(|offset| (-MAX_LEAD_MINUTES..0).contains(offset))
+        .map(|offset| offset.unsigned_abs() as u32)
+        .collect();
+    minutes.sort_unstable_by(|a, b| b.cmp(a));
+    minutes.dedup();
+    minutes.truncate(MAX_LEADS);
+    minutes
+        .into_iter()
+        .map(|minutes| LeadAlert {
+            minutes,
+            message: String::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::reminder::export_ics::event_lines;
+    use crate::modules::{ics::CalendarWriter, reminder::upcoming_fires};
+
+    const GUILD: GuildId = GuildId::new(1);
+    const CHANNEL: ChannelId = ChannelId::new(2);
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        date(2025, 3, 5).and_hms_opt(12, 0, 0).unwrap().and_utc()
+    }
+
+    fn berlin() -> Tz {
+        "Europe/Berlin".parse().unwrap()
+    }
+
+    fn reminder(recurrence: Recurrence) -> Reminder {
+        Reminder {
+            id: "abcde".to_string(),
+            time: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            recurrence,
+            message: "站會, 記得帶筆電".to_string(),
+            ..Default::default()
+        }
+    }
+
+    // 匯出為 .ics 後再匯入，返回匯入的提醒與事件
+    fn round_trip(reminder: &Reminder) -> (Reminder, Event) {
+        let holidays = BTreeMap::new();
+        let mut writer = CalendarWriter::new("test", berlin().name());
+        for line in event_lines(reminder, GUILD, CHANNEL, &berlin(), &holidays, now()).unwrap() {
+            writer.line(&line);
+        }
+        let events = ics::parse_events(&writer.finish()).unwrap();
+        assert_eq!(events.len(), 1);
+        let imported = event_to_reminder(&events[0], &berlin(), &holidays, now()).unwrap();
+        (imported, events[0].clone())
+    }
+
+    // 匯入的提醒與原本的提醒應在相同的時間觸發
+    fn assert_same_fires(original: &Reminder, imported: &Reminder) {
+        let holidays = BTreeMap::new();
+        assert_eq!(
+            upcoming_fires(imported, &berlin(), &holidays, now(), 12),
+            upcoming_fires(original, &berlin(), &holidays, now(), 12)
+        );
+    }
+
+    #[test]
+    fn weekly_round_trip_keeps_days_end_skips_and_leads() {
+        let mut original = reminder(Recurrence::Weekly {
+            weekdays: vec![Weekday::Mon, Weekday::Wed],
+            interval: 1,
+            anchor: None,
+        });
+        original.until = Some(date(2025, 12, 31));
+        original.skip_dates.insert(date(2025, 3, 17));
+        original.leads = vec![LeadAlert {
+            minutes: 30,
+            message: String::new(),
+        }];
+
+        let (imported, event) = round_trip(&original);
+        assert_eq!(imported.recurrence, original.recurrence);
+        assert_eq!(imported.time, original.time);
+        assert_eq!(imported.message, original.message);
+        assert_eq!(imported.until, original.until);
+        assert_eq!(imported.skip_dates, original.skip_dates);
+        assert_eq!(imported.leads, original.leads);
+        assert_eq!(exported_id(&event, GUILD), Some("abcde".to_string()));
+        assert_same_fires(&original, &imported);
+    }
+
+    #[test]
+    fn interval_rules_round_trip() {
+        for recurrence in [
+            Recurrence::Weekly {
+                weekdays: vec![Weekday::Tue],
+                interval: 2,
+                anchor: Some(date(2025, 2, 25)),
+            },
+            Recurrence::Daily {
+                interval: 3,
+                anchor: date(2025, 3, 1),
+            },
+        ] {
+            let original = reminder(recurrence);
+            let (imported, _) = round_trip(&original);
+            assert_same_fires(&original, &imported);
+        }
+    }
+
+    #[test]
+    fn monthly_rules_round_trip() {
+        for recurrence in [
+            Recurrence::MonthlyDay { day: 15 },
+            Recurrence::MonthlyDay { day: 31 },
+            Recurrence::MonthlyWeekday {
+                nth: 2,
+                weekday: Weekday::Tue,
+            },
+            Recurrence::MonthlyWeekday {
+                nth: -1,
+                weekday: Weekday::Fri,
+            },
+        ] {
+            let original = reminder(recurrence);
+            let (imported, _) = round_trip(&original);
+            assert_eq!(imported.recurrence, original.recurrence);
+            assert_same_fires(&original, &imported);
+        }
+    }
+
+    #[test]
+    fn remaining_count_round_trips_as_an_end_date() {
+        let mut original = reminder(Recurrence::Daily {
+            interval: 1,
+            anchor: date(2025, 3, 1),
+        });
+        original.remaining = Some(3);
+
+        let (imported, _) = round_trip(&original);
+        assert_eq!(imported.until, Some(date(2025, 3, 8)));
+        assert_same_fires(&original, &imported);
+    }
+
+    #[test]
+    fn unsupported_rules_are_rejected() {
+        let start = now().with_timezone(&berlin());
+        for rule in [
+            "FREQ=MONTHLY;INTERVAL=2",
+            "FREQ=MONTHLY;BYMONTHDAY=30",
+            "FREQ=WEEKLY;BYMONTH=3",
+            "FREQ=HOURLY",
+            "INTERVAL=2",
+        ] {
+            assert!(
+                parse_rule(&ics::rule_parts(rule), start).is_err(),
+                "{}",
+                rule
+            );
+        }
+    }
+}
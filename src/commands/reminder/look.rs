@@ -1,71 +1,350 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
 
-use serenity::all::GuildId;
-use serenity::{builder::CreateCommand, model::prelude::ChannelId};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use serenity::all::{
+    ButtonStyle, ChannelId, CommandInteraction, ComponentInteraction, Context, CreateActionRow,
+    CreateButton, CreateCommand, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, GuildId,
+};
 
-use crate::modules::func::load_reminders_from_file;
-use crate::Reminder;
+use crate::modules::{
+    cron::CronSchedule,
+    reminder::{guild_holidays, guild_timezone, next_fire, upcoming_fires},
+    template::{format_lead_time, truncate, weekday_name},
+};
+use crate::{GuildSettingsMap, Recurrence, Reminder, Reminders};
+
+// 翻頁按鈕 custom_id 的前綴
+pub const LOOK_PREFIX: &str = "look";
 
 // 註冊 look 命令
 pub fn register() -> CreateCommand {
     CreateCommand::new("look").description("查看當前設置的提醒")
 }
 
-// 執行 look 命令的主函數
-pub fn run(guild_id: GuildId, channel_id: ChannelId) -> String {
-    match load_reminders_from_file() {
-        Ok(reminders) => format_reminders(reminders, guild_id, channel_id),
-        Err(err) => format!("Failed to load reminders: {}", err),
+// cron 提醒顯示的下次觸發次數
+const UPCOMING_COUNT: usize = 3;
+// 每頁顯示的提醒數量
+const REMINDERS_PER_PAGE: usize = 10;
+// 每頁內容的長度上限，低於嵌入訊息描述的 4096 字元限制
+const MAX_PAGE_CHARS: usize = 3800;
+// 列表中顯示的訊息長度上限
+const MAX_MESSAGE_CHARS: usize = 80;
+
+// 執行 look 命令的主函數，以嵌入訊息回覆第一頁
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = command.guild_id.unwrap();
+    let (embed, components) =
+        render_page(&reminders, &guild_settings, guild_id, command.channel_id, 0).await;
+    let data = CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(components)
+        .ephemeral(true);
+    command
+        .create_response(&ctx.http, CreateInteractionResponse::Message(data))
+        .await?;
+    Ok(())
+}
+
+// 處理上一頁與下一頁按鈕，以目前的提醒重新產生指定的頁面
+pub async fn turn_page(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let page = component
+        .data
+        .custom_id
+        .strip_prefix(LOOK_PREFIX)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|page| page.parse().ok());
+    let (Some(page), Some(guild_id)) = (page, component.guild_id) else {
+        return Ok(());
+    };
+    let (embed, components) = render_page(
+        &reminders,
+        &guild_settings,
+        guild_id,
+        component.channel_id,
+        page,
+    )
+    .await;
+    let data = CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(components);
+    component
+        .create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(data))
+        .await?;
+    Ok(())
+}
+
+// 產生指定頁面的嵌入訊息與翻頁按鈕，頁數超出範圍時顯示最後一頁
+async fn render_page(
+    reminders: &Reminders,
+    guild_settings: &GuildSettingsMap,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    page: usize,
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let (tz, holidays) = {
+        let settings = guild_settings.read().await;
+        (
+            guild_timezone(&settings, &guild_id),
+            guild_holidays(&settings, &guild_id).clone(),
+        )
+    };
+    let (pages, total) = {
+        let reminders = reminders.read().await;
+        build_pages(reminders.get(&guild_id), channel_id, &tz, &holidays)
+    };
+
+    let embed = CreateEmbed::new().title("提醒列表");
+    if pages.is_empty() {
+        return (embed.description(">> 該群組尚未新增提醒"), Vec::new());
+    }
+    let page = page.min(pages.len() - 1);
+    let embed = embed
+        .description(&pages[page])
+        .footer(CreateEmbedFooter::new(format!(
+            "第 {} / {} 頁 · 共 {} 個提醒 · 時區 {}",
+            page + 1,
+            pages.len(),
+            total,
+            tz.name()
+        )));
+    if pages.len() == 1 {
+        return (embed, Vec::new());
     }
+
+    // 上一頁與下一頁的頁數相差 2，custom_id 不會重複
+    let buttons = vec![
+        CreateButton::new(format!("{}:{}", LOOK_PREFIX, page.saturating_sub(1)))
+            .label("上一頁")
+            .emoji('◀')
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("{}:{}", LOOK_PREFIX, page + 1))
+            .label("下一頁")
+            .emoji('▶')
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 == pages.len()),
+    ];
+    (embed, vec![CreateActionRow::Buttons(buttons)])
 }
 
-// 格式化提醒信息的函數
-fn format_reminders(
-    reminders: HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>,
-    o_guild_id: GuildId,
-    o_channel_id: ChannelId,
-) -> String {
-    let mut output = String::new();
-
-    if let Some(guild_reminders) = reminders.get(&o_guild_id) {
-        // 先處理當前頻道的提醒
-        if let Some(current_channel_reminders) = guild_reminders.get(&o_channel_id) {
-            output.push_str(&format!("V 頻道 ID: {} (當前頻道) V\n", o_channel_id));
-            if !current_channel_reminders.is_empty() {
-                for (index, reminder) in current_channel_reminders.iter().enumerate() {
-                    output.push_str(&format!(
-                        "{}. 週期: {:?}, 時間: {}, 訊息: {}\n",
-                        index, reminder.weekdays, reminder.time, reminder.message
-                    ));
+// 將伺服器的提醒依頻道分組並分頁，當前頻道排在最前面；返回各頁內容與提醒總數
+fn build_pages(
+    guild_reminder: Option<&HashMap<ChannelId, Vec<Reminder>>>,
+    current_channel: ChannelId,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+) -> (Vec<String>, usize) {
+    let Some(guild_reminder) = guild_reminder else {
+        return (Vec::new(), 0);
+    };
+    let mut channels: Vec<_> = guild_reminder.iter().collect();
+    channels.sort_by_key(|(channel_id, _)| (**channel_id != current_channel, **channel_id));
+
+    let now = Utc::now();
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    let mut count = 0;
+    let mut total = 0;
+    let mut heading = None;
+    for (channel_id, reminder_list) in channels {
+        // 個人提醒只屬於設定者本人，不在列表中顯示
+        for reminder in reminder_list.iter().filter(|r| r.dm_user.is_none()) {
+            let line = format_reminder(reminder, tz, holidays, now);
+            if count == REMINDERS_PER_PAGE
+                || page.chars().count() + line.chars().count() > MAX_PAGE_CHARS
+            {
+                pages.push(std::mem::take(&mut page));
+                count = 0;
+                heading = None;
+            }
+            // 每頁開頭與換頻道時加上頻道標題
+            if heading != Some(*channel_id) {
+                if !page.is_empty() {
+                    page.push('\n');
+                }
+                page.push_str(&format!("**<#{}>**", channel_id));
+                if *channel_id == current_channel {
+                    page.push_str(" (當前頻道)");
                 }
-            } else {
-                output.push_str(">> 尚未新增提醒\n");
+                page.push('\n');
+                heading = Some(*channel_id);
             }
-            output.push('\n')
-        } else {
-            output.push_str(">> 當前頻道尚未新增提醒\n\n")
+            page.push_str(&line);
+            count += 1;
+            total += 1;
         }
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    (pages, total)
+}
 
-        // 再處理其他頻道的提醒
-        for (channel_id, reminder_list) in guild_reminders {
-            if *channel_id != o_channel_id {
-                output.push_str(&format!("V 頻道 ID: {} V\n", channel_id));
-                if !reminder_list.is_empty() {
-                    for (index, reminder) in reminder_list.iter().enumerate() {
-                        output.push_str(&format!(
-                            "{}. 週期: {:?}, 時間: {}, 訊息: {}\n",
-                            index, reminder.weekdays, reminder.time, reminder.message
-                        ));
-                    }
-                } else {
-                    output.push_str(">> 尚未新增提醒\n");
-                }
-                output.push('\n');
+// 格式化單條提醒，下次觸發時間以 Discord 相對時間戳記顯示
+fn format_reminder(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    now: DateTime<Utc>,
+) -> String {
+    // cron 提醒的時間包含在表達式中
+    let time = match &reminder.recurrence {
+        Recurrence::Cron { .. } => String::new(),
+        _ => format!(" {}", reminder.time.format("%H:%M")),
+    };
+    let next = if reminder.paused {
+        "已暫停".to_string()
+    } else {
+        match reminder
+            .next_due
+            .or_else(|| next_fire(reminder, tz, holidays, now))
+        {
+            Some(next) => format!("下次 <t:{}:R>", next.timestamp()),
+            None => "不會再觸發".to_string(),
+        }
+    };
+    // cron 表達式不易閱讀，另外列出接下來的觸發時間
+    let upcoming = match &reminder.recurrence {
+        Recurrence::Cron { .. } if !reminder.paused => {
+            format!(" ({})", format_upcoming(reminder, tz, holidays))
+        }
+        _ => String::new(),
+    };
+    // 顯示設定提醒的成員，較早儲存的提醒沒有記錄
+    let owner = match (reminder.creator, reminder.created_at) {
+        (Some(user), Some(at)) => format!(
+            " · 建立者: <@{}> {}",
+            user,
+            at.with_timezone(tz).format("%Y-%m-%d")
+        ),
+        (Some(user), None) => format!(" · 建立者: <@{}>", user),
+        _ => String::new(),
+    };
+    // 顯示最後按下「完成」的成員
+    let acknowledged = match &reminder.acknowledged {
+        Some(ack) => format!(" · 最後完成: <@{}> <t:{}:R>", ack.user, ack.at.timestamp()),
+        None => String::new(),
+    };
+    format!(
+        "`{}` {}{} · {}{} · {}{}{}\n",
+        reminder.id,
+        format_schedule(reminder),
+        time,
+        next,
+        upcoming,
+        truncate(&reminder.message.replace('\n', " "), MAX_MESSAGE_CHARS),
+        owner,
+        acknowledged
+    )
+}
+
+// 格式化提醒接下來的觸發時間（已略過假日與跳過日期）
+pub fn format_upcoming(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+) -> String {
+    if let Recurrence::Cron { expr } = &reminder.recurrence {
+        if let Err(err) = CronSchedule::parse(expr) {
+            return format!("(無效的表達式：{})", err);
+        }
+    }
+    let upcoming = upcoming_fires(reminder, tz, holidays, Utc::now(), UPCOMING_COUNT);
+    if upcoming.is_empty() {
+        return "(不會再觸發)".to_string();
+    }
+    upcoming
+        .iter()
+        .map(|fire_at| {
+            fire_at
+                .with_timezone(tz)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+// 格式化提醒的週期規則或單次日期，以及結束條件
+pub fn format_schedule(reminder: &Reminder) -> String {
+    let mut schedule = format_recurrence(&reminder.recurrence);
+    if let Some(until) = reminder.until {
+        schedule.push_str(&format!(" (至 {})", until));
+    }
+    if let Some(remaining) = reminder.remaining {
+        schedule.push_str(&format!(" (剩 {} 次)", remaining));
+    }
+    if !reminder.leads.is_empty() {
+        let leads: Vec<String> = reminder
+            .leads
+            .iter()
+            .map(|lead| format!("{}前", format_lead_time(lead.minutes)))
+            .collect();
+        schedule.push_str(&format!(" (預告: {})", leads.join("、")));
+    }
+    if let Some(event) = &reminder.event {
+        schedule.push_str(&format!(" (Discord 活動: {} 分鐘)", event.duration));
+    }
+    if let Some(thread) = &reminder.thread {
+        let kind = match thread.forum {
+            true => "論壇貼文",
+            false => "討論串",
+        };
+        schedule.push_str(&format!(" ({}: {})", kind, thread.title));
+    }
+    if !reminder.skip_dates.is_empty() {
+        let dates: Vec<String> = reminder.skip_dates.iter().map(|d| d.to_string()).collect();
+        schedule.push_str(&format!(" (跳過: {})", dates.join("、")));
+    }
+    schedule
+}
+
+// 格式化提醒的週期規則或單次日期，不含結束條件等附加設定
+pub fn format_recurrence(recurrence: &Recurrence) -> String {
+    match recurrence {
+        Recurrence::Once { date } => format!("單次: {}", date),
+        Recurrence::Weekly {
+            weekdays, interval, ..
+        } => {
+            let days = format_weekdays(weekdays);
+            match interval {
+                0 | 1 => format!("週期: {}", days),
+                n => format!("週期: 每 {} 週的 {}", n, days),
             }
         }
-    } else {
-        output.push_str(">> 該群組尚未新增提醒\n");
+        Recurrence::Daily { interval, .. } => match interval {
+            0 | 1 => "週期: 每天".to_string(),
+            n => format!("週期: 每 {} 天", n),
+        },
+        Recurrence::MonthlyDay { day } => format!("週期: 每月 {} 日", day),
+        Recurrence::Cron { expr } => format!("Cron: `{}`", expr),
+        Recurrence::MonthlyWeekday { nth, weekday } => match nth {
+            -1 => format!("週期: 每月最後一個 {}", weekday_name(*weekday)),
+            n => format!("週期: 每月第 {} 個 {}", n, weekday_name(*weekday)),
+        },
     }
+}
 
-    output
+// 將星期列表格式化為「一、三、五」
+fn format_weekdays(weekdays: &[Weekday]) -> String {
+    weekdays
+        .iter()
+        .map(|day| weekday_name(*day))
+        .collect::<Vec<_>>()
+        .join("、")
 }
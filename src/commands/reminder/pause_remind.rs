@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use serenity::{
+    all::{CommandOptionType, GuildId, ResolvedOption, ResolvedValue, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+};
+use tokio::sync::Notify;
+
+use crate::{
+    modules::{
+        func::save_reminders,
+        reminder::{find_reminder_mut, owns_reminder},
+    },
+    Reminders,
+};
+
+// 註冊 pause_remind 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("pause_remind")
+        .description("暫停指定的提醒")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "id",
+                "提醒的 ID：輸入頻道、時間或訊息搜尋，或參照\"/look\"顯示的 ID",
+            )
+            .required(true)
+            .set_autocomplete(true),
+        )
+}
+
+// 執行 pause_remind 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminders: Reminders,
+    guild_id: GuildId,
+    member: Option<UserId>,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    set_paused(options, reminders, guild_id, member, notify, true).await
+}
+
+// 設定提醒的暫停狀態，供 pause_remind 與 resume_remind 共用；
+// member 為一般成員時只能設定自己的提醒
+pub async fn set_paused<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminders: Reminders,
+    guild_id: GuildId,
+    member: Option<UserId>,
+    notify: &Arc<Notify>,
+    paused: bool,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    // 從選項中獲取 id 的值
+    let id = options
+        .iter()
+        .find(|opt| opt.name == "id")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::String(s) => Some(s),
+            _ => None,
+        })
+        .unwrap_or("");
+
+    {
+        let mut reminders_lock = reminders.write().await;
+        let mut updated = reminders_lock.clone();
+        let Some(guild_reminder) = updated.get_mut(&guild_id) else {
+            return Ok(">> 該公會沒有設置任何提醒".to_string());
+        };
+        let Some((_, reminder)) = find_reminder_mut(guild_reminder, id) else {
+            return Ok(format!(">> 找不到 ID 為 '{}' 的提醒", id.trim()));
+        };
+        if member.is_some_and(|user_id| !owns_reminder(reminder, user_id)) {
+            return Ok(">> 你只能管理自己設定的提醒".to_string());
+        }
+
+        if reminder.paused == paused {
+            return Ok(if paused {
+                format!(">> 提醒 '{}' 已經是暫停狀態", reminder.id)
+            } else {
+                format!(">> 提醒 '{}' 並未暫停", reminder.id)
+            });
+        }
+        reminder.paused = paused;
+        // 恢復時從現在開始重新排程，不補發暫停期間的提醒
+        reminder.next_due = None;
+
+        // 先保存修改後的副本，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+        save_reminders(&updated)?;
+        *reminders_lock = updated;
+    }
+
+    // 通知提醒處理器重新排程
+    notify.notify_one();
+
+    Ok(if paused {
+        format!(">> 提醒 '{}' 已暫停", id.trim().to_lowercase())
+    } else {
+        format!(">> 提醒 '{}' 已恢復", id.trim().to_lowercase())
+    })
+}
@@ -1,8 +1,8 @@
 use std::{collections::HashMap, error::Error, sync::Arc};
 
-use chrono::{NaiveTime, Weekday};
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike, Utc};
 use serenity::{
-    all::{GuildId, ResolvedValue},
+    all::{ChannelType, CommandInteraction, ResolvedValue},
     builder::{CreateCommand, CreateCommandOption},
     model::{
         application::{CommandOptionType, ResolvedOption},
@@ -11,119 +11,530 @@ use serenity::{
 };
 use tokio::sync::Notify;
 
-use crate::Reminder;
-use crate::{modules::func::save_reminders_to_file, Reminders};
+use super::look::{format_schedule, format_upcoming};
+use crate::{
+    modules::{
+        cron::CronSchedule,
+        func::save_reminders,
+        reminder::{
+            guild_allows_everyone, guild_holidays, guild_timezone, new_reminder_id, next_fire,
+        },
+        scheduled_event::{DEFAULT_EVENT_MINUTES, MAX_EVENT_MINUTES},
+        template::{parse_color, parse_mentions},
+        time_parser::{parse_leads, parse_schedule, parse_time, parse_weekdays, Schedule},
+    },
+    GuildSettingsMap, Reminders,
+};
+use crate::{
+    CatchUpPolicy, Recurrence, Reminder, ReminderEmbed, ReminderEvent, ReminderMentions,
+    ReminderThread,
+};
+
+// 討論串預設的自動封存時間 (分鐘)，以及未設定標題時論壇貼文使用的標題
+const DEFAULT_AUTO_ARCHIVE: u16 = 1440;
+const DEFAULT_THREAD_TITLE: &str = "{date} {weekday}";
 
 // 註冊 remind 命令
 pub fn register() -> CreateCommand {
     CreateCommand::new("remind")
         .description("設置提醒")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "message", "提醒訊息")
+                .required(true),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "when",
+            "提醒時間：ex. \"every weekday 08:30\"、\"每月1號 09:00\"、\"明天晚上八點\"、\"in 20 minutes\"",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "weekdays",
+            "日期：需要提醒的日期，以 \"1,4,7\"、\"mon-fri\" 或 \"每週三\" 格式表示",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "time",
+            "時間：提醒時間，以 \"HH:MM\"、\"9pm\" 或 \"下午三點\" 格式表示",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "cron",
+            "cron 表達式：分 時 日 月 星期，ex. \"0 9 * * 1-5\" (平日早上 9 點)",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "until",
+            "結束日期：週期提醒最後觸發的日期，以 \"YYYY-MM-DD\" 格式表示",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "count",
+                "次數：週期提醒觸發幾次後結束",
+            )
+            .min_int_value(1),
+        )
+        .add_option(missed_option())
+        .add_option(missed_within_option())
+        .add_option(before_option())
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "mention",
+            "提醒時提及的身分組或成員：ex. \"@身分組 @成員\"",
+        ))
         .add_option(
             CreateCommandOption::new(
                 CommandOptionType::String,
-                "weekdays",
-                "日期：需要提醒的日期，以 \"1,4,7\" 格式表示",
+                "embed_title",
+                "以嵌入訊息發送時的標題，設定任一 embed 選項即以嵌入訊息發送",
+            )
+            .max_length(256),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "embed_color",
+            "嵌入訊息的顏色：ex. \"#ff8800\"",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "embed_image",
+            "嵌入訊息的圖片網址",
+        ))
+        .add_option(event_option())
+        .add_option(event_duration_option())
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "發送提醒的頻道、討論串或論壇，預設為目前頻道",
             )
-            .required(true),
+            .channel_types(vec![
+                ChannelType::Text,
+                ChannelType::News,
+                ChannelType::PublicThread,
+                ChannelType::PrivateThread,
+                ChannelType::NewsThread,
+                ChannelType::Forum,
+            ]),
         )
         .add_option(
             CreateCommandOption::new(
                 CommandOptionType::String,
-                "time",
-                "時間：提醒時間，以 \"HH:MM\" 格式表示",
+                "thread_title",
+                "每次提醒時建立新的討論串或論壇貼文，標題可使用 {date}、{weekday} 等佔位符",
             )
-            .required(true),
+            .max_length(100),
         )
         .add_option(
-            CreateCommandOption::new(CommandOptionType::String, "message", "提醒訊息")
-                .required(true),
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "auto_archive",
+                "建立的討論串沒有活動多久後自動封存 (預設 1 天)",
+            )
+            .add_int_choice("1 小時", 60)
+            .add_int_choice("1 天", 1440)
+            .add_int_choice("3 天", 4320)
+            .add_int_choice("1 週", 10080),
         )
 }
 
-// 執行 remind 命令的主函數
-pub async fn run<'a>(
-    options: &'a [ResolvedOption<'a>],
-    reminder: Reminders,
+// 從選項中取得發送提醒的頻道與其種類，未指定時為目前頻道
+fn parse_target(
+    options: &[ResolvedOption],
     channel_id: ChannelId,
-    guild_id: GuildId,
-    notify: &Arc<Notify>,
-) -> Result<String, Box<dyn Error + Send + Sync>> {
-    // 從選項中獲取 weekdays、time 和 message 的值
-    let weekdays = options
+    channel_kind: Option<ChannelType>,
+) -> (ChannelId, Option<ChannelType>) {
+    options
         .iter()
-        .find(|opt| opt.name == "weekdays")
+        .find(|opt| opt.name == "channel")
         .and_then(|opt| match &opt.value {
-            ResolvedValue::String(s) => Some(s),
+            ResolvedValue::Channel(channel) => Some((channel.id, Some(channel.kind))),
             _ => None,
         })
-        .unwrap_or(&"");
+        .unwrap_or((channel_id, channel_kind))
+}
 
-    let time = options
+// 從選項中建立每次觸發建立討論串的設定；論壇只能以貼文發送，未設定標題時使用預設標題
+fn parse_thread(
+    options: &[ResolvedOption],
+    kind: Option<ChannelType>,
+) -> Result<Option<ReminderThread>, String> {
+    let title = options
         .iter()
-        .find(|opt| opt.name == "time")
-        .and_then(|opt| match &opt.value {
-            ResolvedValue::String(s) => Some(s),
+        .find(|opt| opt.name == "thread_title")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::String(s) => Some(s.trim()),
             _ => None,
         })
-        .unwrap_or(&"");
+        .filter(|title| !title.is_empty());
+    let auto_archive = options
+        .iter()
+        .find(|opt| opt.name == "auto_archive")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Integer(i) => u16::try_from(i).ok(),
+            _ => None,
+        });
+
+    let forum = kind == Some(ChannelType::Forum);
+    let title = match (title, forum) {
+        (Some(title), _) => title,
+        (None, true) => DEFAULT_THREAD_TITLE,
+        (None, false) if auto_archive.is_some() => {
+            return Err("auto_archive 只能在建立討論串或論壇貼文時使用".to_string())
+        }
+        (None, false) => return Ok(None),
+    };
+    let in_thread = matches!(
+        kind,
+        Some(ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread)
+    );
+    if in_thread {
+        return Err("無法在討論串中再建立討論串，請選擇一般頻道或論壇".to_string());
+    }
+    Ok(Some(ReminderThread {
+        title: title.to_string(),
+        auto_archive: auto_archive.unwrap_or(DEFAULT_AUTO_ARCHIVE),
+        forum,
+    }))
+}
+
+// 建立離線錯過提醒處理方式的選項
+pub fn missed_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::String,
+        "missed",
+        "機器人離線而錯過提醒時的處理方式 (預設：延遲補發)",
+    )
+    .add_string_choice("延遲補發", "late")
+    .add_string_choice("略過", "skip")
+    .add_string_choice("限時補發", "within")
+}
 
-    let message = options
+// 建立觸發前預告的選項
+pub fn before_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::String,
+        "before",
+        "觸發前預告：以 \";\" 分隔，時長後可接訊息，ex. \"30m 準備集合; 5m 即將開始\"",
+    )
+}
+
+// 建立同步為 Discord 活動的選項
+pub fn event_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Boolean,
+        "event",
+        "同時建立 Discord 活動，顯示下一次提醒的時間，讓成員可以按「有興趣」",
+    )
+}
+
+// 建立 Discord 活動長度的選項
+pub fn event_duration_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "event_duration",
+        "Discord 活動的長度 (分鐘，預設 60)",
+    )
+    .min_int_value(1)
+    .max_int_value(MAX_EVENT_MINUTES.into())
+}
+
+// 從選項中建立 Discord 活動設定，指定長度時視為開啟，未開啟時返回 None
+pub fn parse_event(options: &[ResolvedOption]) -> Option<ReminderEvent> {
+    let enabled = options
         .iter()
-        .find(|opt| opt.name == "message")
-        .and_then(|opt| match &opt.value {
+        .find(|opt| opt.name == "event")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Boolean(b) => Some(b),
+            _ => None,
+        });
+    let duration = options
+        .iter()
+        .find(|opt| opt.name == "event_duration")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Integer(i) => u32::try_from(i).ok(),
+            _ => None,
+        });
+
+    match (enabled, duration) {
+        (Some(false), _) | (None, None) => None,
+        (_, duration) => Some(ReminderEvent {
+            duration: duration.unwrap_or(DEFAULT_EVENT_MINUTES),
+            id: None,
+            synced: None,
+        }),
+    }
+}
+
+// 建立限時補發分鐘數的選項
+pub fn missed_within_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "missed_within",
+        "限時補發：延遲不超過幾分鐘才補發 (預設 60)",
+    )
+    .min_int_value(1)
+}
+
+// 從選項中解析離線錯過提醒的處理方式
+pub fn parse_catch_up(options: &[ResolvedOption]) -> CatchUpPolicy {
+    let missed = options
+        .iter()
+        .find(|opt| opt.name == "missed")
+        .and_then(|opt| match opt.value {
             ResolvedValue::String(s) => Some(s),
             _ => None,
-        })
-        .unwrap_or(&"");
-
-    // 解析星期幾
-    let weekdays_result: Result<Vec<Weekday>, _> = weekdays
-        .split(',')
-        .map(|s| s.trim().parse::<u32>())
-        .map(|day_result| {
-            day_result.map(|day| match day {
-                1 => Weekday::Mon,
-                2 => Weekday::Tue,
-                3 => Weekday::Wed,
-                4 => Weekday::Thu,
-                5 => Weekday::Fri,
-                6 => Weekday::Sat,
-                7 => Weekday::Sun,
-                _ => Weekday::Mon, // 默認為星期一
+        });
+    let within = options
+        .iter()
+        .find(|opt| opt.name == "missed_within")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Integer(i) => u32::try_from(i).ok(),
+            _ => None,
+        });
+
+    match (missed, within) {
+        (Some("skip"), _) => CatchUpPolicy::Skip,
+        (Some("within"), minutes) | (None, minutes @ Some(_)) => CatchUpPolicy::Within {
+            minutes: minutes.unwrap_or(60),
+        },
+        _ => CatchUpPolicy::Late,
+    }
+}
+
+// 從 embed 選項建立嵌入訊息設定，皆未提供時以一般訊息發送
+fn parse_embed(
+    title: Option<&str>,
+    color: Option<&str>,
+    image: Option<&str>,
+) -> Result<Option<ReminderEmbed>, String> {
+    if title.is_none() && color.is_none() && image.is_none() {
+        return Ok(None);
+    }
+    let color = color.map(parse_color).transpose()?;
+    let image_url = image.map(str::trim);
+    if image_url.is_some_and(|url| !url.starts_with("https://") && !url.starts_with("http://")) {
+        return Err("圖片網址需以 http:// 或 https:// 開頭".to_string());
+    }
+    Ok(Some(ReminderEmbed {
+        title: title.map(|t| t.trim().to_string()),
+        color,
+        image_url: image_url.map(str::to_string),
+    }))
+}
+
+// 執行 remind 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminder: Reminders,
+    guild_settings: GuildSettingsMap,
+    command: &CommandInteraction,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let guild_id = command.guild_id.unwrap();
+    let user_id = command.user.id;
+    // 從選項中獲取 when、weekdays、time 和 message 的值
+    let get_option = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| match opt.value {
+                ResolvedValue::String(s) => Some(s),
+                _ => None,
             })
-        })
-        .collect();
+    };
+    let when = get_option("when");
+    let weekdays = get_option("weekdays");
+    let time = get_option("time");
+    let cron = get_option("cron");
+    let message = get_option("message").unwrap_or("");
+
+    let (tz, holidays, allow_everyone) = {
+        let settings = guild_settings.read().await;
+        (
+            guild_timezone(&settings, &guild_id),
+            guild_holidays(&settings, &guild_id).clone(),
+            guild_allows_everyone(&settings, &guild_id),
+        )
+    };
+    let now = Utc::now();
+    let today = now.with_timezone(&tz).date_naive();
 
-    let weekdays = match weekdays_result {
-        Ok(days) => days,
-        Err(_) => return Err(">> 錯誤的日期格式：ex. 1,2,3".into()),
+    // 解析提醒時間，when、weekdays 與 time、cron 三者擇一使用
+    let schedule = match (when, weekdays, time, cron) {
+        (Some(when), None, None, None) => parse_schedule(when, today),
+        (None, None, None, Some(cron)) => CronSchedule::parse(cron).map(|_| Schedule::Recurring {
+            recurrence: Recurrence::Cron {
+                expr: cron.split_whitespace().collect::<Vec<_>>().join(" "),
+            },
+            time: NaiveTime::MIN,
+        }),
+        (None, Some(weekdays), Some(time), None) => parse_weekdays(weekdays).and_then(|weekdays| {
+            parse_time(time).map(|time| Schedule::Recurring {
+                recurrence: Recurrence::Weekly {
+                    weekdays,
+                    interval: 1,
+                    anchor: None,
+                },
+                time,
+            })
+        }),
+        (Some(_), _, _, _) | (_, _, _, Some(_)) => {
+            return Ok(">> when、weekdays 與 time、cron 只能擇一使用".to_string())
+        }
+        _ => {
+            return Ok(
+                ">> 請提供 when、cron，或同時提供 weekdays 與 time：ex. when:every weekday 08:30"
+                    .to_string(),
+            )
+        }
+    };
+    let schedule = match schedule {
+        Ok(schedule) => schedule,
+        Err(err) => return Ok(format!(">> {}", err)),
     };
 
-    // 解析時間
-    let time = match NaiveTime::parse_from_str(time.trim(), "%H:%M") {
-        Ok(t) => t,
-        Err(_) => return Err(">> 錯誤的時間格式(24小時制)：ex. 01:24 or 23:34".into()),
+    // 解析結束日期與次數，只適用於週期提醒
+    let until = match get_option("until")
+        .map(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d"))
+        .transpose()
+    {
+        Ok(until) => until,
+        Err(_) => return Ok(">> 錯誤的結束日期格式：ex. 2026-12-31".to_string()),
     };
+    let count = options
+        .iter()
+        .find(|opt| opt.name == "count")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::Integer(i) => u32::try_from(i).ok(),
+            _ => None,
+        });
+    if (until.is_some() || count.is_some()) && !matches!(schedule, Schedule::Recurring { .. }) {
+        return Ok(">> until 與 count 只能用於週期提醒".to_string());
+    }
 
-    let reminder_message = message.to_string();
+    // 解析提及與嵌入訊息設定
+    let mentions = match get_option("mention").map(|m| parse_mentions(m, guild_id)) {
+        Some(Ok(mentions)) => mentions,
+        Some(Err(err)) => return Ok(format!(">> {}", err)),
+        None => ReminderMentions::default(),
+    };
+    if mentions.everyone && !allow_everyone {
+        return Ok(
+            ">> 此伺服器未開放提醒提及 @everyone，請管理員使用 /mention_everyone 開啟".to_string(),
+        );
+    }
+    let leads = match get_option("before").map(parse_leads).transpose() {
+        Ok(leads) => leads.unwrap_or_default(),
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    let embed = match parse_embed(
+        get_option("embed_title"),
+        get_option("embed_color"),
+        get_option("embed_image"),
+    ) {
+        Ok(embed) => embed,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+
+    // 解析發送的頻道與討論串設定，論壇無法直接發送預告訊息
+    let (channel_id, channel_kind) = parse_target(
+        options,
+        command.channel_id,
+        command.channel.as_ref().map(|channel| channel.kind),
+    );
+    let thread = match parse_thread(options, channel_kind) {
+        Ok(thread) => thread,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    if channel_kind == Some(ChannelType::Forum) && !leads.is_empty() {
+        return Ok(">> 論壇頻道的提醒無法設定觸發前預告".to_string());
+    }
+
+    // 依提醒時間的種類建立提醒
+    let new_reminder = Reminder {
+        creator: Some(user_id),
+        created_at: Some(now),
+        message: message.to_string(),
+        mentions,
+        embed,
+        leads,
+        event: parse_event(options),
+        thread,
+        catch_up: parse_catch_up(options),
+        until,
+        remaining: count,
+        ..Default::default()
+    };
+    let new_reminder = match schedule {
+        Schedule::Recurring { recurrence, time } => Reminder {
+            recurrence,
+            time,
+            ..new_reminder
+        },
+        Schedule::Once { date, time } => Reminder {
+            recurrence: Recurrence::Once { date },
+            time,
+            ..new_reminder
+        },
+        Schedule::After(duration) => {
+            if duration < Duration::minutes(1) {
+                return Ok(">> 提醒時間至少要 1 分鐘".to_string());
+            }
+            let fire_at = now + duration;
+            let local = fire_at.with_timezone(&tz);
+            Reminder {
+                recurrence: Recurrence::Once {
+                    date: local.date_naive(),
+                },
+                time: local.time().with_nanosecond(0).unwrap_or(local.time()),
+                next_due: Some(fire_at),
+                ..new_reminder
+            }
+        }
+    };
+
+    // 不允許設定不會再觸發的提醒（以伺服器時區計算）
+    let Some(first_fire) = new_reminder
+        .next_due
+        .or_else(|| next_fire(&new_reminder, &tz, &holidays, now))
+    else {
+        return Ok(">> 提醒時間已經過去或不會再觸發，請確認設定".to_string());
+    };
+    let reply = match &new_reminder.recurrence {
+        Recurrence::Cron { .. } => format!(
+            ">> 已設定提醒：{}, 下次提醒: {}",
+            format_schedule(&new_reminder),
+            format_upcoming(&new_reminder, &tz, &holidays)
+        ),
+        _ => format!(
+            ">> 已設定提醒：{}, 時間: {}, 下次提醒: {}",
+            format_schedule(&new_reminder),
+            new_reminder.time.format("%H:%M"),
+            first_fire.with_timezone(&tz).format("%Y-%m-%d %H:%M")
+        ),
+    };
 
     // 添加新的提醒
-    {
+    let id = {
         let mut reminders = reminder.write().await;
-        let guild_reminder = reminders.entry(guild_id).or_insert_with(HashMap::new);
+        let mut updated = reminders.clone();
+        let guild_reminder = updated.entry(guild_id).or_insert_with(HashMap::new);
+        let id = new_reminder_id(Some(guild_reminder));
         let channel_reminder = guild_reminder.entry(channel_id).or_insert_with(Vec::new);
         channel_reminder.push(Reminder {
-            weekdays,
-            time,
-            message: reminder_message,
-            last_executed: None,
+            id: id.clone(),
+            ..new_reminder
         });
-        // 保存提醒到文件
-        save_reminders_to_file(&reminders).expect("Failed to save reminders");
-    }
+        // 先保存加入新提醒的副本，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+        save_reminders(&updated)?;
+        *reminders = updated;
+        id
+    };
 
     // 通知提醒處理器有新的提醒
     notify.notify_one();
 
-    Ok(">> 已設定每週提醒".to_string())
+    Ok(format!("{}, 頻道: <#{}> (ID: {})", reply, channel_id, id))
 }
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, error::Error, sync::Arc};
+
+use chrono::{NaiveDate, Utc};
+use serenity::{
+    all::{GuildId, ResolvedValue, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+    model::{
+        application::{CommandOptionType, ResolvedOption},
+        id::ChannelId,
+    },
+};
+use tokio::sync::Notify;
+
+use super::remind::{
+    event_duration_option, event_option, missed_option, missed_within_option, parse_catch_up,
+    parse_event,
+};
+use crate::{
+    modules::{
+        func::save_reminders,
+        reminder::{guild_timezone, local_to_utc, new_reminder_id},
+        time_parser::parse_time,
+    },
+    GuildSettingsMap, Reminders,
+};
+use crate::{Recurrence, Reminder};
+
+// 註冊 remind_once 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("remind_once")
+        .description("設置單次提醒")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "date",
+                "日期：提醒日期，以 \"YYYY-MM-DD\" 格式表示",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "time",
+                "時間：提醒時間，以 \"HH:MM\"、\"9pm\" 或 \"下午三點\" 格式表示",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "message", "提醒訊息")
+                .required(true),
+        )
+        .add_option(missed_option())
+        .add_option(missed_within_option())
+        .add_option(event_option())
+        .add_option(event_duration_option())
+}
+
+// 執行 remind_once 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminder: Reminders,
+    guild_settings: GuildSettingsMap,
+    channel_id: ChannelId,
+    guild_id: GuildId,
+    user_id: UserId,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    // 從選項中獲取 date、time 和 message 的值
+    let date = options
+        .iter()
+        .find(|opt| opt.name == "date")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::String(s) => Some(s),
+            _ => None,
+        })
+        .unwrap_or(&"");
+
+    let time = options
+        .iter()
+        .find(|opt| opt.name == "time")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::String(s) => Some(s),
+            _ => None,
+        })
+        .unwrap_or(&"");
+
+    let message = options
+        .iter()
+        .find(|opt| opt.name == "message")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::String(s) => Some(s),
+            _ => None,
+        })
+        .unwrap_or(&"");
+
+    // 解析日期
+    let date = match NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+        Ok(d) => d,
+        Err(_) => return Ok(">> 錯誤的日期格式：ex. 2026-11-03".to_string()),
+    };
+
+    // 解析時間
+    let time = match parse_time(time) {
+        Ok(t) => t,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+
+    // 不允許設定已經過去的時間（以伺服器時區計算）
+    let tz = guild_timezone(&*guild_settings.read().await, &guild_id);
+    if local_to_utc(&tz, date, time) <= Utc::now() {
+        return Ok(">> 提醒時間已經過去，請設定未來的時間".to_string());
+    }
+
+    // 添加新的提醒
+    let id = {
+        let mut reminders = reminder.write().await;
+        let mut updated = reminders.clone();
+        let guild_reminder = updated.entry(guild_id).or_insert_with(HashMap::new);
+        let id = new_reminder_id(Some(guild_reminder));
+        let channel_reminder = guild_reminder.entry(channel_id).or_insert_with(Vec::new);
+        channel_reminder.push(Reminder {
+            id: id.clone(),
+            creator: Some(user_id),
+            created_at: Some(Utc::now()),
+            time,
+            recurrence: Recurrence::Once { date },
+            message: message.to_string(),
+            catch_up: parse_catch_up(options),
+            event: parse_event(options),
+            ..Default::default()
+        });
+        // 先保存加入新提醒的副本，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+        save_reminders(&updated)?;
+        *reminders = updated;
+        id
+    };
+
+    // 通知提醒處理器有新的提醒
+    notify.notify_one();
+
+    Ok(format!(
+        ">> 已設定單次提醒：{} {} (ID: {})",
+        date,
+        time.format("%H:%M"),
+        id
+    ))
+}
@@ -0,0 +1,152 @@
+use std::{collections::HashMap, error::Error, sync::Arc};
+
+use chrono::{Duration, Timelike, Utc};
+use serenity::{
+    all::{GuildId, ResolvedValue, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+    model::{
+        application::{CommandOptionType, ResolvedOption},
+        id::ChannelId,
+    },
+};
+use tokio::sync::Notify;
+
+use crate::{
+    modules::{
+        func::save_reminders,
+        reminder::{guild_timezone, new_reminder_id},
+        time_parser::parse_duration,
+    },
+    GuildSettingsMap, Reminders,
+};
+use crate::{Recurrence, Reminder};
+
+// 每位成員同時存在的個人提醒上限，延後按鈕建立的提醒也計算在內
+pub const MAX_PERSONAL_REMINDERS: usize = 5;
+// 個人提醒最長可設定的時間
+const MAX_DURATION_DAYS: i64 = 30;
+// 未提供訊息時使用的預設訊息
+const DEFAULT_MESSAGE: &str = "時間到了！";
+
+// 註冊 remindme 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("remindme")
+        .description("設置個人提醒，時間到時以私訊通知你")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "duration",
+                "多久之後提醒：ex. \"45m\"、\"2h30m\"、\"in 20 minutes\"、\"20分鐘後\"",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "message", "提醒訊息")
+                .max_length(500),
+        )
+}
+
+// 執行 remindme 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminder: Reminders,
+    guild_settings: GuildSettingsMap,
+    channel_id: ChannelId,
+    guild_id: GuildId,
+    user_id: UserId,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    // 從選項中獲取 duration 和 message 的值
+    let duration = options
+        .iter()
+        .find(|opt| opt.name == "duration")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::String(s) => Some(*s),
+            _ => None,
+        })
+        .unwrap_or("");
+
+    let message = options
+        .iter()
+        .find(|opt| opt.name == "message")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::String(s) => Some(s.trim()),
+            _ => None,
+        })
+        .filter(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_MESSAGE);
+
+    // 解析相對時間
+    let duration = match parse_duration(duration) {
+        Ok(duration) => duration,
+        Err(err) => return Ok(format!(">> {}", err)),
+    };
+    if duration < Duration::minutes(1) {
+        return Ok(">> 提醒時間至少要 1 分鐘".to_string());
+    }
+    if duration > Duration::days(MAX_DURATION_DAYS) {
+        return Ok(format!(">> 提醒時間最多只能設定 {} 天", MAX_DURATION_DAYS));
+    }
+
+    let fire_at = Utc::now() + duration;
+    let tz = guild_timezone(&*guild_settings.read().await, &guild_id);
+    let local = fire_at.with_timezone(&tz);
+
+    // 添加新的個人提醒
+    let id = {
+        let mut reminders = reminder.write().await;
+
+        // 限制每位成員的個人提醒數量
+        if personal_reminder_count(&reminders, user_id) >= MAX_PERSONAL_REMINDERS {
+            return Ok(format!(
+                ">> 你最多只能同時設定 {} 個個人提醒",
+                MAX_PERSONAL_REMINDERS
+            ));
+        }
+
+        let mut updated = reminders.clone();
+        let guild_reminder = updated.entry(guild_id).or_insert_with(HashMap::new);
+        let id = new_reminder_id(Some(guild_reminder));
+        let channel_reminder = guild_reminder.entry(channel_id).or_insert_with(Vec::new);
+        channel_reminder.push(Reminder {
+            id: id.clone(),
+            creator: Some(user_id),
+            created_at: Some(Utc::now()),
+            time: local.time().with_nanosecond(0).unwrap_or(local.time()),
+            recurrence: Recurrence::Once {
+                date: local.date_naive(),
+            },
+            message: message.to_string(),
+            next_due: Some(fire_at),
+            dm_user: Some(user_id),
+            ..Default::default()
+        });
+        // 先保存加入新提醒的副本，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+        save_reminders(&updated)?;
+        *reminders = updated;
+        id
+    };
+
+    // 通知提醒處理器有新的提醒
+    notify.notify_one();
+
+    Ok(format!(
+        ">> 將於 {} 私訊提醒你 (ID: {})",
+        local.format("%Y-%m-%d %H:%M"),
+        id
+    ))
+}
+
+// 計算成員目前的個人提醒數量：以私訊接收的提醒，以及成員按下延後按鈕建立的提醒
+pub fn personal_reminder_count(
+    reminders: &HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>,
+    user_id: UserId,
+) -> usize {
+    reminders
+        .values()
+        .flat_map(|guild_reminder| guild_reminder.values().flatten())
+        .filter(|r| {
+            r.dm_user == Some(user_id) || (r.snoozed_from.is_some() && r.creator == Some(user_id))
+        })
+        .count()
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use serenity::{
+    all::{CommandOptionType, GuildId, ResolvedOption, UserId},
+    builder::{CreateCommand, CreateCommandOption},
+};
+use tokio::sync::Notify;
+
+use super::pause_remind::set_paused;
+use crate::Reminders;
+
+// 註冊 resume_remind 命令
+pub fn register() -> CreateCommand {
+    CreateCommand::new("resume_remind")
+        .description("恢復已暫停的提醒")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "id",
+                "提醒的 ID：輸入頻道、時間或訊息搜尋，或參照\"/look\"顯示的 ID",
+            )
+            .required(true)
+            .set_autocomplete(true),
+        )
+}
+
+// 執行 resume_remind 命令的主函數
+pub async fn run<'a>(
+    options: &'a [ResolvedOption<'a>],
+    reminders: Reminders,
+    guild_id: GuildId,
+    member: Option<UserId>,
+    notify: &Arc<Notify>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    set_paused(options, reminders, guild_id, member, notify, false).await
+}
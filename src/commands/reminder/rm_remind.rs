@@ -1,10 +1,15 @@
+use std::sync::Arc;
+
 use serenity::{
-    all::{CommandOptionType, GuildId, ResolvedOption, ResolvedValue},
+    all::{CommandOptionType, GuildId, Http, ResolvedOption, ResolvedValue, UserId},
     builder::{CreateCommand, CreateCommandOption},
-    model::id::ChannelId,
 };
+use tokio::sync::Notify;
 
-use crate::{modules::func::save_reminders_to_file, Reminders};
+use crate::{
+    modules::{func::save_reminders, reminder::owns_reminder, scheduled_event::delete_event},
+    Reminders,
+};
 
 // 註冊 rm_remind 命令
 pub fn register() -> CreateCommand {
@@ -12,79 +17,99 @@ pub fn register() -> CreateCommand {
         .description("移除指定的提醒")
         .add_option(
             CreateCommandOption::new(
-                CommandOptionType::Integer,
-                "index",
-                "提醒的索引：請參照\"/look\"產生的索引",
+                CommandOptionType::String,
+                "id",
+                "提醒的 ID：輸入頻道、時間或訊息搜尋，或參照\"/look\"顯示的 ID",
             )
-            .required(true),
+            .required(true)
+            .set_autocomplete(true),
         )
         .add_option(CreateCommandOption::new(
-            CommandOptionType::String,
-            "channel_id",
-            "頻道ID請至 /look 查看",
+            CommandOptionType::Channel,
+            "channel",
+            "提醒所在的頻道，選擇後只建議該頻道的提醒",
         ))
 }
 
-// 執行 rm_remind 命令的主函數
+// 執行 rm_remind 命令的主函數，member 為一般成員時只能移除自己設定的提醒
 pub async fn run<'a>(
     options: &'a [ResolvedOption<'a>],
     reminders: Reminders,
-    channel_id: ChannelId,
     guild_id: GuildId,
+    member: Option<UserId>,
+    http: &Http,
+    notify: &Arc<Notify>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    // 從選項中獲取 index 和 channel_id 的值
-    let index_option = options.iter().find(|opt| opt.name == "index");
-    let channel_id_option = options.iter().find(|opt| opt.name == "channel_id");
+    // 從選項中獲取 id 的值
+    let id = options
+        .iter()
+        .find(|opt| opt.name == "id")
+        .and_then(|opt| match opt.value {
+            ResolvedValue::String(s) => Some(s.trim().to_lowercase()),
+            _ => None,
+        });
 
-    // 處理 channel_id 選項，如果沒有提供則使用當前頻道
-    let rm_channel_id = if let Some(channel_id_option) = channel_id_option {
-        if let ResolvedValue::String(channel_id_str) = channel_id_option.value {
-            if let Ok(channel_id_parse) = channel_id_str.parse::<u64>() {
-                ChannelId::new(channel_id_parse)
-            } else {
-                channel_id
-            }
-        } else {
-            channel_id
-        }
-    } else {
-        channel_id
+    let Some(id) = id else {
+        return Ok(">> 未提供有效的 ID".to_string());
+    };
+    // 指定頻道時只移除該頻道的提醒
+    let channel = options
+        .iter()
+        .find(|opt| opt.name == "channel")
+        .and_then(|opt| match &opt.value {
+            ResolvedValue::Channel(channel) => Some(channel.id),
+            _ => None,
+        });
+
+    let mut reminders_lock = reminders.write().await;
+    let mut updated = reminders_lock.clone();
+    let Some(guild_reminder) = updated.get_mut(&guild_id) else {
+        return Ok(">> 該公會沒有設置任何提醒".to_string());
+    };
+
+    // 在所有頻道中尋找指定 ID 的提醒
+    let found = guild_reminder
+        .iter()
+        .find_map(|(channel_id, reminder_list)| {
+            reminder_list
+                .iter()
+                .position(|r| r.id == id)
+                .map(|index| (*channel_id, index, &reminder_list[index]))
+        });
+    let Some((channel_id, index, reminder)) = found else {
+        return Ok(format!(">> 找不到 ID 為 '{}' 的提醒", id));
     };
+    if let Some(channel) = channel.filter(|channel| *channel != channel_id) {
+        return Ok(format!(">> 頻道 <#{}> 沒有 ID 為 '{}' 的提醒", channel, id));
+    }
+    if member.is_some_and(|user_id| !owns_reminder(reminder, user_id)) {
+        return Ok(">> 你只能管理自己設定的提醒".to_string());
+    }
 
-    // 處理 index 選項
-    if let Some(index_option) = index_option {
-        if let ResolvedValue::Integer(index) = index_option.value {
-            let index = index - 1; // 將用戶輸入的索引轉換為實際的數組索引
-            {
-                let mut reminders_lock = reminders.write().await;
-                if let Some(guild_reminder) = reminders_lock.get_mut(&guild_id) {
-                    if let Some(reminder_list) = guild_reminder.get_mut(&rm_channel_id) {
-                        if (index as usize) < reminder_list.len() {
-                            // 移除指定索引的提醒
-                            reminder_list.remove(index as usize);
-                            // 如果頻道內的提醒列表為空，則移除該頻道 ID
-                            if reminder_list.is_empty() {
-                                guild_reminder.remove(&rm_channel_id);
-                            }
-                            // 如果公會內的提醒列表為空，則移除該公會 ID
-                            if guild_reminder.is_empty() {
-                                reminders_lock.remove(&guild_id);
-                            }
-                            // 保存更新後的提醒列表到文件
-                            save_reminders_to_file(&reminders_lock).unwrap();
-                            return Ok(format!(">> 提醒索引 '{}' 已移除", index + 1));
-                        } else {
-                            return Ok(format!(">> 索引 '{}' 無效", index + 1));
-                        }
-                    } else {
-                        return Ok(">> 該頻道沒有設置任何提醒".to_string());
-                    }
-                } else {
-                    return Ok(">> 該公會沒有設置任何提醒".to_string());
-                }
-            }
+    // 移除指定的提醒
+    let mut event_id = None;
+    if let Some(reminder_list) = guild_reminder.get_mut(&channel_id) {
+        event_id = reminder_list.remove(index).event.and_then(|event| event.id);
+        // 如果頻道內的提醒列表為空，則移除該頻道 ID
+        if reminder_list.is_empty() {
+            guild_reminder.remove(&channel_id);
         }
     }
+    // 如果公會內的提醒列表為空，則移除該公會 ID
+    if guild_reminder.is_empty() {
+        updated.remove(&guild_id);
+    }
+    // 先保存更新後的提醒列表，成功後才更新記憶體中的提醒，避免保存失敗時兩者不一致
+    save_reminders(&updated)?;
+    *reminders_lock = updated;
+    drop(reminders_lock);
+    // 通知提醒處理器重新排程
+    notify.notify_one();
+
+    // 刪除同步建立的 Discord 活動
+    if let Some(event_id) = event_id {
+        delete_event(http, guild_id, event_id).await;
+    }
 
-    Ok(">> 未提供有效的索引".to_string())
+    Ok(format!(">> 提醒 '{}' 已移除", id))
 }
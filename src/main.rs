@@ -1,18 +1,18 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    env,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    process,
     sync::Arc,
 };
 
-use chrono::{NaiveDate, NaiveTime, Weekday};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc, Weekday};
 use colored::*;
 use dotenvy::dotenv;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serenity::{
     all::{
-        ActivityData, ChannelId, Colour, CreateEmbed, CreateMessage, GuildId, Interaction,
-        Timestamp, UserId, VoiceState,
+        ActivityData, ChannelId, Colour, CreateEmbed, CreateMessage, GuildId, Interaction, MessageId,
+        RoleId, ScheduledEventId, Timestamp, UserId, VoiceState,
     },
     async_trait,
     model::{channel::Message, gateway::Ready},
@@ -24,30 +24,360 @@ mod commands;
 mod modules;
 use modules::anti_tiktok::tiktok_refuse;
 use modules::func::{
-    ensure_file_exists, error_output, is_user_admin, load_reminders_from_file,
-    register_commands_guild_ids, system_output,
+    error_output, is_user_admin, load_reminders, register_commands_guild_ids, system_output,
 };
 use modules::{
-    anti_tiktok::load_tiktok_refuse_msg,
-    bot_process::{interaction_process, prefix_command_process},
+    bot_process::{
+        autocomplete_process, component_process, interaction_process, prefix_command_process,
+    },
+    config::{self, config},
+    storage::{self, exit_on_error, storage},
 };
 
 // Define the Reminder structure
 // 定義 Reminder 結構
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 struct Reminder {
-    // Days of the week for the reminder
-    // 要提醒的星期幾
-    weekdays: Vec<Weekday>,
+    // Short identifier that stays the same for the reminder's lifetime
+    // 提醒的短識別碼，在提醒存在期間保持不變
+    #[serde(default)]
+    id: String,
+    // Member who set the reminder and owns it, told when it is disabled; empty for reminders stored before
+    // 設定並擁有提醒的成員，提醒被停用時會收到通知；較早儲存的提醒為空
+    #[serde(default)]
+    creator: Option<UserId>,
+    // When the reminder was set, empty for reminders stored before
+    // 提醒的設定時間，較早儲存的提醒為空
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
     // Time for the reminder
     // 提醒時間
     time: NaiveTime,
-    // Content of the reminder message
-    // 提醒訊息內容
+    // When the reminder fires
+    // 提醒的觸發規則
+    #[serde(default)]
+    recurrence: Recurrence,
+    // Last date the reminder may fire on, empty when it never ends
+    // 提醒最後可觸發的日期，為空表示沒有結束日期
+    #[serde(default)]
+    until: Option<NaiveDate>,
+    // Occurrences left before the reminder ends, empty when unlimited
+    // 提醒結束前剩餘的次數，為空表示不限次數
+    #[serde(default)]
+    remaining: Option<u32>,
+    // Dates on which this reminder does not fire
+    // 此提醒不觸發的日期
+    #[serde(default)]
+    skip_dates: BTreeSet<NaiveDate>,
+    // Content of the reminder message, placeholders such as {date} are filled in when sent
+    // 提醒訊息內容，{date} 等佔位符會在發送時替換
     message: String,
+    // Roles and members pinged by the reminder
+    // 提醒會提及的身分組與成員
+    #[serde(default)]
+    mentions: ReminderMentions,
+    // Sends the message as an embed when set
+    // 設定後以嵌入訊息發送
+    #[serde(default)]
+    embed: Option<ReminderEmbed>,
+    // Number of times the reminder has fired
+    // 提醒已觸發的次數
+    #[serde(default)]
+    fire_count: u32,
     // Date of last execution
     // 上次執行的日期
     last_executed: Option<NaiveDate>,
+    // Occurrence the scheduler is waiting to deliver, empty when it needs rescheduling
+    // 排程器等待發送的下一次提醒時間，為空表示需要重新排程
+    #[serde(default)]
+    next_due: Option<DateTime<Utc>>,
+    // Time the reminder was last delivered successfully
+    // 上次成功發送提醒的時間
+    #[serde(default)]
+    last_sent: Option<DateTime<Utc>>,
+    // Time of the next attempt at next_due after its delivery failed with a transient error
+    // next_due 因暫時性錯誤發送失敗後，下一次嘗試發送的時間
+    #[serde(default)]
+    retry_at: Option<DateTime<Utc>>,
+    // How to handle an occurrence missed while the bot was offline
+    // 機器人離線期間錯過提醒時的處理方式
+    #[serde(default)]
+    catch_up: CatchUpPolicy,
+    // Paused reminders stay stored but are not scheduled
+    // 暫停的提醒會保留但不會被排程
+    #[serde(default)]
+    paused: bool,
+    // Member who receives a personal reminder by DM, channel reminders leave it empty
+    // 以私訊接收個人提醒的成員，頻道提醒則為空
+    #[serde(default)]
+    dm_user: Option<UserId>,
+    // Member who last pressed "Done" on a delivered reminder
+    // 最後在已發送的提醒上按下「完成」的成員
+    #[serde(default)]
+    acknowledged: Option<Acknowledgement>,
+    // Delivered message a snoozed reminder resends, so snoozing that message again replaces it
+    // 延後的提醒所重新發送的訊息，再次延後同一則訊息時會取代這個提醒
+    #[serde(default)]
+    snoozed_from: Option<MessageId>,
+    // Alerts sent a while before each occurrence, such as 30 minutes before
+    // 每次觸發前一段時間發送的預告，例如 30 分鐘前
+    #[serde(default)]
+    leads: Vec<LeadAlert>,
+    // Instants of the lead alerts already sent for the upcoming occurrence
+    // 下一次觸發已發送過的預告時間
+    #[serde(default)]
+    leads_sent: Vec<DateTime<Utc>>,
+    // Discord scheduled event showing the next occurrence, empty when not mirrored
+    // 顯示下一次觸發的 Discord 活動，為空表示不建立活動
+    #[serde(default)]
+    event: Option<ReminderEvent>,
+    // New thread or forum post started for every occurrence, empty to post in the channel itself
+    // 每次觸發時建立的新討論串或論壇貼文，為空表示直接發送到頻道
+    #[serde(default)]
+    thread: Option<ReminderThread>,
+}
+
+// Define when a reminder fires
+// 定義提醒的觸發規則
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Recurrence {
+    // Fires once on the given date
+    // 在指定日期觸發一次
+    Once {
+        date: NaiveDate,
+    },
+    // Repeats on the given weekdays every `interval` weeks, counted from the week of `anchor`
+    // 每 `interval` 週在指定的星期重複，從 `anchor` 所在的週開始計算
+    Weekly {
+        weekdays: Vec<Weekday>,
+        #[serde(default = "default_interval")]
+        interval: u32,
+        #[serde(default)]
+        anchor: Option<NaiveDate>,
+    },
+    // Repeats every `interval` days starting from `anchor`
+    // 從 `anchor` 開始每 `interval` 天重複
+    Daily {
+        interval: u32,
+        anchor: NaiveDate,
+    },
+    // Repeats on a day of every month, months without that day use their last day
+    // 每月的指定日重複，沒有該日的月份使用當月最後一天
+    MonthlyDay {
+        day: u32,
+    },
+    // Repeats on the nth weekday of every month, -1 being the last one
+    // 每月第 n 個指定星期重複，-1 表示最後一個
+    MonthlyWeekday {
+        nth: i8,
+        weekday: Weekday,
+    },
+    // Fires whenever a standard 5-field cron expression matches, ignoring `time`
+    // 依標準 5 欄位 cron 表達式觸發，不使用 `time`
+    Cron {
+        expr: String,
+    },
+}
+
+impl Default for Recurrence {
+    fn default() -> Self {
+        Recurrence::Weekly {
+            weekdays: Vec::new(),
+            interval: default_interval(),
+            anchor: None,
+        }
+    }
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+// Define who a reminder pings
+// 定義提醒會提及的對象
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+struct ReminderMentions {
+    #[serde(default)]
+    roles: Vec<RoleId>,
+    #[serde(default)]
+    users: Vec<UserId>,
+    // Pings @everyone, only honoured when the guild allows it
+    // 提及 @everyone，只有在伺服器允許時才有效
+    #[serde(default)]
+    everyone: bool,
+}
+
+// Define how a reminder is shown as an embed
+// 定義提醒以嵌入訊息顯示的方式
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+struct ReminderEmbed {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    color: Option<u32>,
+    #[serde(default)]
+    image_url: Option<String>,
+}
+
+// Define an alert sent `minutes` before a reminder fires, an empty message uses a default text
+// 定義在提醒觸發前 `minutes` 分鐘發送的預告，訊息為空時使用預設文字
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct LeadAlert {
+    minutes: u32,
+    #[serde(default)]
+    message: String,
+}
+
+// Define who acknowledged a delivered reminder and when
+// 定義誰在何時確認了已發送的提醒
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct Acknowledgement {
+    user: UserId,
+    at: DateTime<Utc>,
+}
+
+// Define the Discord scheduled event mirroring the next occurrence of a reminder
+// 定義同步顯示提醒下一次觸發的 Discord 活動
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ReminderEvent {
+    // Length of the event in minutes
+    // 活動的長度（分鐘）
+    duration: u32,
+    // ID of the created event, empty until it is created
+    // 已建立活動的 ID，建立前為空
+    #[serde(default)]
+    id: Option<ScheduledEventId>,
+    // Occurrence the event was last updated to, compared to find reminders needing a sync
+    // 活動最後更新的觸發內容，用於比對提醒是否需要同步
+    #[serde(default)]
+    synced: Option<EventOccurrence>,
+}
+
+// Define the name and time an event is shown with
+// 定義活動顯示的名稱與時間
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct EventOccurrence {
+    name: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+// Define the thread or forum post a reminder starts for each occurrence
+// 定義提醒每次觸發時建立的討論串或論壇貼文
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ReminderThread {
+    // Title with placeholders such as {date}, filled in for each occurrence
+    // 標題，可使用 {date} 等佔位符，每次觸發時替換
+    title: String,
+    // Minutes without activity before the thread is archived: 60, 1440, 4320 or 10080
+    // 討論串沒有活動多久後自動封存的分鐘數：60、1440、4320 或 10080
+    auto_archive: u16,
+    // Whether the channel is a forum, where each occurrence becomes a new post
+    // 頻道是否為論壇，每次觸發時建立新的貼文
+    #[serde(default)]
+    forum: bool,
+}
+
+// Define one attempt to send a reminder, kept in the guild's delivery log
+// 定義一次發送提醒的嘗試，記錄在伺服器的發送紀錄中
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct DeliveryRecord {
+    at: DateTime<Utc>,
+    channel_id: ChannelId,
+    reminder_id: String,
+    // Whether this was a lead alert rather than the reminder itself
+    // 是否為預告而非提醒本身
+    #[serde(default)]
+    lead: bool,
+    // Whether the reminder was sent by DM
+    // 是否以私訊發送
+    #[serde(default)]
+    dm: bool,
+    // Number of the attempt, starting at 1
+    // 第幾次嘗試，從 1 開始
+    attempt: u32,
+    // Error of a failed attempt, empty when it was sent
+    // 失敗時的錯誤訊息，成功時為空
+    #[serde(default)]
+    error: Option<String>,
+}
+
+// Define how a reminder missed during downtime is handled
+// 定義離線期間錯過的提醒如何處理
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+enum CatchUpPolicy {
+    // Send it late with a "(delayed)" marker
+    // 延遲發送並加上 "(delayed)" 標記
+    #[default]
+    Late,
+    // Drop the missed occurrence
+    // 略過錯過的提醒
+    Skip,
+    // Send it late only if it is at most this many minutes overdue
+    // 只有在延遲不超過指定分鐘數時才發送
+    Within {
+        minutes: u32,
+    },
+}
+
+// Define per-guild settings
+// 定義每個伺服器的設定
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct GuildSettings {
+    // IANA timezone name used for reminders and bans, e.g. "Europe/Berlin"
+    // 提醒與封禁使用的 IANA 時區名稱，例如 "Europe/Berlin"
+    #[serde(default)]
+    timezone: Option<String>,
+    // Dates on which recurring reminders of the guild do not fire, with an optional name
+    // 伺服器的週期提醒不觸發的日期，可附上名稱
+    #[serde(default)]
+    holidays: BTreeMap<NaiveDate, String>,
+    // Whether reminders may ping @everyone and @here, set by an administrator
+    // 提醒是否可以提及 @everyone 與 @here，由管理員設定
+    #[serde(default)]
+    allow_everyone: bool,
+    // Role whose members may manage every reminder like administrators
+    // 可以像管理員一樣管理所有提醒的身分組
+    #[serde(default)]
+    manager_role: Option<RoleId>,
+    // Channel treated as a honeypot: whoever posts there is listed as a fraud bot and has
+    // their messages removed
+    // 作為誘捕頻道的頻道：在此發言的人會被列為詐騙機器人，其訊息會被刪除
+    #[serde(default)]
+    honeypot_channel: Option<ChannelId>,
+    // Channel logging members joining, moving between and leaving voice channels
+    // 記錄成員進入、移動與離開語音頻道的頻道
+    #[serde(default)]
+    voice_log_channel: Option<ChannelId>,
+    // Voice channel members are disconnected from as soon as they join
+    // 成員一進入就會被中斷連線的語音頻道
+    #[serde(default)]
+    voice_ban_channel: Option<ChannelId>,
+    // Whether TikTok links posted in the guild are answered with a refuse message
+    // 是否以拒絕訊息回覆伺服器中發送的 TikTok 鏈接
+    #[serde(default)]
+    tiktok_refuse: bool,
+    // Role whose members cannot be banned with /ban, the guild owner never can be
+    // 無法以 /ban 封禁的身分組，伺服器擁有者永遠無法被封禁
+    #[serde(default)]
+    ban_protected_role: Option<RoleId>,
+    // Member who cannot be banned with /ban, such as a co-owner of the guild
+    // 無法以 /ban 封禁的成員，例如伺服器的共同擁有者
+    #[serde(default)]
+    ban_protected_user: Option<UserId>,
+}
+
+// Define Cash struct to store individual debt information
+// 定義 Cash 結構體，用於存儲單筆欠款信息
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Cash {
+    creator: UserId,
+    debtor: String,
+    creditor: String,
+    debt: usize,
+    ps: String,
 }
 
 // Define Reminders type for storing reminders for all servers and channels
@@ -57,6 +387,9 @@ type BanList = Arc<RwLock<Vec<(UserId, NaiveTime)>>>;
 type TiktokRefuseMsg = Arc<RwLock<Vec<String>>>;
 type MessageCaches = Arc<RwLock<VecDeque<Message>>>;
 type FraudBotList = Arc<RwLock<HashSet<UserId>>>;
+type GuildSettingsMap = Arc<RwLock<HashMap<GuildId, GuildSettings>>>;
+type DeliveryLog = Arc<RwLock<HashMap<GuildId, VecDeque<DeliveryRecord>>>>;
+type CashLists = Arc<RwLock<HashMap<GuildId, Vec<Cash>>>>;
 
 // Define the Handler structure
 // 定義 Handler 結構
@@ -77,6 +410,15 @@ struct Handler {
     // Ban list
     // 封禁列表
     ban_list: BanList,
+    // Per-guild settings such as timezone
+    // 每個伺服器的設定，例如時區
+    guild_settings: GuildSettingsMap,
+    // Recent delivery attempts of reminders per guild
+    // 每個伺服器最近的提醒發送紀錄
+    delivery_log: DeliveryLog,
+    // Debts kept by /cash per guild
+    // 每個伺服器以 /cash 保存的欠款
+    cash_lists: CashLists,
 
     message_caches: MessageCaches,
     fraud_bot_list: FraudBotList,
@@ -118,7 +460,10 @@ impl Handler {
     }
 
     async fn fraud_bot_list_add(&self, user_id: UserId) {
-        self.fraud_bot_list.write().await.insert(user_id);
+        let mut list = self.fraud_bot_list.write().await;
+        if list.insert(user_id) {
+            save_fraud_bot_list(&list);
+        }
     }
 
     async fn is_fraud_bot(&self, user_id: &UserId) -> bool {
@@ -126,7 +471,18 @@ impl Handler {
     }
 
     async fn fraud_bot_list_remove(&self, user_id: &UserId) {
-        self.fraud_bot_list.write().await.remove(user_id);
+        let mut list = self.fraud_bot_list.write().await;
+        if list.remove(user_id) {
+            save_fraud_bot_list(&list);
+        }
+    }
+}
+
+// Save the fraud bot list, logging failures
+// 保存詐騙機器人名單，失敗時記錄錯誤
+fn save_fraud_bot_list(list: &HashSet<UserId>) {
+    if let Err(err) = storage().save_fraud_list(list) {
+        println!("{} Failed to save fraud list: {}", error_output(), err);
     }
 }
 
@@ -142,13 +498,25 @@ impl EventHandler for Handler {
             return;
         }
 
-        if self.is_fraud_bot(&msg.author.id).await {
-            self.delete_message_from(&ctx, msg.author.id).await;
-        }
+        let features = &config().features;
+        // Settings of the guild the message was sent in
+        // 訊息所在伺服器的設定
+        let (honeypot_channel, tiktok_refuse_enabled) = {
+            let settings = self.guild_settings.read().await;
+            msg.guild_id
+                .and_then(|guild_id| settings.get(&guild_id))
+                .map_or((None, false), |s| (s.honeypot_channel, s.tiktok_refuse))
+        };
 
-        if msg.channel_id == ChannelId::new(1470829664527192167) {
-            self.fraud_bot_list_add(msg.author.id).await;
-            self.delete_message_from(&ctx, msg.author.id).await;
+        if features.fraud_guard {
+            if self.is_fraud_bot(&msg.author.id).await {
+                self.delete_message_from(&ctx, msg.author.id).await;
+            }
+
+            if Some(msg.channel_id) == honeypot_channel {
+                self.fraud_bot_list_add(msg.author.id).await;
+                self.delete_message_from(&ctx, msg.author.id).await;
+            }
         }
 
         // Check if the message matches the command prefix
@@ -157,9 +525,9 @@ impl EventHandler for Handler {
             prefix_command_process(&ctx, &msg, self).await
         };
 
-        // Handle TikTok messages in a specific guild
-        // 在特定伺服器中處理 TikTok 消息
-        if msg.guild_id == Some(GuildId::new(1143403544599334992)) {
+        // Handle TikTok messages in guilds that turned it on
+        // 在開啟此功能的伺服器中處理 TikTok 消息
+        if features.anti_tiktok && tiktok_refuse_enabled {
             tiktok_refuse(&ctx, &msg, Arc::clone(&self.tiktok_refuse_msg)).await;
         };
     }
@@ -167,6 +535,18 @@ impl EventHandler for Handler {
     // Handle interaction commands
     // 處理交互命令
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        // Buttons attached to messages, such as those on delivered reminders
+        // 訊息上的按鈕，例如已發送提醒上的按鈕
+        if let Interaction::Component(component) = interaction {
+            component_process(self, &ctx, &component).await;
+            return;
+        }
+        // Suggestions while an option is typed, such as reminder IDs
+        // 輸入選項時的建議，例如提醒 ID
+        if let Interaction::Autocomplete(command) = interaction {
+            autocomplete_process(self, &ctx, &command).await;
+            return;
+        }
         if let Interaction::Command(command) = interaction {
             // Output information about the received interaction command
             // 輸出接收到的交互命令信息
@@ -188,17 +568,22 @@ impl EventHandler for Handler {
     }
 
     async fn voice_state_update(&self, ctx: Context, old: Option<VoiceState>, new: VoiceState) {
-        let guild_id = Some(GuildId::new(1143403544599334992));
-        if new.guild_id != guild_id {
+        if !config().features.voice {
             return;
         }
-
-        let channel = ChannelId::new(1368144725520945182);
-        let ban_channel = ChannelId::new(1470833491779256442);
+        let Some(guild_id) = new.guild_id else {
+            return;
+        };
+        let (log_channel, ban_channel) = {
+            let settings = self.guild_settings.read().await;
+            settings
+                .get(&guild_id)
+                .map_or((None, None), |s| (s.voice_log_channel, s.voice_ban_channel))
+        };
 
         // Kick user if they entered the ban channel
         // 若使用者進入封禁頻道則踢出
-        if new.channel_id == Some(ban_channel) {
+        if ban_channel.is_some() && new.channel_id == ban_channel {
             if let Some(member) = new.member.clone() {
                 if let Err(err) = member.disconnect_from_voice(&ctx.http).await {
                     println!(
@@ -211,77 +596,46 @@ impl EventHandler for Handler {
             }
             return; // Skip move/join announcement after kicking
         }
-        match old {
-            Some(old) => {
-                if old.channel_id == Some(ban_channel) {
-                    return;
-                };
-                if old.guild_id == guild_id && old.channel_id.is_some() {
-                    if new.channel_id.is_none() {
-                        // 離開頻道
-                        let msg =
-                            format!("<@{}> 離開了 <#{}>", old.user_id, old.channel_id.unwrap());
-                        let embed = CreateEmbed::new()
-                            .timestamp(Timestamp::now())
-                            .field("", msg, true)
-                            .color(Colour::RED);
-                        if let Err(err) = channel
-                            .send_message(&ctx, CreateMessage::new().add_embed(embed))
-                            .await
-                        {
-                            println!(
-                                "{} {} {:?}",
-                                error_output(),
-                                "Voice state update error:".red(),
-                                err
-                            );
-                        }
-                    } else {
-                        if old.channel_id == new.channel_id {
-                            return;
-                        }
-                        let msg = format!(
-                            "<@{}> 從 <#{}> 移動到 <#{}>",
-                            old.user_id,
-                            old.channel_id.unwrap(),
-                            new.channel_id.unwrap()
-                        );
-                        let embed = CreateEmbed::new()
-                            .timestamp(Timestamp::now())
-                            .field("", msg, true)
-                            .color(Colour::GOLD);
-                        if let Err(err) = channel
-                            .send_message(&ctx, CreateMessage::new().add_embed(embed))
-                            .await
-                        {
-                            println!(
-                                "{} {} {:?}",
-                                error_output(),
-                                "Voice state update error:".red(),
-                                err
-                            );
-                        }
-                    }
-                }
-            }
-            None => {
-                let msg = format!("<@{}> 進入了 <#{}>", new.user_id, new.channel_id.unwrap());
-                let embed = CreateEmbed::new()
-                    .timestamp(Timestamp::now())
-                    .field("", msg, true)
-                    .color(Colour::DARK_GREEN);
-                if let Err(err) = channel
-                    .send_message(&ctx, CreateMessage::new().add_embed(embed))
-                    .await
-                {
-                    println!(
-                        "{} {} {:?}",
-                        error_output(),
-                        "Voice state update error:".red(),
-                        err
-                    );
-                }
-            }
+
+        let Some(channel) = log_channel else {
+            return;
+        };
+        let old_channel = old.and_then(|old| old.channel_id);
+        if ban_channel.is_some() && old_channel == ban_channel {
+            return;
+        }
+        let (msg, colour) = match (old_channel, new.channel_id) {
+            // 離開頻道
+            (Some(from), None) => (
+                format!("<@{}> 離開了 <#{}>", new.user_id, from),
+                Colour::RED,
+            ),
+            // 移動頻道
+            (Some(from), Some(to)) if from != to => (
+                format!("<@{}> 從 <#{}> 移動到 <#{}>", new.user_id, from, to),
+                Colour::GOLD,
+            ),
+            // 進入頻道
+            (None, Some(to)) => (
+                format!("<@{}> 進入了 <#{}>", new.user_id, to),
+                Colour::DARK_GREEN,
+            ),
+            _ => return,
+        };
+        let embed = CreateEmbed::new()
+            .timestamp(Timestamp::now())
+            .field("", msg, true)
+            .color(colour);
+        if let Err(err) = channel
+            .send_message(&ctx, CreateMessage::new().add_embed(embed))
+            .await
+        {
+            println!(
+                "{} {} {:?}",
+                error_output(),
+                "Voice state update error:".red(),
+                err
+            );
         }
     }
 
@@ -290,12 +644,8 @@ impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         // Set the bot's activity status
         // 設置機器人活動狀態
-        ctx.set_activity(Some(ActivityData::playing("記憶大賽....")));
-
-        // Ensure the file for storing guild IDs exists
-        // 確保存儲伺服器 ID 的文件存在
-        let file_path = "assets/guild_id.txt";
-        ensure_file_exists(file_path).unwrap();
+        let activity = &config().bot.activity;
+        ctx.set_activity((!activity.is_empty()).then(|| ActivityData::playing(activity)));
 
         // Register commands for specified guild IDs
         // 註冊命令到指定的伺服器
@@ -318,9 +668,19 @@ async fn main() {
     // 載入環境變量
     dotenv().ok();
 
+    // Load the configuration file, reporting every problem at once
+    // 加載設定文件，一次回報所有問題
+    if let Err(problems) = config::init() {
+        println!("{} {}", error_output(), "Invalid configuration:".red());
+        for problem in problems {
+            println!("  - {}", problem);
+        }
+        process::exit(1);
+    }
+
     // Get Discord bot token
     // 獲取 Discord 機器人令牌
-    let token = env::var("TOKEN").expect("missing token");
+    let token = config().token.clone();
 
     // Set required bot permissions
     // 設置機器人所需的權限
@@ -330,16 +690,52 @@ async fn main() {
         | GatewayIntents::GUILD_VOICE_STATES
         | GatewayIntents::GUILD_MEMBERS;
 
-    // Load reminders from file or create an empty HashMap
-    // 從文件加載提醒，如果失敗則創建一個空的 HashMap
-    let reminders = match load_reminders_from_file() {
-        Ok(r) => Arc::new(RwLock::new(r)),
-        Err(_) => Arc::new(RwLock::new(HashMap::new())),
-    };
+    // Open the storage backend chosen in the configuration
+    // 開啟設定中選擇的存儲後端
+    exit_on_error("open storage", storage::init());
+
+    // Load every store, refusing to start if one of them cannot be read
+    // 加載所有存儲，任何一個無法讀取時拒絕啟動
+    let reminders = Arc::new(RwLock::new(exit_on_error(
+        "load reminders",
+        load_reminders(),
+    )));
+    let mut guild_settings = exit_on_error("load guild settings", storage().load_guild_settings());
+    // Write the settings of a server listed under [legacy_guild] in the configuration file
+    // 寫入設定文件中 [legacy_guild] 列出的伺服器設定
+    if let Some(legacy_guild) = &config().legacy_guild {
+        if legacy_guild.apply(&mut guild_settings) {
+            if let Err(err) = storage().save_guild_settings(&guild_settings) {
+                println!(
+                    "{} {} {}",
+                    error_output(),
+                    "Failed to save the legacy guild settings:".red(),
+                    err
+                );
+            }
+        }
+    }
+    let guild_settings = Arc::new(RwLock::new(guild_settings));
+    let delivery_log = Arc::new(RwLock::new(exit_on_error(
+        "load delivery log",
+        storage().load_delivery_log(),
+    )));
+    let ban_list = exit_on_error("load ban list", storage().load_bans());
+    let fraud_bot_list = exit_on_error("load fraud list", storage().load_fraud_list());
+    let tiktok_refuse_msg = exit_on_error(
+        "load TikTok refuse messages",
+        storage().load_refuse_messages(),
+    );
+
+    let cash_lists = exit_on_error("load cash", storage().load_cash());
 
     // Create regex for matching command prefixes
     // 創建用於匹配命令前綴的正則表達式
-    let prefix = Regex::new(r"^![A-Za-z]").unwrap();
+    let prefix = Regex::new(&format!(
+        "^{}[A-Za-z]",
+        regex::escape(&config().bot.prefix)
+    ))
+    .unwrap();
 
     // Create Handler instance
     // 創建 Handler 實例
@@ -347,10 +743,13 @@ async fn main() {
         reminders: Arc::clone(&reminders),
         trigger_notify: Arc::new(Notify::new()),
         prefix,
-        tiktok_refuse_msg: Arc::new(RwLock::new(load_tiktok_refuse_msg())),
-        ban_list: Arc::new(RwLock::new(Vec::new())),
+        tiktok_refuse_msg: Arc::new(RwLock::new(tiktok_refuse_msg)),
+        ban_list: Arc::new(RwLock::new(ban_list)),
+        guild_settings: Arc::clone(&guild_settings),
+        delivery_log: Arc::clone(&delivery_log),
+        cash_lists: Arc::new(RwLock::new(cash_lists)),
         message_caches: Arc::new(RwLock::new(VecDeque::new())),
-        fraud_bot_list: Arc::new(RwLock::new(HashSet::new())),
+        fraud_bot_list: Arc::new(RwLock::new(fraud_bot_list)),
     };
 
     // Create Discord client
@@ -364,11 +763,15 @@ async fn main() {
 
     // Start reminder task
     // 啟動提醒任務
-    tokio::spawn(modules::reminder::remind_task(
-        http,
-        Arc::clone(&handler.reminders),
-        Arc::clone(&handler.trigger_notify),
-    ));
+    if config().features.reminders {
+        tokio::spawn(modules::reminder::remind_task(
+            http,
+            Arc::clone(&handler.reminders),
+            Arc::clone(&handler.guild_settings),
+            Arc::clone(&handler.delivery_log),
+            Arc::clone(&handler.trigger_notify),
+        ));
+    }
 
     // Start the client and output error message if it fails
     // 啟動客戶端，如果出錯則輸出錯誤信息
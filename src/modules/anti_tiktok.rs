@@ -2,49 +2,22 @@ use rand::seq::IndexedRandom;
 use rand::SeedableRng;
 use rand_chacha::ChaCha12Rng;
 use serenity::all::{Context, Message};
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::error::Error;
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
-use super::func::ensure_file_exists;
-
-// Load TikTok refuse messages from file
-// 從文件加載 TikTok 拒絕消息
-pub fn load_tiktok_refuse_msg() -> Vec<String> {
-    ensure_file_exists("./assets/tiktok_refuse_msg.txt").unwrap();
-    let mut msg_vec = Vec::new();
-    let file_path = "./assets/tiktok_refuse_msg.txt";
-    let file = File::open(file_path).unwrap();
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        msg_vec.push(line.unwrap());
-    }
-    msg_vec
-}
-
-// Save TikTok refuse messages to file
-// 保存 TikTok 拒絕消息到文件
-pub fn save_tiktok_refuse_msg(tiktok_refuse_msg: &Vec<String>) -> Result<(), io::Error> {
-    let file_path = "./assets/tiktok_refuse_msg.txt";
-    let mut file = File::create(file_path).unwrap();
-    for msg in tiktok_refuse_msg {
-        file.write_all(format!("{}\n", msg).as_bytes()).unwrap();
-    }
-    Ok(())
-}
+use super::storage::storage;
 
 // Add a new TikTok refuse message
 // 添加新的 TikTok 拒絕消息
 pub async fn add_tiktok_refuse_msg(
     msg: &str,
     tiktok_refuse_msg: Arc<RwLock<Vec<String>>>,
-) -> Result<(), io::Error> {
+) -> Result<(), Box<dyn Error + Send + Sync>> {
     let mut tiktok_refuse_msg = tiktok_refuse_msg.write().await;
     tiktok_refuse_msg.push(msg.to_string());
 
-    save_tiktok_refuse_msg(&tiktok_refuse_msg)?;
+    storage().save_refuse_messages(&tiktok_refuse_msg)?;
 
     Ok(())
 }
@@ -1,19 +1,28 @@
 use std::sync::Arc;
 
 use colored::Colorize;
-use serenity::all::{CommandInteraction, Context, Message, UserId};
+use serenity::all::{CommandInteraction, ComponentInteraction, Context, Message, UserId};
 
-use super::func::{check_permission, interaction_response, register_commands};
+use super::config::config;
+use super::func::{
+    check_openai, check_permission, check_reminder_permission, interaction_response,
+    is_reminder_manager, register_commands,
+};
 use crate::{commands, modules::func::error_output, Handler};
 
 // Process prefix commands
 // 處理前綴命令的函數
 pub async fn prefix_command_process(ctx: &Context, msg: &Message, handler: &Handler) {
-    let content = &msg.content;
+    // Strip the configured prefix, "!" by default
+    // 去除設定的前綴，預設為 "!"
+    let prefix = config().bot.prefix.as_str();
+    let Some(content) = msg.content.strip_prefix(prefix) else {
+        return;
+    };
 
     // Handle !register command
     // 處理 !register 命令
-    if content == "!register" {
+    if content == "register" {
         println!("get command !register");
         let guild_id = msg.guild_id.unwrap();
         // Register slash commands
@@ -24,14 +33,17 @@ pub async fn prefix_command_process(ctx: &Context, msg: &Message, handler: &Hand
         msg.delete(&ctx.http).await.unwrap();
     }
 
-    if content.starts_with("!fraud_list_remove") {
+    if content.starts_with("fraud_list_remove") {
         let parts: Vec<&str> = content.split_whitespace().collect();
 
         if parts.len() < 2 {
             // no user id provided
             if let Err(err) = msg
                 .channel_id
-                .say(&ctx.http, "Usage: !fraud_list_remove {user_id}")
+                .say(
+                    &ctx.http,
+                    format!("Usage: {}fraud_list_remove {{user_id}}", prefix),
+                )
                 .await
             {
                 println!("send usage error: {:?}", err);
@@ -81,28 +93,127 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
                 .unwrap();
             true
         }
-        // Handle look command (view reminders)
-        // 處理 look 命令（查看提醒）
-        "look" => {
-            let guild_id = command.guild_id.unwrap();
-            let channel_id = command.channel_id;
-            let msg = commands::reminder::look::run(guild_id, channel_id);
+        // Handle timezone command (view or set guild timezone)
+        // 處理 timezone 命令（查看或設定伺服器時區）
+        "timezone" => {
+            let msg = commands::base::timezone::run(
+                command,
+                Arc::clone(&handler.guild_settings),
+                Arc::clone(&handler.reminders),
+                &handler.trigger_notify,
+                &command.data.options(),
+            )
+            .await;
+            interaction_response(ctx, command, msg, true).await;
+            true
+        }
+        // Handle mention_everyone command (allow reminders to ping @everyone)
+        // 處理 mention_everyone 命令（允許提醒提及 @everyone）
+        "mention_everyone" => {
+            let msg = commands::base::mention_everyone::run(
+                command,
+                Arc::clone(&handler.guild_settings),
+                &command.data.options(),
+            )
+            .await;
             interaction_response(ctx, command, msg, true).await;
             true
         }
+        // Handle reminder_manager command (set the role that may manage every reminder)
+        // 處理 reminder_manager 命令（設定可以管理所有提醒的身分組）
+        "reminder_manager" => {
+            let msg = commands::base::reminder_manager::run(
+                command,
+                Arc::clone(&handler.guild_settings),
+                &command.data.options(),
+            )
+            .await;
+            interaction_response(ctx, command, msg, true).await;
+            true
+        }
+        // Handle config command (view, set or reset the guild's settings)
+        // 處理 config 命令（查看、設定或重設伺服器設定）
+        "config" => {
+            if !check_permission(ctx, command).await {
+                return;
+            }
+            let msg = commands::base::config::run(
+                command,
+                Arc::clone(&handler.guild_settings),
+                Arc::clone(&handler.reminders),
+                &handler.trigger_notify,
+                &command.data.options(),
+            )
+            .await;
+            interaction_response(ctx, command, msg, true).await;
+            true
+        }
+        // Handle look command (view reminders)
+        // 處理 look 命令（查看提醒）
+        "look" => match commands::reminder::look::run(
+            ctx,
+            command,
+            handler.reminders.clone(),
+            Arc::clone(&handler.guild_settings),
+        )
+        .await
+        {
+            Ok(()) => true,
+            Err(err) => {
+                println!(
+                    "{} {} {}",
+                    error_output(),
+                    "Failed to show reminders:".red(),
+                    err
+                );
+                false
+            }
+        },
         // Handle remind command (set reminder)
         // 處理 remind 命令（設置提醒）
         "remind" => {
-            if !check_permission(ctx, command).await {
+            if !check_reminder_permission(ctx, command, &handler.guild_settings).await {
+                return;
+            }
+            match commands::reminder::remind::run(
+                &command.data.options(),
+                handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
+                command,
+                &handler.trigger_notify,
+            )
+            .await
+            {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to set reminder:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle remind_once command (set one-shot reminder)
+        // 處理 remind_once 命令（設置單次提醒）
+        "remind_once" => {
+            if !check_reminder_permission(ctx, command, &handler.guild_settings).await {
                 return;
             }
             let channel_id = command.channel_id;
             let guild_id = command.guild_id.unwrap();
-            match commands::reminder::remind::run(
+            match commands::reminder::remind_once::run(
                 &command.data.options(),
                 handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
                 channel_id,
                 guild_id,
+                command.user.id,
                 &handler.trigger_notify,
             )
             .await
@@ -122,19 +233,151 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
                 }
             }
         }
+        // Handle remindme command (set personal DM reminder, open to every member)
+        // 處理 remindme 命令（設置個人私訊提醒，所有成員皆可使用）
+        "remindme" => {
+            let channel_id = command.channel_id;
+            let guild_id = command.guild_id.unwrap();
+            match commands::reminder::remindme::run(
+                &command.data.options(),
+                handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
+                channel_id,
+                guild_id,
+                command.user.id,
+                &handler.trigger_notify,
+            )
+            .await
+            {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to set personal reminder:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
         // Handle rm_remind command (delete reminder)
         // 處理 rm_remind 命令（刪除提醒）
         "rm_remind" => {
+            let member = restricted_member(handler, command).await;
+            let guild_id = command.guild_id.unwrap();
+            match commands::reminder::rm_remind::run(
+                &command.data.options(),
+                handler.reminders.clone(),
+                guild_id,
+                member,
+                &ctx.http,
+                &handler.trigger_notify,
+            )
+            .await
+            {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to remove reminder:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle edit_remind command (edit reminder by ID)
+        // 處理 edit_remind 命令（依 ID 修改提醒）
+        "edit_remind" => {
+            let member = restricted_member(handler, command).await;
+            let guild_id = command.guild_id.unwrap();
+            match commands::reminder::edit_remind::run(
+                &command.data.options(),
+                handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
+                guild_id,
+                member,
+                &ctx.http,
+                &handler.trigger_notify,
+            )
+            .await
+            {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to edit reminder:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle pause_remind / resume_remind commands (pause or resume reminder by ID)
+        // 處理 pause_remind / resume_remind 命令（依 ID 暫停或恢復提醒）
+        "pause_remind" | "resume_remind" => {
+            let member = restricted_member(handler, command).await;
+            let guild_id = command.guild_id.unwrap();
+            let result = if command.data.name == "pause_remind" {
+                commands::reminder::pause_remind::run(
+                    &command.data.options(),
+                    handler.reminders.clone(),
+                    guild_id,
+                    member,
+                    &handler.trigger_notify,
+                )
+                .await
+            } else {
+                commands::reminder::resume_remind::run(
+                    &command.data.options(),
+                    handler.reminders.clone(),
+                    guild_id,
+                    member,
+                    &handler.trigger_notify,
+                )
+                .await
+            };
+            match result {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to pause or resume reminder:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle holiday command (manage guild holidays skipped by reminders)
+        // 處理 holiday 命令（管理提醒會跳過的伺服器假日）
+        "holiday" => {
             if !check_permission(ctx, command).await {
                 return;
             }
-            let channel_id = command.channel_id;
             let guild_id = command.guild_id.unwrap();
-            match commands::reminder::rm_remind::run(
+            match commands::reminder::holiday::run(
                 &command.data.options(),
+                Arc::clone(&handler.guild_settings),
                 handler.reminders.clone(),
-                channel_id,
                 guild_id,
+                &handler.trigger_notify,
             )
             .await
             {
@@ -146,7 +389,82 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
                     println!(
                         "{} {} {}",
                         error_output(),
-                        "Failed to remove reminder:".red(),
+                        "Failed to update holidays:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle delivery_log command (show recent reminder deliveries)
+        // 處理 delivery_log 命令（顯示最近的提醒發送紀錄）
+        "delivery_log" => {
+            if !check_permission(ctx, command).await {
+                return;
+            }
+            let msg = commands::reminder::delivery_log::run(
+                &command.data.options(),
+                Arc::clone(&handler.delivery_log),
+                Arc::clone(&handler.guild_settings),
+                command.guild_id.unwrap(),
+            )
+            .await;
+            interaction_response(ctx, command, msg, true).await;
+            true
+        }
+        // Handle export_ics command (export reminders as an iCalendar file)
+        // 處理 export_ics 命令（將提醒匯出為 iCalendar 文件）
+        "export_ics" => {
+            if !check_reminder_permission(ctx, command, &handler.guild_settings).await {
+                return;
+            }
+            match commands::reminder::export_ics::run(
+                ctx,
+                command,
+                handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
+            )
+            .await
+            {
+                Ok(()) => true,
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to export reminders:".red(),
+                        err
+                    );
+                    false
+                }
+            }
+        }
+        // Handle import_ics command (import reminders from an iCalendar file)
+        // 處理 import_ics 命令（從 iCalendar 文件匯入提醒）
+        "import_ics" => {
+            if !check_reminder_permission(ctx, command, &handler.guild_settings).await {
+                return;
+            }
+            let guild_id = command.guild_id.unwrap();
+            match commands::reminder::import_ics::run(
+                &command.data.options(),
+                handler.reminders.clone(),
+                Arc::clone(&handler.guild_settings),
+                command.channel_id,
+                guild_id,
+                command.user.id,
+                &handler.trigger_notify,
+            )
+            .await
+            {
+                Ok(msg) => {
+                    interaction_response(ctx, command, msg, true).await;
+                    true
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} {}",
+                        error_output(),
+                        "Failed to import reminders:".red(),
                         err
                     );
                     false
@@ -154,6 +472,9 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
             }
         }
 
+        // OpenAI commands answer that they are disabled when no API key is configured
+        // 沒有設定 API 金鑰時，OpenAI 命令回應功能未啟用
+        "chat" | "image" | "model_list" if !check_openai(ctx, command).await => false,
         // Handle chat command (OpenAI chat)
         // 處理 chat 命令（OpenAI 聊天）
         "chat" => match commands::openai::chat::run(ctx, command, &command.data.options()).await {
@@ -208,6 +529,7 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
                 ctx,
                 command,
                 Arc::clone(&handler.ban_list),
+                Arc::clone(&handler.guild_settings),
                 &command.data.options(),
             )
             .await;
@@ -249,7 +571,13 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
         // Handle cash command (debt system)
         // 處理 cash 命令（欠債系統）
         "cash" => {
-            commands::cash::run(ctx, command, &command.data.options()).await;
+            commands::cash::run(
+                ctx,
+                command,
+                Arc::clone(&handler.cash_lists),
+                &command.data.options(),
+            )
+            .await;
             true
         }
         // Handle tiktok_msg_add command (add TikTok refuse message)
@@ -271,3 +599,82 @@ pub async fn interaction_process(handler: &Handler, ctx: &Context, command: &Com
         _ => false,
     };
 }
+
+// Process autocomplete interactions, suggesting values while an option is typed
+// 處理自動完成交互，在輸入選項時提供建議
+pub async fn autocomplete_process(handler: &Handler, ctx: &Context, command: &CommandInteraction) {
+    // Suggest reminders for the ID option of reminder management commands
+    // 為提醒管理指令的 ID 選項建議提醒
+    if let "rm_remind" | "edit_remind" | "pause_remind" | "resume_remind" =
+        command.data.name.as_str()
+    {
+        if let Err(err) = commands::reminder::autocomplete::run(
+            ctx,
+            command,
+            handler.reminders.clone(),
+            Arc::clone(&handler.guild_settings),
+        )
+        .await
+        {
+            println!(
+                "{} {} {}",
+                error_output(),
+                "Failed to suggest reminders:".red(),
+                err
+            );
+        }
+    }
+}
+
+// The user a reminder command is restricted to: members without the manager
+// permission may only manage reminders they set, managers get None
+// 提醒指令限制的使用者：沒有管理權限的成員只能管理自己設定的提醒，管理者為 None
+async fn restricted_member(handler: &Handler, command: &CommandInteraction) -> Option<UserId> {
+    let settings = handler.guild_settings.read().await;
+    (!is_reminder_manager(command, &settings)).then_some(command.user.id)
+}
+
+// Process message component interactions such as buttons
+// 處理按鈕等訊息元件的交互
+pub async fn component_process(handler: &Handler, ctx: &Context, component: &ComponentInteraction) {
+    let prefix = component.data.custom_id.split(':').next().unwrap_or("");
+    // Handle Done and Snooze buttons on delivered reminders
+    // 處理已發送提醒上的完成與延後按鈕
+    if prefix == commands::reminder::buttons::BUTTON_PREFIX {
+        if let Err(err) = commands::reminder::buttons::run(
+            ctx,
+            component,
+            handler.reminders.clone(),
+            Arc::clone(&handler.guild_settings),
+            &handler.trigger_notify,
+        )
+        .await
+        {
+            println!(
+                "{} {} {}",
+                error_output(),
+                "Failed to handle reminder button:".red(),
+                err
+            );
+        }
+    }
+    // Handle previous and next page buttons of the reminder list
+    // 處理提醒列表的上一頁與下一頁按鈕
+    if prefix == commands::reminder::look::LOOK_PREFIX {
+        if let Err(err) = commands::reminder::look::turn_page(
+            ctx,
+            component,
+            handler.reminders.clone(),
+            Arc::clone(&handler.guild_settings),
+        )
+        .await
+        {
+            println!(
+                "{} {} {}",
+                error_output(),
+                "Failed to turn reminder list page:".red(),
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,662 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::{collections::HashMap, env, fs, io, path::Path};
+
+use colored::*;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serenity::all::{ChannelId, GuildId, UserId};
+
+use super::func::system_output;
+use crate::GuildSettings;
+
+// Configuration file read when `CONFIG_PATH` is not set; the bot runs on defaults without it
+// 未設定 `CONFIG_PATH` 時讀取的設定文件；沒有此文件時機器人以預設值運行
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+// Longest activity text Discord shows
+// Discord 顯示的最長活動文字
+const MAX_ACTIVITY_LEN: usize = 128;
+
+// The configuration loaded at startup
+// 啟動時加載的設定
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+// Settings of the bot, read from the configuration file and overridden by environment variables
+// 機器人的設定，從設定文件讀取，並可由環境變數覆蓋
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // Discord bot token, overridden by `TOKEN`
+    // Discord 機器人令牌，可由 `TOKEN` 覆蓋
+    pub token: String,
+    pub bot: BotConfig,
+    pub paths: PathsConfig,
+    pub storage: StorageConfig,
+    pub openai: OpenAiConfig,
+    pub features: Features,
+    pub legacy_guild: Option<LegacyGuildConfig>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    // Prefix of message commands such as !register, overridden by `COMMAND_PREFIX`
+    // 訊息命令（例如 !register）的前綴，可由 `COMMAND_PREFIX` 覆蓋
+    pub prefix: String,
+    // Text shown as the bot's activity, empty to show none; overridden by `ACTIVITY`
+    // 機器人活動狀態顯示的文字，為空表示不顯示；可由 `ACTIVITY` 覆蓋
+    pub activity: String,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        BotConfig {
+            prefix: "!".to_string(),
+            activity: "記憶大賽....".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    // Folder of the stores, backups and update logs, overridden by `DATA_DIR`
+    // 存儲、備份與更新日誌所在的資料夾，可由 `DATA_DIR` 覆蓋
+    pub data_dir: String,
+    // Folder of the pages shown by /info, overridden by `INFO_DIR`
+    // /info 顯示的頁面所在的資料夾，可由 `INFO_DIR` 覆蓋
+    pub info_dir: String,
+    // File of the debts kept by /cash, overridden by `CASH_FILE`
+    // /cash 保存欠款的文件，可由 `CASH_FILE` 覆蓋
+    pub cash_file: String,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        PathsConfig {
+            data_dir: "assets".to_string(),
+            info_dir: "info".to_string(),
+            cash_file: "cash.json".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    // "json" or "sqlite", overridden by `STORAGE_BACKEND`
+    // "json" 或 "sqlite"，可由 `STORAGE_BACKEND` 覆蓋
+    pub backend: String,
+    // Database file of the sqlite backend, "storage.db" in the data folder when empty;
+    // overridden by `SQLITE_PATH`
+    // sqlite 後端的資料庫文件，為空時為資料夾中的 "storage.db"；可由 `SQLITE_PATH` 覆蓋
+    pub sqlite_path: Option<String>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: "json".to_string(),
+            sqlite_path: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct OpenAiConfig {
+    // OpenAI API key, overridden by `API_KEY`; the OpenAI commands are disabled without one
+    // OpenAI API 金鑰，可由 `API_KEY` 覆蓋；沒有金鑰時停用 OpenAI 命令
+    pub api_key: Option<String>,
+}
+
+// Channels and member of a server set up before they became /config settings, which are
+// written into that server's settings at every start. Only the keys listed are written, the
+// others stay managed with /config
+// 在成為 /config 設定之前設定的伺服器頻道與成員，每次啟動時會寫入該伺服器的設定。
+// 只會寫入列出的項目，其他項目仍以 /config 管理
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct LegacyGuildConfig {
+    pub guild_id: u64,
+    pub honeypot_channel: Option<u64>,
+    pub voice_log_channel: Option<u64>,
+    pub voice_ban_channel: Option<u64>,
+    pub tiktok_refuse: Option<bool>,
+    pub ban_protected_user: Option<u64>,
+}
+
+impl LegacyGuildConfig {
+    // Write the listed values into the server's settings, returning whether anything changed
+    // 將列出的值寫入伺服器的設定，返回是否有任何變更
+    pub fn apply(&self, settings: &mut HashMap<GuildId, GuildSettings>) -> bool {
+        let guild = settings.entry(GuildId::new(self.guild_id)).or_default();
+        let mut changed = false;
+        let channels = [
+            (self.honeypot_channel, &mut guild.honeypot_channel),
+            (self.voice_log_channel, &mut guild.voice_log_channel),
+            (self.voice_ban_channel, &mut guild.voice_ban_channel),
+        ];
+        for (id, slot) in channels {
+            if let Some(channel) = id.map(ChannelId::new).filter(|c| *slot != Some(*c)) {
+                *slot = Some(channel);
+                changed = true;
+            }
+        }
+        if let Some(enabled) = self.tiktok_refuse.filter(|e| *e != guild.tiktok_refuse) {
+            guild.tiktok_refuse = enabled;
+            changed = true;
+        }
+        if let Some(user) = self
+            .ban_protected_user
+            .map(UserId::new)
+            .filter(|u| guild.ban_protected_user != Some(*u))
+        {
+            guild.ban_protected_user = Some(user);
+            changed = true;
+        }
+        changed
+    }
+
+    // IDs of Discord cannot be 0, which is what an unfilled key usually holds
+    // Discord 的 ID 不能為 0，未填寫的項目通常是 0
+    fn validate(&self, problems: &mut Vec<String>) {
+        let ids = [
+            ("guild_id", Some(self.guild_id)),
+            ("honeypot_channel", self.honeypot_channel),
+            ("voice_log_channel", self.voice_log_channel),
+            ("voice_ban_channel", self.voice_ban_channel),
+            ("ban_protected_user", self.ban_protected_user),
+        ];
+        for (name, id) in ids {
+            if id == Some(0) {
+                problems.push(format!("legacy_guild.{} must be a Discord ID", name));
+            }
+        }
+    }
+}
+
+// Feature modules that can be turned off, each overridden by `FEATURE_<NAME>`, e.g. `FEATURE_CASH=false`
+// 可以關閉的功能模組，每個都可由 `FEATURE_<名稱>` 覆蓋，例如 `FEATURE_CASH=false`
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Features {
+    // Reminder commands and the task delivering reminders
+    // 提醒命令與發送提醒的任務
+    pub reminders: bool,
+    // /chat, /image and /model_list
+    // /chat、/image 與 /model_list
+    pub openai: bool,
+    // Timed bans and the block list
+    // 限時封禁與封鎖名單
+    pub ban: bool,
+    // /cash debts
+    // /cash 欠款紀錄
+    pub cash: bool,
+    // Replies to TikTok links
+    // 回覆 TikTok 鏈接
+    pub anti_tiktok: bool,
+    // Honeypot channel and removal of messages from listed fraud bots
+    // 誘捕頻道與刪除名單中詐騙機器人的訊息
+    pub fraud_guard: bool,
+    // Voice channel join and leave log, and the voice ban channel
+    // 語音頻道進出紀錄與語音封禁頻道
+    pub voice: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features {
+            reminders: true,
+            openai: true,
+            ban: true,
+            cash: true,
+            anti_tiktok: true,
+            fraud_guard: true,
+            voice: true,
+        }
+    }
+}
+
+impl Features {
+    // Every feature with its name in the configuration file
+    // 所有功能及其在設定文件中的名稱
+    fn iter_mut(&mut self) -> [(&'static str, &mut bool); 7] {
+        [
+            ("reminders", &mut self.reminders),
+            ("openai", &mut self.openai),
+            ("ban", &mut self.ban),
+            ("cash", &mut self.cash),
+            ("anti_tiktok", &mut self.anti_tiktok),
+            ("fraud_guard", &mut self.fraud_guard),
+            ("voice", &mut self.voice),
+        ]
+    }
+}
+
+impl Config {
+    // Path of a file in the data folder
+    // 資料夾中文件的路徑
+    pub fn data_path(&self, file: &str) -> String {
+        Path::new(&self.paths.data_dir)
+            .join(file)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    // Database file of the sqlite backend
+    // sqlite 後端的資料庫文件
+    pub fn sqlite_path(&self) -> String {
+        self.storage
+            .sqlite_path
+            .clone()
+            .unwrap_or_else(|| self.data_path("storage.db"))
+    }
+
+    // Whether the OpenAI commands are enabled, which needs an API key
+    // OpenAI 命令是否啟用，需要有 API 金鑰
+    pub fn openai_enabled(&self) -> bool {
+        self.features.openai && self.openai.api_key.is_some()
+    }
+
+    // Apply the environment variables overriding the file, read through `var`
+    // 套用覆蓋設定文件的環境變數，透過 `var` 讀取
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>, problems: &mut Vec<String>) {
+        let strings = [
+            ("TOKEN", &mut self.token),
+            ("COMMAND_PREFIX", &mut self.bot.prefix),
+            ("ACTIVITY", &mut self.bot.activity),
+            ("DATA_DIR", &mut self.paths.data_dir),
+            ("INFO_DIR", &mut self.paths.info_dir),
+            ("CASH_FILE", &mut self.paths.cash_file),
+            ("STORAGE_BACKEND", &mut self.storage.backend),
+        ];
+        for (name, value) in strings {
+            if let Some(env_value) = var(name) {
+                *value = env_value;
+            }
+        }
+        if let Some(path) = var("SQLITE_PATH") {
+            self.storage.sqlite_path = Some(path);
+        }
+        if let Some(key) = var("API_KEY") {
+            self.openai.api_key = Some(key);
+        }
+        for (name, enabled) in self.features.iter_mut() {
+            let name_var = format!("FEATURE_{}", name.to_uppercase());
+            let Some(value) = var(&name_var) else {
+                continue;
+            };
+            match value.trim().to_lowercase().as_str() {
+                "true" | "1" | "on" => *enabled = true,
+                "false" | "0" | "off" => *enabled = false,
+                _ => problems.push(format!(
+                    "{} must be true or false, got \"{}\"",
+                    name_var, value
+                )),
+            }
+        }
+    }
+
+    // Check every setting, collecting all problems instead of stopping at the first one
+    // 檢查所有設定，收集所有問題而不是在第一個問題就停止
+    fn validate(&mut self, path: &str, problems: &mut Vec<String>) {
+        if self.token.trim().is_empty() {
+            problems.push(format!(
+                "no Discord bot token, set `token` in {} or the TOKEN environment variable",
+                path
+            ));
+        }
+        if self.bot.prefix.is_empty() || self.bot.prefix.chars().any(char::is_whitespace) {
+            problems.push("bot.prefix must not be empty or contain spaces".to_string());
+        }
+        if self.bot.activity.chars().count() > MAX_ACTIVITY_LEN {
+            problems.push(format!(
+                "bot.activity must be at most {} characters",
+                MAX_ACTIVITY_LEN
+            ));
+        }
+        let paths = [
+            ("paths.data_dir", &self.paths.data_dir),
+            ("paths.info_dir", &self.paths.info_dir),
+            ("paths.cash_file", &self.paths.cash_file),
+        ];
+        for (name, value) in paths {
+            if value.trim().is_empty() {
+                problems.push(format!("{} must not be empty", name));
+            }
+        }
+        self.storage.backend = self.storage.backend.trim().to_lowercase();
+        if !matches!(self.storage.backend.as_str(), "json" | "sqlite") {
+            problems.push(format!(
+                "storage.backend must be \"json\" or \"sqlite\", got \"{}\"",
+                self.storage.backend
+            ));
+        }
+        if self
+            .storage
+            .sqlite_path
+            .as_ref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            problems.push("storage.sqlite_path must not be empty when set".to_string());
+        }
+        if let Some(legacy_guild) = &self.legacy_guild {
+            legacy_guild.validate(problems);
+        }
+        // An empty key in the file means no key
+        // 文件中的空金鑰視為沒有金鑰
+        if self
+            .openai
+            .api_key
+            .as_ref()
+            .is_some_and(|key| key.trim().is_empty())
+        {
+            self.openai.api_key = None;
+        }
+    }
+}
+
+// Read an environment variable, treating an empty one as unset
+// 讀取環境變數，空值視為未設定
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+// Parse the configuration file, recording a syntax error and falling back to the defaults so
+// the environment variables and the remaining settings are still checked
+// 解析設定文件，語法錯誤時記錄問題並改用預設值，以便仍然檢查環境變數與其餘設定
+fn parse(content: &str, path: &str, problems: &mut Vec<String>) -> Config {
+    toml::from_str(content).unwrap_or_else(|err| {
+        problems.push(format!("{}: {}", path, err));
+        Config::default()
+    })
+}
+
+// Read the configuration file named by `CONFIG_PATH` (config.toml by default), apply the
+// environment variables and validate the result. Returns every problem found
+// 讀取 `CONFIG_PATH` 指定的設定文件（預設為 config.toml），套用環境變數並驗證結果。
+// 返回找到的所有問題
+pub fn init() -> Result<(), Vec<String>> {
+    let explicit_path = env_var("CONFIG_PATH");
+    let path = explicit_path
+        .clone()
+        .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+    let mut problems = Vec::new();
+    let mut config = match fs::read_to_string(&path) {
+        Ok(content) => parse(&content, &path, &mut problems),
+        // Without a file the defaults and environment variables are used, as before the file existed
+        // 沒有文件時使用預設值與環境變數，與加入設定文件之前相同
+        Err(err) if err.kind() == io::ErrorKind::NotFound && explicit_path.is_none() => {
+            Config::default()
+        }
+        Err(err) => {
+            problems.push(format!("{}: {}", path, err));
+            Config::default()
+        }
+    };
+
+    config.apply_env(env_var, &mut problems);
+    config.validate(&path, &mut problems);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    if config.features.openai && !config.openai_enabled() {
+        println!(
+            "{} {}",
+            system_output(),
+            "No OpenAI API key is configured, the OpenAI commands are disabled".yellow()
+        );
+    }
+    CONFIG
+        .set(config)
+        .map_err(|_| vec!["configuration is already loaded".to_string()])
+}
+
+// The configuration loaded by `init`
+// 由 `init` 加載的設定
+pub fn config() -> &'static Config {
+    CONFIG.get().expect("configuration is not loaded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "config.toml";
+
+    // Environment variables given to `apply_env` instead of the process environment
+    // 提供給 `apply_env` 的環境變數，取代行程的環境
+    fn vars<'a>(pairs: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            pairs
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    // Parse a file, apply the variables and validate, as `init` does
+    // 與 `init` 相同，解析文件、套用變數並驗證
+    fn load(content: &str, env: &[(&str, &str)]) -> (Config, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut config = parse(content, PATH, &mut problems);
+        config.apply_env(vars(env), &mut problems);
+        config.validate(PATH, &mut problems);
+        (config, problems)
+    }
+
+    #[test]
+    fn defaults_with_token_are_valid() {
+        let (config, problems) = load("", &[("TOKEN", "abc")]);
+        assert!(problems.is_empty(), "{problems:?}");
+        assert_eq!(config.bot.prefix, "!");
+        assert_eq!(config.storage.backend, "json");
+        assert_eq!(config.data_path("reminders.json"), "assets/reminders.json");
+        assert_eq!(config.sqlite_path(), "assets/storage.db");
+    }
+
+    #[test]
+    fn env_overrides_the_file() {
+        let file = r#"
+            token = "from-file"
+            [bot]
+            prefix = "?"
+            [paths]
+            data_dir = "data"
+            [storage]
+            backend = "json"
+            [openai]
+            api_key = "file-key"
+        "#;
+        let (config, problems) = load(
+            file,
+            &[
+                ("TOKEN", "from-env"),
+                ("COMMAND_PREFIX", "$"),
+                ("STORAGE_BACKEND", "SQLite"),
+                ("SQLITE_PATH", "/var/lib/bot.db"),
+                ("API_KEY", "env-key"),
+            ],
+        );
+        assert!(problems.is_empty(), "{problems:?}");
+        assert_eq!(config.token, "from-env");
+        assert_eq!(config.bot.prefix, "$");
+        // Settings without a variable keep the file's value
+        // 沒有對應變數的設定保留文件中的值
+        assert_eq!(config.paths.data_dir, "data");
+        assert_eq!(config.storage.backend, "sqlite");
+        assert_eq!(config.sqlite_path(), "/var/lib/bot.db");
+        assert_eq!(config.openai.api_key.as_deref(), Some("env-key"));
+    }
+
+    #[test]
+    fn feature_toggles() {
+        let file = r#"
+            token = "abc"
+            [features]
+            cash = false
+            voice = false
+        "#;
+        let (config, problems) = load(
+            file,
+            &[
+                ("FEATURE_CASH", "on"),
+                ("FEATURE_BAN", "0"),
+                ("FEATURE_ANTI_TIKTOK", "False"),
+            ],
+        );
+        assert!(problems.is_empty(), "{problems:?}");
+        assert!(config.features.cash);
+        assert!(!config.features.ban);
+        assert!(!config.features.anti_tiktok);
+        assert!(!config.features.voice);
+        assert!(config.features.reminders);
+
+        let (_, problems) = load(file, &[("FEATURE_VOICE", "maybe")]);
+        assert_eq!(
+            problems,
+            ["FEATURE_VOICE must be true or false, got \"maybe\""]
+        );
+    }
+
+    #[test]
+    fn openai_is_disabled_without_a_key() {
+        let (config, _) = load("token = \"abc\"", &[]);
+        assert!(config.features.openai);
+        assert!(!config.openai_enabled());
+
+        // An empty key in the file counts as no key
+        // 文件中的空金鑰視為沒有金鑰
+        let (config, _) = load("token = \"abc\"\n[openai]\napi_key = \" \"", &[]);
+        assert_eq!(config.openai.api_key, None);
+        assert!(!config.openai_enabled());
+
+        let (config, _) = load("token = \"abc\"", &[("API_KEY", "key")]);
+        assert!(config.openai_enabled());
+        let (config, _) = load(
+            "token = \"abc\"",
+            &[("API_KEY", "key"), ("FEATURE_OPENAI", "off")],
+        );
+        assert!(!config.openai_enabled());
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let file = r#"
+            [bot]
+            prefix = "! "
+            [paths]
+            info_dir = ""
+            [storage]
+            backend = "postgres"
+            sqlite_path = ""
+        "#;
+        let activity = "a".repeat(MAX_ACTIVITY_LEN + 1);
+        let (_, problems) = load(file, &[("ACTIVITY", &activity)]);
+        assert_eq!(problems.len(), 6, "{problems:?}");
+        assert!(problems[0].starts_with("no Discord bot token"));
+        assert_eq!(
+            problems[1],
+            "bot.prefix must not be empty or contain spaces"
+        );
+        assert_eq!(problems[2], "bot.activity must be at most 128 characters");
+        assert_eq!(problems[3], "paths.info_dir must not be empty");
+        assert_eq!(
+            problems[4],
+            "storage.backend must be \"json\" or \"sqlite\", got \"postgres\""
+        );
+        assert_eq!(
+            problems[5],
+            "storage.sqlite_path must not be empty when set"
+        );
+    }
+
+    #[test]
+    fn syntax_error_still_checks_env_and_settings() {
+        let (config, problems) = load(
+            "token = \"abc\"\n[bot\nprefix = \"?\"",
+            &[("COMMAND_PREFIX", "a b"), ("FEATURE_CASH", "nope")],
+        );
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].starts_with("config.toml: "));
+        assert_eq!(
+            problems[1],
+            "FEATURE_CASH must be true or false, got \"nope\""
+        );
+        // The file was not read, so its token is missing too
+        // 文件未被讀取，因此其中的令牌也缺少
+        assert!(problems[2].starts_with("no Discord bot token"));
+        assert_eq!(
+            problems[3],
+            "bot.prefix must not be empty or contain spaces"
+        );
+        assert_eq!(config.paths.data_dir, "assets");
+    }
+
+    #[test]
+    fn legacy_guild_is_opt_in() {
+        let (config, _) = load("token = \"abc\"", &[]);
+        assert!(config.legacy_guild.is_none());
+    }
+
+    #[test]
+    fn legacy_guild_writes_only_listed_settings() {
+        let file = r#"
+            token = "abc"
+            [legacy_guild]
+            guild_id = 10
+            voice_log_channel = 20
+            tiktok_refuse = true
+            ban_protected_user = 30
+        "#;
+        let (config, problems) = load(file, &[]);
+        assert!(problems.is_empty(), "{problems:?}");
+        let legacy_guild = config.legacy_guild.unwrap();
+
+        let guild_id = GuildId::new(10);
+        let mut settings = HashMap::from([(
+            guild_id,
+            GuildSettings {
+                honeypot_channel: Some(ChannelId::new(40)),
+                tiktok_refuse: false,
+                ..Default::default()
+            },
+        )]);
+        assert!(legacy_guild.apply(&mut settings));
+        let guild = &settings[&guild_id];
+        assert_eq!(guild.voice_log_channel, Some(ChannelId::new(20)));
+        assert!(guild.tiktok_refuse);
+        assert_eq!(guild.ban_protected_user, Some(UserId::new(30)));
+        // Settings not listed are left to /config
+        // 未列出的設定交由 /config 管理
+        assert_eq!(guild.honeypot_channel, Some(ChannelId::new(40)));
+        assert_eq!(guild.voice_ban_channel, None);
+        // Nothing to save when the settings already match
+        // 設定已經相符時不需要保存
+        assert!(!legacy_guild.apply(&mut settings));
+        assert_eq!(settings.len(), 1);
+    }
+
+    #[test]
+    fn legacy_guild_needs_ids() {
+        let file = "token = \"abc\"\n[legacy_guild]\nhoneypot_channel = 0\ntiktok_refuse = true";
+        let (_, problems) = load(file, &[]);
+        assert_eq!(
+            problems,
+            [
+                "legacy_guild.guild_id must be a Discord ID",
+                "legacy_guild.honeypot_channel must be a Discord ID",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let (_, problems) = load("[bot]\nprefx = \"?\"", &[("TOKEN", "abc")]);
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("prefx"), "{problems:?}");
+    }
+}
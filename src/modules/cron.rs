@@ -0,0 +1,316 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+use super::reminder::local_to_utc;
+
+// How far ahead to look for the next match, enough for "0 0 29 2 *" across leap years
+// 往後尋找下一次觸發的範圍，足以涵蓋跨閏年的「0 0 29 2 *」
+const SEARCH_DAYS: u64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// A parsed standard 5-field cron expression: minute hour day-of-month month day-of-week
+// 解析後的標準 5 欄位 cron 表達式：分 時 日 月 星期
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    // 0 is Sunday, 7 is folded into 0
+    // 0 為星期日，7 會被轉換為 0
+    days_of_week: Vec<u32>,
+    // Whether the day fields leave out some days; "*/1" or "1-31" restrict nothing
+    // 日期欄位是否排除了某些日期；「*/1」或「1-31」不算限制
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    // Parse an expression such as "0 9 * * 1-5" or "*/15 8-18 * * mon-fri"
+    // 解析表達式，例如「0 9 * * 1-5」或「*/15 8-18 * * mon-fri」
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(format!(
+                "cron 表達式需要 5 個欄位 (分 時 日 月 星期)，但 '{}' 有 {} 個",
+                expr,
+                fields.len()
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, "星期", 0, 7, &WEEKDAY_NAMES)?;
+        for day in days_of_week.iter_mut() {
+            *day %= 7;
+        }
+        days_of_week.sort_unstable();
+        days_of_week.dedup();
+        let days_of_month = parse_field(day_of_month, "日", 1, 31, &[])?;
+
+        Ok(CronSchedule {
+            minutes: parse_field(minute, "分", 0, 59, &[])?,
+            hours: parse_field(hour, "時", 0, 23, &[])?,
+            months: parse_field(month, "月", 1, 12, &MONTH_NAMES)?,
+            day_of_month_restricted: days_of_month.len() < 31,
+            day_of_week_restricted: days_of_week.len() < 7,
+            days_of_month,
+            days_of_week,
+        })
+    }
+
+    // Check whether the expression fires at some time on the given local date;
+    // like standard cron, restricting both day fields matches either of them
+    // 檢查表達式是否會在指定的本地日期觸發；
+    // 與標準 cron 相同，兩個日期欄位都有限制時符合其一即可
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let day_of_month = self.days_of_month.contains(&date.day());
+        let day_of_week = self
+            .days_of_week
+            .contains(&date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    // Compute the first fire instant strictly after the given instant, in the given timezone,
+    // leaving out dates for which `skip` returns true
+    // 計算在指定時間點之後、於指定時區的第一次觸發時間，略過 `skip` 返回 true 的日期
+    pub fn next_after(
+        &self,
+        tz: &Tz,
+        after: DateTime<Utc>,
+        until: Option<NaiveDate>,
+        skip: impl Fn(&NaiveDate) -> bool,
+    ) -> Option<DateTime<Utc>> {
+        let start = after.with_timezone(tz).date_naive();
+        for offset in 0..=SEARCH_DAYS {
+            let date = start.checked_add_days(Days::new(offset))?;
+            if until.is_some_and(|until| date > until) {
+                return None;
+            }
+            if !self.matches_date(date) || skip(&date) {
+                continue;
+            }
+            for hour in &self.hours {
+                for minute in &self.minutes {
+                    let time = NaiveTime::from_hms_opt(*hour, *minute, 0)?;
+                    let fire_at = local_to_utc(tz, date, time);
+                    if fire_at > after {
+                        return Some(fire_at);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+// Parse one field into the sorted list of values it matches
+// 將單一欄位解析為其符合的值（已排序）
+fn parse_field(
+    field: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<Vec<u32>, String> {
+    let invalid = |part: &str| format!("無效的{}欄位：'{}'", label, part);
+    let mut values = Vec::new();
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid(part))?;
+                if step == 0 {
+                    return Err(format!("{}欄位的間隔不能為 0：'{}'", label, part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        // "?" is the wildcard some cron dialects use for an unused day field
+        // 「?」是部分 cron 方言用於不使用的日期欄位的萬用字元
+        let (start, end) = match range {
+            "*" | "?" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(start, names, min).ok_or_else(|| invalid(part))?,
+                    parse_value(end, names, min).ok_or_else(|| invalid(part))?,
+                ),
+                None => {
+                    let value = parse_value(range, names, min).ok_or_else(|| invalid(part))?;
+                    // "5/15" means from 5 to the end of the field every 15
+                    // 「5/15」表示從 5 開始到欄位最大值，每 15 一次
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+
+        if start < min || end > max {
+            return Err(format!(
+                "{}欄位超出範圍：'{}'，需為 {}-{}",
+                label, part, min, max
+            ));
+        }
+        if start > end {
+            return Err(format!("{}欄位的範圍起點大於終點：'{}'", label, part));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+// Parse a number or a name such as "jan" or "mon"; names are numbered from `offset`
+// 解析數字或名稱，例如「jan」或「mon」；名稱從 `offset` 開始編號
+fn parse_value(value: &str, names: &[&str], offset: u32) -> Option<u32> {
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+    let value = value.to_lowercase();
+    names
+        .iter()
+        .position(|name| *name == value)
+        .map(|index| index as u32 + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        date(y, m, d).and_hms_opt(h, min, 0).unwrap().and_utc()
+    }
+
+    fn field(input: &str, min: u32, max: u32) -> Vec<u32> {
+        parse_field(input, "", min, max, &[]).unwrap()
+    }
+
+    #[test]
+    fn fields_accept_lists_ranges_and_steps() {
+        assert_eq!(field("*/15", 0, 59), vec![0, 15, 30, 45]);
+        assert_eq!(field("5/20", 0, 59), vec![5, 25, 45]);
+        assert_eq!(field("1-5,3,10", 0, 23), vec![1, 2, 3, 4, 5, 10]);
+        assert_eq!(field("8-18/5", 0, 23), vec![8, 13, 18]);
+    }
+
+    #[test]
+    fn names_and_sunday_as_seven() {
+        let cron = CronSchedule::parse("0 9 * jan-mar sat-7").unwrap();
+        assert_eq!(cron.months, vec![1, 2, 3]);
+        assert_eq!(cron.days_of_week, vec![0, 6]);
+        assert_eq!(
+            CronSchedule::parse("0 9 * * SUN").unwrap().days_of_week,
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expr in [
+            "0 9 * *",
+            "0 9 * * * *",
+            "60 9 * * *",
+            "0 24 * * *",
+            "0 9 0 * *",
+            "0 9 * 13 *",
+            "0 9 * * 8",
+            "*/0 9 * * *",
+            "0 9 5-1 * *",
+            "0 9 * foo *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn both_day_fields_restricted_match_either() {
+        // The 1st of the month or any Monday
+        // 每月 1 日或任何星期一
+        let cron = CronSchedule::parse("0 9 1 * 1").unwrap();
+        assert!(cron.matches_date(date(2025, 3, 1)));
+        assert!(cron.matches_date(date(2025, 3, 3)));
+        assert!(!cron.matches_date(date(2025, 3, 4)));
+    }
+
+    #[test]
+    fn wildcard_steps_do_not_restrict_the_day() {
+        // "*/1" covers every day of the month, so only Mondays match
+        // 「*/1」涵蓋每月每一天，因此只有星期一符合
+        let cron = CronSchedule::parse("0 9 */1 * 1").unwrap();
+        assert!(cron.matches_date(date(2025, 3, 3)));
+        assert!(!cron.matches_date(date(2025, 3, 4)));
+
+        let cron = CronSchedule::parse("0 9 ? * mon").unwrap();
+        assert!(cron.matches_date(date(2025, 3, 3)));
+        assert!(!cron.matches_date(date(2025, 3, 4)));
+
+        let cron = CronSchedule::parse("0 9 15 * 0-6").unwrap();
+        assert!(cron.matches_date(date(2025, 3, 15)));
+        assert!(!cron.matches_date(date(2025, 3, 16)));
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        let cron = CronSchedule::parse("0,30 9 * * mon-fri").unwrap();
+        let tz: Tz = "UTC".parse().unwrap();
+        // Friday 09:00 is followed by 09:30, then Monday 09:00
+        // 星期五 09:00 之後是 09:30，接著是星期一 09:00
+        let friday = utc(2025, 3, 7, 9, 0);
+        assert_eq!(
+            cron.next_after(&tz, friday, None, |_| false),
+            Some(utc(2025, 3, 7, 9, 30))
+        );
+        assert_eq!(
+            cron.next_after(&tz, utc(2025, 3, 7, 9, 30), None, |_| false),
+            Some(utc(2025, 3, 10, 9, 0))
+        );
+    }
+
+    #[test]
+    fn next_after_uses_the_timezone_and_skips_dates() {
+        let cron = CronSchedule::parse("0 9 * * *").unwrap();
+        let tz: Tz = "Asia/Taipei".parse().unwrap();
+        let after = utc(2025, 3, 5, 0, 0);
+        assert_eq!(
+            cron.next_after(&tz, after, None, |_| false),
+            Some(utc(2025, 3, 5, 1, 0))
+        );
+        assert_eq!(
+            cron.next_after(&tz, after, None, |d| *d == date(2025, 3, 5)),
+            Some(utc(2025, 3, 6, 1, 0))
+        );
+        assert_eq!(
+            cron.next_after(&tz, after, Some(date(2025, 3, 4)), |_| false),
+            None
+        );
+    }
+
+    #[test]
+    fn leap_day_is_found_years_ahead() {
+        let cron = CronSchedule::parse("0 0 29 2 *").unwrap();
+        let tz: Tz = "UTC".parse().unwrap();
+        assert_eq!(
+            cron.next_after(&tz, utc(2024, 3, 1, 0, 0), None, |_| false),
+            Some(utc(2028, 2, 29, 0, 0))
+        );
+    }
+}
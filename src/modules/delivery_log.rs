@@ -0,0 +1,54 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::collections::{HashMap, VecDeque};
+
+use serenity::{all::GuildId, http::HttpError, Error as SerenityError};
+
+use crate::DeliveryRecord;
+
+// Records kept per guild, the oldest ones are dropped first
+// 每個伺服器保留的紀錄數量，最舊的紀錄會先被移除
+pub const MAX_RECORDS_PER_GUILD: usize = 200;
+
+// Discord error code of a channel that no longer exists
+// 頻道已不存在時 Discord 回傳的錯誤代碼
+const UNKNOWN_CHANNEL: isize = 10003;
+
+// Append a record to the log of a guild, dropping the oldest beyond the limit
+// 將紀錄加入伺服器的發送紀錄，超過上限時移除最舊的紀錄
+pub fn push_record(
+    log: &mut HashMap<GuildId, VecDeque<DeliveryRecord>>,
+    guild_id: GuildId,
+    record: DeliveryRecord,
+) {
+    let records = log.entry(guild_id).or_default();
+    records.push_back(record);
+    while records.len() > MAX_RECORDS_PER_GUILD {
+        records.pop_front();
+    }
+}
+
+// Check whether a send error may succeed when retried: network errors, rate limits
+// and server errors are transient, missing permissions or invalid messages are not
+// 檢查發送錯誤重試後是否可能成功：網路錯誤、速率限制與伺服器錯誤屬於暫時性錯誤，
+// 缺少權限或訊息無效則不是
+pub fn is_transient(err: &SerenityError) -> bool {
+    match err {
+        SerenityError::Http(HttpError::UnsuccessfulRequest(response)) => {
+            let status = response.status_code.as_u16();
+            status == 429 || status >= 500
+        }
+        SerenityError::Http(HttpError::Request(_)) | SerenityError::Io(_) => true,
+        _ => false,
+    }
+}
+
+// Check whether a send error means the channel was deleted
+// 檢查發送錯誤是否表示頻道已被刪除
+pub fn is_unknown_channel(err: &SerenityError) -> bool {
+    matches!(
+        err,
+        SerenityError::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == UNKNOWN_CHANNEL
+    )
+}
@@ -1,33 +1,27 @@
 use std::{
-    collections::HashMap,
-    env,
+    collections::{HashMap, VecDeque},
     error::Error,
-    fs::{self, File, OpenOptions},
-    io::{self, BufRead, BufReader, Write},
-    path::Path,
 };
 
 use async_openai::config::OpenAIConfig;
 use colored::*;
 use serenity::{
     all::{
-        ChannelId, CommandInteraction, CreateInteractionResponse, CreateInteractionResponseMessage,
-        GuildId, Message,
+        Attachment, CommandInteraction, CreateInteractionResponse,
+        CreateInteractionResponseMessage, GuildId, Message,
     },
     prelude::*,
 };
 
-use crate::{commands, Reminder};
-
-// Type alias for a simplified version of the Reminder structure
-// Reminder 結構的簡化版本的類型別名
-type ArcLessReminder = HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>;
+use super::config::config;
+use super::reminder::assign_missing_ids;
+use super::storage::{storage, ReminderMap};
+use crate::{commands, DeliveryRecord, GuildSettings, GuildSettingsMap};
 
 // Constants definition
 // 常量定義
 const SYSTEM_OUTPUT: &str = "[SYSTEM_OUTPUT]:";
 const ERROR_OUTPUT: &str = "[ERROR]:";
-const INFO_PATH: &str = "./info/";
 
 // Return blue colored system output prefix
 // 返回藍色的系統輸出前綴
@@ -44,14 +38,24 @@ pub fn error_output() -> ColoredString {
 // Create OpenAI configuration
 // 創建 OpenAI 配置
 pub fn openai_config() -> OpenAIConfig {
-    let api = env::var("API_KEY").unwrap();
+    let api = config().openai.api_key.clone().unwrap_or_default();
     OpenAIConfig::new().with_api_key(api)
 }
 
 // Return info path
 // 返回信息路徑
 pub fn info_path() -> String {
-    INFO_PATH.to_string()
+    format!("{}/", config().paths.info_dir.trim_end_matches('/'))
+}
+
+// Check whether the OpenAI commands are enabled, responding when not
+// 檢查 OpenAI 命令是否啟用，未啟用時回應
+pub async fn check_openai(ctx: &Context, command: &CommandInteraction) -> bool {
+    if !config().openai_enabled() {
+        interaction_response(ctx, command, ">> OpenAI 功能未啟用".to_string(), true).await;
+        return false;
+    }
+    true
 }
 
 // Handle interaction response
@@ -74,21 +78,69 @@ pub async fn interaction_response(
 // Check user permissions
 // 檢查用戶權限
 pub async fn check_permission(ctx: &Context, command: &CommandInteraction) -> bool {
-    if let Some(permissions) = command.member.clone().unwrap().permissions {
-        if !permissions.administrator() {
-            let data = CreateInteractionResponseMessage::new()
-                .content("你沒有許可權使用指令")
-                .ephemeral(true);
-            let builder = CreateInteractionResponse::Message(data);
-            if let Err(err) = command.create_response(&ctx.http, builder).await {
-                println!("Cannot respond to slash command: {err}");
-            }
-            return false;
-        }
+    if !has_admin_permission(command) {
+        deny_permission(ctx, command).await;
+        return false;
+    }
+    true
+}
+
+// Check whether the user may create and manage every reminder, responding when not
+// 檢查用戶是否可以建立與管理所有提醒，沒有權限時回應
+pub async fn check_reminder_permission(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild_settings: &GuildSettingsMap,
+) -> bool {
+    if !is_reminder_manager(command, &*guild_settings.read().await) {
+        deny_permission(ctx, command).await;
+        return false;
     }
     true
 }
 
+// Check whether the command user is an administrator or has the guild's reminder manager role
+// 檢查指令使用者是否為管理員或擁有伺服器的提醒管理身分組
+pub fn is_reminder_manager(
+    command: &CommandInteraction,
+    settings: &HashMap<GuildId, GuildSettings>,
+) -> bool {
+    if has_admin_permission(command) {
+        return true;
+    }
+    let manager_role = command
+        .guild_id
+        .and_then(|guild_id| settings.get(&guild_id))
+        .and_then(|settings| settings.manager_role);
+    match (manager_role, &command.member) {
+        (Some(role_id), Some(member)) => member.roles.contains(&role_id),
+        _ => false,
+    }
+}
+
+// Tell the user they may not use the command
+// 告知用戶沒有使用指令的權限
+async fn deny_permission(ctx: &Context, command: &CommandInteraction) {
+    let data = CreateInteractionResponseMessage::new()
+        .content("你沒有許可權使用指令")
+        .ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    if let Err(err) = command.create_response(&ctx.http, builder).await {
+        println!("Cannot respond to slash command: {err}");
+    }
+}
+
+// Check whether the command user is an administrator without responding.
+// Without a member or its permissions the user is not treated as one
+// 檢查指令使用者是否為管理員（不回應互動）。沒有成員或權限資訊時不視為管理員
+pub fn has_admin_permission(command: &CommandInteraction) -> bool {
+    command
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.administrator())
+}
+
 pub async fn is_user_admin(ctx: &Context, msg: &Message) -> serenity::Result<bool> {
     let member = msg.guild_id.unwrap().member(&ctx.http, msg.author.id).await?;
 
@@ -99,48 +151,62 @@ pub async fn is_user_admin(ctx: &Context, msg: &Message) -> serenity::Result<boo
     }
 }
 
-// Load reminders from file
-// 從文件加載提醒
-pub fn load_reminders_from_file() -> Result<ArcLessReminder, Box<dyn std::error::Error>> {
-    let file_content = fs::read_to_string("assets/reminders.json")?;
-    let reminders: HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>> =
-        serde_json::from_str(&file_content)?;
+// Download a text attachment, refusing files over `max_bytes` or not in UTF-8
+// 下載文字附件，拒絕超過 `max_bytes` 或非 UTF-8 的文件
+pub async fn download_text(file: &Attachment, max_bytes: u32) -> Result<String, String> {
+    if file.size > max_bytes {
+        return Err(format!("文件大小不能超過 {} KB", max_bytes / 1024));
+    }
+    let bytes = file
+        .download()
+        .await
+        .map_err(|err| format!("無法下載文件：{}", err))?;
+    String::from_utf8(bytes).map_err(|_| "文件必須是 UTF-8 文字".to_string())
+}
+
+// Load reminders from storage
+// 從存儲加載提醒
+pub fn load_reminders() -> Result<ReminderMap, Box<dyn Error + Send + Sync>> {
+    let mut reminders = storage().load_reminders()?;
+    assign_missing_ids(&mut reminders);
     Ok(reminders)
 }
 
-// Save reminders to file
-// 保存提醒到文件
-pub fn save_reminders_to_file(
-    reminders: &HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>,
-) -> Result<(), Box<dyn Error>> {
-    let json_content = serde_json::to_string(reminders)?;
-    std::fs::write("assets/reminders.json", json_content)?;
-    Ok(())
+// Save reminders to storage
+// 保存提醒到存儲
+pub fn save_reminders(reminders: &ReminderMap) -> Result<(), Box<dyn Error + Send + Sync>> {
+    storage().save_reminders(reminders)
+}
+
+// Save per-guild settings to storage
+// 保存伺服器設定到存儲
+pub fn save_guild_settings(
+    settings: &HashMap<GuildId, GuildSettings>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    storage().save_guild_settings(settings)
+}
+
+// Save the delivery log of reminders to storage
+// 保存提醒的發送紀錄到存儲
+pub fn save_delivery_log(
+    log: &HashMap<GuildId, VecDeque<DeliveryRecord>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    storage().save_delivery_log(log)
 }
 
 // Register commands for all guilds
 // 為所有 guild 註冊命令
 pub async fn register_commands_guild_ids(ctx: &Context) {
-    let file_path = "assets/guild_id.txt";
-
-    // Read all guild_ids from guild_id.txt
-    // 讀取 guild_id.txt 內的所有 guild_id
-    let file = match File::open(file_path) {
-        Ok(file) => file,
+    // Read all registered guild_ids from storage
+    // 從存儲讀取所有已註冊的 guild_id
+    let guild_ids = match storage().load_guild_ids() {
+        Ok(guild_ids) => guild_ids,
         Err(err) => {
-            println!("{} Failed to open guild_id.txt: {:?}", error_output(), err);
+            println!("{} Failed to load guild ids: {:?}", error_output(), err);
             return;
         }
     };
 
-    let reader = BufReader::new(file);
-    let guild_ids: Vec<GuildId> = reader
-        .lines()
-        .map_while(Result::ok)
-        .filter_map(|line| line.parse::<u64>().ok())
-        .map(GuildId::from)
-        .collect();
-
     for guild_id in guild_ids {
         // Register commands
         // 註冊指令
@@ -156,39 +222,65 @@ pub async fn register_commands_guild_ids(ctx: &Context) {
 // Register commands for a specific guild
 // 為特定 guild 註冊命令
 pub async fn register_commands(ctx: &Context, guild_id: &GuildId, for_guilds: bool) {
-    let command = guild_id
-        .set_commands(
-            ctx,
-            vec![
-                // Basic commands
-                // 基本命令
-                commands::base::info::register(),
-                commands::base::ping::register(),
-                commands::base::update::register(),
-                // Reminder related commands
-                // 提醒相關命令
-                commands::reminder::remind::register(),
-                commands::reminder::look::register(),
-                commands::reminder::rm_remind::register(),
-                // OpenAI related commands
-                // OpenAI 相關命令
-                commands::openai::chat::register(),
-                commands::openai::image::register(),
-                commands::openai::model_list::register(),
-                // Ban related commands
-                // 封禁相關命令
-                commands::ban::add_ban::register(),
-                commands::ban::un_ban::register(),
-                commands::ban::remove_block::register(),
-                commands::ban::display_block_list::register(),
-                // Other feature commands
-                // 其他功能命令
-                commands::cash::register(),
-                commands::anti_tiktok::tiktok_msg_add::register(),
+    let features = &config().features;
+    // Basic commands
+    // 基本命令
+    let mut command_list = vec![
+        commands::base::info::register(),
+        commands::base::ping::register(),
+        commands::base::update::register(),
+        commands::base::timezone::register(),
+        commands::base::mention_everyone::register(),
+        commands::base::reminder_manager::register(),
+        commands::base::config::register(),
+    ];
+    // Reminder related commands
+    // 提醒相關命令
+    if features.reminders {
+        command_list.extend([
+            commands::reminder::remind::register(),
+            commands::reminder::remind_once::register(),
+            commands::reminder::remindme::register(),
+            commands::reminder::look::register(),
+            commands::reminder::rm_remind::register(),
+            commands::reminder::edit_remind::register(),
+            commands::reminder::pause_remind::register(),
+            commands::reminder::resume_remind::register(),
+            commands::reminder::holiday::register(),
+            commands::reminder::export_ics::register(),
+            commands::reminder::import_ics::register(),
+            commands::reminder::delivery_log::register(),
+        ]);
+    }
+    // OpenAI related commands, only with an API key
+    // OpenAI 相關命令，只在有 API 金鑰時註冊
+    if config().openai_enabled() {
+        command_list.extend([
+            commands::openai::chat::register(),
+            commands::openai::image::register(),
+            commands::openai::model_list::register(),
+        ]);
+    }
+    // Ban related commands
+    // 封禁相關命令
+    if features.ban {
+        command_list.extend([
+            commands::ban::add_ban::register(),
+            commands::ban::un_ban::register(),
+            commands::ban::remove_block::register(),
+            commands::ban::display_block_list::register(),
+        ]);
+    }
+    // Other feature commands
+    // 其他功能命令
+    if features.cash {
+        command_list.push(commands::cash::register());
+    }
+    if features.anti_tiktok {
+        command_list.push(commands::anti_tiktok::tiktok_msg_add::register());
+    }
 
-            ],
-        )
-        .await;
+    let command = guild_id.set_commands(ctx, command_list).await;
     match command {
         Ok(cmds) => {
             let command_names: Vec<_> = cmds.iter().map(|cmd| &cmd.name).collect();
@@ -200,9 +292,13 @@ pub async fn register_commands(ctx: &Context, guild_id: &GuildId, for_guilds: bo
                     command_names,
                     guild_id
                 );
-                // Save guild_id to file
-                // 儲存 guild_id 到文件
-                save_guild_id_to_file(guild_id).unwrap();
+                // Save guild_id to storage
+                // 儲存 guild_id 到存儲
+                if let Err(err) = storage().add_guild_id(*guild_id) {
+                    println!("{} Failed to save guild_id: {}", error_output(), err);
+                } else {
+                    println!("{} Saved guild_id to storage.", system_output());
+                }
             }
         }
         Err(err) => {
@@ -216,41 +312,3 @@ pub async fn register_commands(ctx: &Context, guild_id: &GuildId, for_guilds: bo
         }
     }
 }
-
-// Save guild_id to file
-// 保存 guild_id 到文件
-fn save_guild_id_to_file(guild_id: &GuildId) -> io::Result<()> {
-    let file_path = "assets/guild_id.txt";
-
-    // Read existing guild_ids
-    // 讀取已存在的 guild_id
-    let existing_ids = fs::read_to_string(file_path).unwrap_or_default();
-
-    if !existing_ids.contains(&guild_id.to_string()) {
-        // If the file doesn't contain the current guild_id, write it to the file
-        // 如果文件中不包含當前 guild_id，則寫入文件
-        let mut file = OpenOptions::new().append(true).open(file_path)?;
-
-        writeln!(file, "{}", guild_id)?;
-        println!("{} Saved guild_id to file.", system_output());
-    }
-
-    Ok(())
-}
-
-// Ensure folder and file exist
-// 確保資料夾與文件存在
-pub fn ensure_file_exists(file_path: &str) -> io::Result<()> {
-    // Ensure directory exists
-    // 確保目錄存在
-    if let Some(parent) = Path::new(file_path).parent() {
-        fs::create_dir_all(parent)?;
-    }
-    
-    // Ensure file exists
-    // 確保文件存在
-    if !Path::new(file_path).exists() {
-        File::create(file_path)?;
-    }
-    Ok(())
-}
\ No newline at end of file
@@ -0,0 +1,513 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+use super::reminder::local_to_utc;
+
+// Longest all-day event accepted, so a broken DTEND cannot expand into years of dates
+// 可接受的最長全天事件，避免錯誤的 DTEND 展開成數年的日期
+const MAX_EVENT_DAYS: u64 = 366;
+
+// Content lines longer than this many bytes are folded when writing
+// 寫入時超過此位元組數的內容行會被折行
+const MAX_LINE_BYTES: usize = 75;
+
+// Weekday codes used by RRULE and BYDAY
+// RRULE 與 BYDAY 使用的星期代碼
+const WEEKDAY_CODES: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+// One content line of an iCalendar file, e.g. "DTSTART;VALUE=DATE:20261225"
+// iCalendar 文件中的一行內容，例如「DTSTART;VALUE=DATE:20261225」
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    // Get the value of a parameter such as VALUE or TZID
+    // 取得參數的值，例如 VALUE 或 TZID
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// The properties between BEGIN:VEVENT and END:VEVENT
+// 位於 BEGIN:VEVENT 與 END:VEVENT 之間的屬性
+#[derive(Debug, Clone, Default)]
+pub struct Event {
+    pub properties: Vec<Property>,
+}
+
+impl Event {
+    // Get the first property with the given name
+    // 取得第一個符合名稱的屬性
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|prop| prop.name.eq_ignore_ascii_case(name))
+    }
+
+    // Get every property with the given name, such as several EXDATE lines;
+    // properties of nested components like VALARM are included
+    // 取得所有符合名稱的屬性，例如多行 EXDATE；
+    // 也包含 VALARM 等內嵌元件的屬性
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties
+            .iter()
+            .filter(move |prop| prop.name.eq_ignore_ascii_case(name))
+    }
+
+    // The unescaped SUMMARY of the event
+    // 事件的 SUMMARY（已還原跳脫字元）
+    pub fn summary(&self) -> Option<String> {
+        self.get("SUMMARY").map(|prop| unescape(&prop.value))
+    }
+}
+
+// Check whether the text looks like an iCalendar file
+// 檢查文字內容是否為 iCalendar 文件
+pub fn is_calendar(input: &str) -> bool {
+    input
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .to_ascii_uppercase()
+        .starts_with("BEGIN:VCALENDAR")
+}
+
+// Parse every VEVENT in the file; line numbers in errors refer to the lines before unfolding
+// 解析文件中的所有 VEVENT；錯誤訊息中的行號為展開折行前的行號
+pub fn parse_events(input: &str) -> Result<Vec<Event>, String> {
+    let mut events = Vec::new();
+    let mut current: Option<Event> = None;
+
+    for (line_no, line) in unfold(input) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let prop = parse_property(&line).ok_or_else(|| format!("第 {} 行格式錯誤", line_no))?;
+        let value = prop.value.to_ascii_uppercase();
+        match (prop.name.as_str(), value.as_str()) {
+            ("BEGIN", "VEVENT") => {
+                if current.is_some() {
+                    return Err(format!("第 {} 行：VEVENT 尚未結束", line_no));
+                }
+                current = Some(Event::default());
+            }
+            ("END", "VEVENT") => match current.take() {
+                Some(event) => events.push(event),
+                None => return Err(format!("第 {} 行：多餘的 END:VEVENT", line_no)),
+            },
+            _ => {
+                if let Some(event) = current.as_mut() {
+                    event.properties.push(prop);
+                }
+            }
+        }
+    }
+
+    if current.is_some() {
+        return Err("VEVENT 沒有對應的 END:VEVENT".to_string());
+    }
+    Ok(events)
+}
+
+// Expand the all-day events into (date, name) pairs; DTEND is exclusive as in RFC 5545.
+// Returns the dates along with how many timed events were left out
+// 將全天事件展開為 (日期, 名稱)；依 RFC 5545，DTEND 不包含在內。
+// 返回日期以及被略過的非全天事件數量
+pub fn all_day_dates(events: &[Event]) -> Result<(Vec<(NaiveDate, String)>, usize), String> {
+    let mut dates = Vec::new();
+    let mut skipped = 0;
+
+    for event in events {
+        let Some(start) = event.get("DTSTART") else {
+            skipped += 1;
+            continue;
+        };
+        let Some(start_date) = parse_date(start) else {
+            skipped += 1;
+            continue;
+        };
+        let end_date = match event.get("DTEND").and_then(parse_date) {
+            Some(end) if end > start_date => end,
+            _ => start_date + Days::new(1),
+        };
+        if (end_date - start_date).num_days() as u64 > MAX_EVENT_DAYS {
+            return Err(format!(
+                "事件 {} 的長度超過 {} 天",
+                start_date, MAX_EVENT_DAYS
+            ));
+        }
+
+        let name = event.summary().unwrap_or_default();
+        let mut date = start_date;
+        while date < end_date {
+            dates.push((date, name.clone()));
+            date = date + Days::new(1);
+        }
+    }
+
+    Ok((dates, skipped))
+}
+
+// Undo the escaping of a TEXT value
+// 還原 TEXT 值的跳脫字元
+pub fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+// Parse a DATE value such as "20261225"; DATE-TIME values are not all-day and return None
+// 解析 DATE 值，例如「20261225」；DATE-TIME 不是全天事件，返回 None
+pub fn parse_date(prop: &Property) -> Option<NaiveDate> {
+    let is_date = prop
+        .param("VALUE")
+        .is_some_and(|value| value.eq_ignore_ascii_case("DATE"))
+        || (prop.value.len() == 8 && prop.value.bytes().all(|b| b.is_ascii_digit()));
+    if !is_date {
+        return None;
+    }
+    NaiveDate::parse_from_str(prop.value.trim(), "%Y%m%d").ok()
+}
+
+// Parse a DATE-TIME value into the given timezone. UTC values end with "Z", values with an
+// unknown TZID (such as Windows zone names) and floating values are read as local to `tz`
+// 將 DATE-TIME 值解析為指定時區的時間。UTC 值以「Z」結尾，未知的 TZID
+// （例如 Windows 時區名稱）與浮動時間皆視為 `tz` 的當地時間
+pub fn parse_date_time(prop: &Property, tz: &Tz) -> Option<DateTime<Tz>> {
+    let value = prop.value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(naive.and_utc().with_timezone(tz));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let source: Tz = prop
+        .param("TZID")
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(*tz);
+    Some(local_to_utc(&source, naive.date(), naive.time()).with_timezone(tz))
+}
+
+// Split an RRULE value such as "FREQ=WEEKLY;BYDAY=MO,WE" into its parts
+// 將 RRULE 值（例如「FREQ=WEEKLY;BYDAY=MO,WE」）拆分為各部分
+pub fn rule_parts(value: &str) -> HashMap<String, String> {
+    value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim().to_ascii_uppercase()))
+        .collect()
+}
+
+// Parse a duration value such as "-PT30M" or "-P1DT2H" into signed minutes
+// 將時長值（例如「-PT30M」或「-P1DT2H」）解析為帶正負號的分鐘數
+pub fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim().to_ascii_uppercase();
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim_start_matches('+')),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut minutes = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                minutes += match (c, in_time) {
+                    ('W', false) => n * 7 * 1440,
+                    ('D', false) => n * 1440,
+                    ('H', true) => n * 60,
+                    ('M', true) => n,
+                    ('S', true) => n / 60,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then_some(sign * minutes)
+}
+
+// The RRULE code of a weekday, such as "MO"
+// 星期的 RRULE 代碼，例如「MO」
+pub fn weekday_code(weekday: Weekday) -> &'static str {
+    WEEKDAY_CODES
+        .iter()
+        .find(|(_, day)| *day == weekday)
+        .map(|(code, _)| *code)
+        .unwrap_or("MO")
+}
+
+// Parse an RRULE weekday code such as "MO"
+// 解析 RRULE 的星期代碼，例如「MO」
+pub fn parse_weekday_code(code: &str) -> Option<Weekday> {
+    WEEKDAY_CODES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(code))
+        .map(|(_, day)| *day)
+}
+
+// Format an instant as a UTC DATE-TIME value
+// 將時間點格式化為 UTC 的 DATE-TIME 值
+pub fn format_utc(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Writes an iCalendar file line by line with CRLF endings and folding
+// 以 CRLF 換行與折行逐行寫入 iCalendar 文件
+pub struct CalendarWriter {
+    output: String,
+}
+
+impl CalendarWriter {
+    // Start a calendar; `timezone` is announced for clients that read X-WR-TIMEZONE
+    // 建立行事曆；`timezone` 會以 X-WR-TIMEZONE 提供給支援的行事曆軟體
+    pub fn new(name: &str, timezone: &str) -> Self {
+        let mut writer = CalendarWriter {
+            output: String::new(),
+        };
+        writer.line("BEGIN:VCALENDAR");
+        writer.line("VERSION:2.0");
+        writer.line("PRODID:-//discord-bot-rs//reminders//ZH");
+        writer.line("CALSCALE:GREGORIAN");
+        writer.line(&format!("X-WR-CALNAME:{}", escape(name)));
+        writer.line(&format!("X-WR-TIMEZONE:{}", timezone));
+        writer
+    }
+
+    // Write one content line, folding it at character boundaries when it is too long
+    // 寫入一行內容，過長時在字元邊界折行
+    pub fn line(&mut self, line: &str) {
+        let mut width = 0;
+        for c in line.chars() {
+            if width + c.len_utf8() > MAX_LINE_BYTES {
+                self.output.push_str("\r\n ");
+                width = 1;
+            }
+            self.output.push(c);
+            width += c.len_utf8();
+        }
+        self.output.push_str("\r\n");
+    }
+
+    // Close the calendar and return its text
+    // 結束行事曆並返回其內容
+    pub fn finish(mut self) -> String {
+        self.line("END:VCALENDAR");
+        self.output
+    }
+}
+
+// Escape text for use in a TEXT value
+// 將文字跳脫以用於 TEXT 值
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Join folded lines (a line starting with a space or tab continues the previous one),
+// keeping the number of the line each logical line started on
+// 合併折行（以空白或 tab 開頭的行接續上一行），並保留每行起始的行號
+fn unfold(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in input.trim_start_matches('\u{feff}').lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+// Split a content line into name, parameters and value; a ':' inside quoted parameters is kept
+// 將內容行拆分為名稱、參數與值；引號參數中的「:」會被保留
+fn parse_property(line: &str) -> Option<Property> {
+    let mut in_quotes = false;
+    let mut colon = None;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                colon = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let colon = colon?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property(line: &str) -> Property {
+        parse_property(line).unwrap()
+    }
+
+    fn calendar(lines: &[&str]) -> String {
+        let mut writer = CalendarWriter::new("test", "UTC");
+        for line in lines {
+            writer.line(line);
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn long_lines_fold_and_unfold_unchanged() {
+        let summary = format!("SUMMARY:{}", "提醒".repeat(40));
+        let text = calendar(&["BEGIN:VEVENT", &summary, "END:VEVENT"]);
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE_BYTES));
+
+        let events = parse_events(&text).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary(), Some("提醒".repeat(40)));
+    }
+
+    #[test]
+    fn text_escaping_round_trips() {
+        let text = "a;b,c\\d\nline";
+        assert_eq!(unescape(&escape(text)), text);
+        assert_eq!(escape("a;b\nc"), "a\\;b\\nc");
+    }
+
+    #[test]
+    fn properties_keep_quoted_colons() {
+        let prop = property("DTSTART;TZID=\"Europe/Berlin\";X-NOTE=\"a:b\":20250305T090000");
+        assert_eq!(prop.name, "DTSTART");
+        assert_eq!(prop.param("tzid"), Some("Europe/Berlin"));
+        assert_eq!(prop.param("X-NOTE"), Some("a:b"));
+        assert_eq!(prop.value, "20250305T090000");
+        assert!(parse_property("no colon").is_none());
+    }
+
+    #[test]
+    fn unbalanced_events_are_errors() {
+        assert!(parse_events("BEGIN:VEVENT\r\nBEGIN:VEVENT\r\n").is_err());
+        assert!(parse_events("END:VEVENT\r\n").is_err());
+        assert!(parse_events("BEGIN:VEVENT\r\nSUMMARY:x\r\n").is_err());
+    }
+
+    #[test]
+    fn date_times_are_read_in_their_timezone() {
+        let taipei: Tz = "Asia/Taipei".parse().unwrap();
+        let at = |line: &str| {
+            parse_date_time(&property(line), &taipei)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let expected = NaiveDate::from_ymd_opt(2025, 3, 5)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap()
+            .and_utc();
+        assert_eq!(at("DTSTART:20250305T080000Z"), expected);
+        assert_eq!(at("DTSTART;TZID=Europe/London:20250305T080000"), expected);
+        // Unknown zones and floating times are local to the guild
+        // 未知時區與浮動時間視為伺服器的當地時間
+        assert_eq!(at("DTSTART;TZID=W. Europe:20250305T160000"), expected);
+        assert_eq!(at("DTSTART:20250305T160000"), expected);
+        assert!(parse_date(&property("DTSTART:20250305T160000")).is_none());
+    }
+
+    #[test]
+    fn all_day_events_expand_to_dates() {
+        let text = calendar(&[
+            "BEGIN:VEVENT",
+            "SUMMARY:Break",
+            "DTSTART;VALUE=DATE:20251224",
+            "DTEND;VALUE=DATE:20251227",
+            "END:VEVENT",
+            "BEGIN:VEVENT",
+            "SUMMARY:Meeting",
+            "DTSTART:20251224T090000",
+            "END:VEVENT",
+        ]);
+        let (dates, skipped) = all_day_dates(&parse_events(&text).unwrap()).unwrap();
+        let first = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        let expected: Vec<_> = (0..3)
+            .map(|offset| (first + Days::new(offset), "Break".to_string()))
+            .collect();
+        assert_eq!(dates, expected);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn rule_parts_and_durations() {
+        let parts = rule_parts("freq=weekly;BYDAY=mo,we;INTERVAL=2");
+        assert_eq!(parts["FREQ"], "WEEKLY");
+        assert_eq!(parts["BYDAY"], "MO,WE");
+        assert_eq!(parts["INTERVAL"], "2");
+
+        assert_eq!(parse_duration("-PT30M"), Some(-30));
+        assert_eq!(parse_duration("-P1DT2H"), Some(-1560));
+        assert_eq!(parse_duration("P1W"), Some(10080));
+        assert_eq!(parse_duration("PT1H30"), None);
+        assert_eq!(parse_duration("30M"), None);
+    }
+
+    #[test]
+    fn weekday_codes_round_trip() {
+        for (code, weekday) in WEEKDAY_CODES {
+            assert_eq!(weekday_code(weekday), code);
+            assert_eq!(parse_weekday_code(&code.to_lowercase()), Some(weekday));
+        }
+        assert_eq!(parse_weekday_code("XX"), None);
+    }
+}
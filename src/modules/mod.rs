@@ -2,6 +2,38 @@
 // 引入提醒功能模塊
 pub mod reminder;
 
+// import time_parser module
+// 引入時間解析模塊
+pub mod time_parser;
+
+// import cron module
+// 引入 cron 表達式模塊
+pub mod cron;
+
+// import ics module
+// 引入 iCalendar 文件解析與寫入模塊
+pub mod ics;
+
+// import template module
+// 引入提醒訊息模板模塊
+pub mod template;
+
+// import scheduled_event module
+// 引入同步 Discord 活動模塊
+pub mod scheduled_event;
+
+// import delivery_log module
+// 引入提醒發送紀錄模塊
+pub mod delivery_log;
+
+// import storage module
+// 引入資料存儲模塊
+pub mod storage;
+
+// import config module
+// 引入設定文件模塊
+pub mod config;
+
 // import func module
 // 引入通用功能函數模塊
 pub mod func;
@@ -17,6 +49,14 @@ pub mod anti_tiktok;
 // This module file defines the core functional structure of the bot
 // Each sub-module contains implementations of specific functionalities:
 // - reminder: handles reminder-related functions, possibly including execution logic for timed tasks
+// - time_parser: parses natural-language schedules such as "every weekday 08:30" or "明天晚上八點"
+// - cron: parses standard 5-field cron expressions and computes their fire times
+// - ics: parses and writes iCalendar (.ics) files, such as holiday calendars and reminder exports
+// - template: renders reminder messages with placeholders, mentions and embeds
+// - scheduled_event: mirrors the next occurrence of reminders as Discord scheduled events
+// - delivery_log: keeps the delivery attempts of reminders and classifies send errors
+// - storage: the storage trait every persisted store goes through, with JSON-file and SQLite backends
+// - config: the configuration file with environment variable overrides, validated at startup
 // - func: contains various general utility functions, such as file operations, permission checks, etc.
 // - bot_process: contains the main processing logic of the bot, such as command parsing and execution
 // - anti_tiktok: handles TikTok-related functions, possibly including blocking or converting TikTok links
@@ -28,6 +68,14 @@ pub mod anti_tiktok;
 // 這個模塊文件定義了機器人的核心功能結構
 // 每個子模塊包含特定功能的實現：
 // - reminder: 處理提醒相關的功能，包括定時任務的執行邏輯
+// - time_parser: 解析自然語言的提醒時間，例如 "every weekday 08:30" 或 "明天晚上八點"
+// - cron: 解析標準 5 欄位 cron 表達式並計算觸發時間
+// - ics: 解析與寫入 iCalendar (.ics) 文件，例如假日行事曆與匯出的提醒
+// - template: 以佔位符、提及與嵌入訊息產生提醒訊息
+// - scheduled_event: 將提醒的下一次觸發同步為 Discord 活動
+// - delivery_log: 保存提醒的發送紀錄並分辨發送錯誤的種類
+// - storage: 所有保存的資料都經由的存儲 trait，提供 JSON 文件與 SQLite 兩種後端
+// - config: 設定文件與覆蓋設定的環境變數，於啟動時驗證
 // - func: 包含各種通用的輔助函數，如文件操作、權限檢查等
 // - bot_process: 包含機器人的主要處理邏輯，如命令解析和執行
 // - anti_tiktok: 處理與 TikTok 相關的功能，可能包括阻止或轉換 TikTok 鏈接
@@ -1,195 +1,1244 @@
 // Import necessary modules and dependencies
 // 導入必要的模組和依賴
-use crate::modules::func::{error_output, save_reminders_to_file, system_output};
-use crate::{Reminder, Reminders};
-use chrono::{Datelike, NaiveTime, Timelike, Utc};
+use crate::commands::reminder::buttons::reminder_buttons;
+use crate::modules::cron::CronSchedule;
+use crate::modules::delivery_log::{is_transient, is_unknown_channel, push_record};
+use crate::modules::func::{error_output, save_delivery_log, save_reminders, system_output};
+use crate::modules::scheduled_event::sync_events;
+use crate::modules::template::{fill_placeholders, format_lead_time, render_message, truncate};
+use crate::{
+    CatchUpPolicy, DeliveryLog, DeliveryRecord, GuildSettings, GuildSettingsMap, LeadAlert,
+    Recurrence, Reminder, Reminders,
+};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
 use chrono_tz::Tz;
 use colored::Colorize;
 use once_cell::sync::Lazy;
-use serenity::all::{ChannelId, Http};
-use std::sync::Arc;
-use tokio::sync::Notify;
-use tokio::{
-    sync::RwLock,
-    time::{interval, Duration},
+use rand::Rng;
+use serenity::{
+    all::{
+        AutoArchiveDuration, ChannelId, CreateForumPost, CreateMessage, CreateThread, GuildId,
+        Http, UserId,
+    },
+    futures::future::join_all,
 };
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
 
 // Define Taipei timezone
 // 定義台北時區
 pub static TW: Lazy<Tz> = Lazy::new(|| "Asia/Taipei".parse().unwrap());
 
-// Define reminder storage structure
-// 定義提醒存儲結構
-#[derive(Clone, Default)]
-struct ReminderStore {
-    reminders_30_min: Arc<RwLock<Vec<(ChannelId, Reminder)>>>,
-    reminders_2_min: Arc<RwLock<Vec<(ChannelId, Reminder)>>>,
-    two_min_checking: Arc<RwLock<bool>>,
-    one_secs_checking: Arc<RwLock<bool>>,
-}
-
-impl ReminderStore {
-    // Create a new ReminderStore instance
-    // 創建一個新的 ReminderStore 實例
-    fn new() -> Self {
-        ReminderStore {
-            two_min_checking: Arc::new(RwLock::new(false)),
-            one_secs_checking: Arc::new(RwLock::new(false)),
-            ..Default::default()
+// Holidays of guilds that have none configured
+// 未設定假日的伺服器使用的空假日列表
+static NO_HOLIDAYS: BTreeMap<NaiveDate, String> = BTreeMap::new();
+
+// Furthest a recurring reminder is scanned ahead for its next occurrence
+// 週期提醒尋找下一次觸發時最多往後掃描的天數
+const MAX_SCAN_DAYS: u64 = 366 * 5;
+
+// Longest single sleep, so wall-clock jumps are noticed in time
+// 單次最長睡眠時間，確保能及時察覺系統時間跳動
+const MAX_SLEEP: Duration = Duration::from_secs(600);
+
+// Characters used for reminder IDs, without look-alikes such as 0/o and 1/l
+// 提醒 ID 使用的字元，排除 0/o、1/l 等易混淆字元
+const ID_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+const ID_LENGTH: usize = 5;
+
+// Marker prepended to reminders sent after their fire time
+// 逾時發送的提醒前方加上的標記
+pub const DELAYED_MARKER: &str = "(delayed)";
+
+// Longest thread name allowed by Discord
+// Discord 允許的討論串名稱長度上限
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+// Attempts to send a reminder before giving up, and the delay before the first retry,
+// doubled after each further attempt
+// 放棄發送提醒前的嘗試次數，以及第一次重試前的等待時間，之後每次加倍
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+// Bounds of the wait before an occurrence that failed with a transient error is sent again,
+// and how long after its fire time it is still retried
+// 因暫時性錯誤發送失敗的提醒再次發送前的等待時間範圍，以及觸發後仍會重試的時間上限
+const MIN_REQUEUE_DELAY: TimeDelta = TimeDelta::minutes(1);
+const MAX_REQUEUE_DELAY: TimeDelta = TimeDelta::minutes(30);
+const MAX_REQUEUE_AGE: TimeDelta = TimeDelta::hours(24);
+
+// An entry in the scheduler queue, ordered by its fire instant
+// 排程佇列中的項目，依觸發時間排序
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct ScheduledEntry {
+    fire_at: DateTime<Utc>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+}
+
+// Min-heap of upcoming reminders, the earliest deadline is on top
+// 即將到來的提醒的最小堆，最早到期的在最上方
+type ReminderQueue = BinaryHeap<Reverse<ScheduledEntry>>;
+
+// A reminder occurrence waiting to be sent
+// 等待發送的提醒
+struct Delivery {
+    entry: ScheduledEntry,
+    id: String,
+    message: CreateMessage,
+    dm_user: Option<UserId>,
+    // Lead alerts are sent before the occurrence and do not advance the schedule
+    // 預告在觸發前發送，不會推進排程
+    lead: bool,
+    // Thread or forum post started for this occurrence, empty to post in the channel
+    // 本次觸發建立的討論串或論壇貼文，為空表示直接發送到頻道
+    thread: Option<ThreadPost>,
+}
+
+// A thread or forum post to start, with its title already filled in
+// 要建立的討論串或論壇貼文，標題已替換佔位符
+struct ThreadPost {
+    name: String,
+    auto_archive: AutoArchiveDuration,
+    forum: bool,
+}
+
+impl Delivery {
+    // Render the reminder for this occurrence, with its Done and Snooze buttons
+    // 產生本次觸發的提醒訊息，並附上完成與延後按鈕
+    fn new(
+        entry: ScheduledEntry,
+        reminder: &Reminder,
+        tz: &Tz,
+        allow_everyone: bool,
+        prefix: Option<&str>,
+    ) -> Self {
+        let message = render_message(
+            reminder,
+            entry.fire_at.with_timezone(tz),
+            allow_everyone,
+            prefix,
+        )
+        .components(vec![reminder_buttons(
+            entry.guild_id,
+            entry.channel_id,
+            &reminder.id,
+        )]);
+        let thread = reminder.thread.as_ref().map(|thread| {
+            let fire_at = entry.fire_at.with_timezone(tz);
+            let name = fill_placeholders(&thread.title, fire_at, reminder.fire_count + 1);
+            let name = match name.trim() {
+                "" => fire_at.format("%Y-%m-%d").to_string(),
+                name => truncate(name, MAX_THREAD_NAME_CHARS),
+            };
+            ThreadPost {
+                name,
+                auto_archive: AutoArchiveDuration::from(thread.auto_archive),
+                forum: thread.forum,
+            }
+        });
+        Delivery {
+            entry,
+            id: reminder.id.clone(),
+            message,
+            dm_user: reminder.dm_user,
+            lead: false,
+            thread,
+        }
+    }
+
+    // Render a lead alert of the upcoming occurrence; placeholders refer to the occurrence
+    // 產生下一次觸發的預告訊息；佔位符使用觸發時的值
+    fn lead(
+        entry: ScheduledEntry,
+        reminder: &Reminder,
+        lead: &LeadAlert,
+        tz: &Tz,
+        allow_everyone: bool,
+    ) -> Self {
+        let message = match lead.message.as_str() {
+            "" => format!("{}後：{}", format_lead_time(lead.minutes), reminder.message),
+            text => text.to_string(),
+        };
+        let alert = Reminder {
+            message,
+            embed: None,
+            ..reminder.clone()
+        };
+        let occurrence = reminder.next_due.unwrap_or(entry.fire_at);
+        Delivery {
+            entry,
+            id: reminder.id.clone(),
+            message: render_message(&alert, occurrence.with_timezone(tz), allow_everyone, None),
+            dm_user: reminder.dm_user,
+            lead: true,
+            // Lead alerts go to the channel, the thread is started by the occurrence itself
+            // 預告發送到頻道，討論串由觸發時的提醒建立
+            thread: None,
         }
     }
 }
 
+// Result of sending one delivery after its retries
+// 一次發送在重試之後的結果
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SendOutcome {
+    Sent,
+    Failed,
+    // Every attempt failed with an error that may be transient, so the occurrence is sent again later
+    // 每次嘗試都因可能是暫時性的錯誤而失敗，稍後會再次發送
+    Retry,
+    // The reminder channel was deleted, so the reminder cannot be sent anymore
+    // 提醒頻道已被刪除，提醒無法再發送
+    ChannelGone,
+}
+
 // Main reminder task function
 // 主要的提醒任務函數
-pub async fn remind_task(http: Arc<Http>, reminders: Reminders, notify: Arc<Notify>) {
+pub async fn remind_task(
+    http: Arc<Http>,
+    reminders: Reminders,
+    guild_settings: GuildSettingsMap,
+    delivery_log: DeliveryLog,
+    notify: Arc<Notify>,
+) {
     println!(
         "{} {}",
         system_output(),
         "Reminder remind_task start".green()
     );
 
-    // Set up interval for checking reminders every 30 minutes
-    // 設置每30分鐘檢查一次提醒的間隔
-    let mut wait_time = interval(Duration::from_secs(1800));
-    let reminder_store = Arc::new(ReminderStore::new());
-
     loop {
-        tokio::select! {
-            _ = wait_time.tick() => {
-                process_reminders(&reminders, &reminder_store).await;
-
-                // Start 2-minute check if there are reminders within 30 minutes
-                // 如果30分鐘內有提醒，開始2分鐘檢查
-                if !reminder_store.reminders_30_min.read().await.is_empty()
-                    && !*reminder_store.two_min_checking.read().await
-                {
-                    *reminder_store.two_min_checking.write().await = true;
-                    tokio::spawn(check_2min_remind(
-                        Arc::clone(&http),
-                        Arc::clone(&reminder_store),
-                    ));
+        // Schedule new or edited reminders and catch up on missed ones
+        // 排程新增或修改的提醒，並處理錯過的提醒
+        let (mut queue, missed) = refresh_schedule(&reminders, &guild_settings).await;
+        deliver(
+            &http,
+            &reminders,
+            &guild_settings,
+            &delivery_log,
+            &mut queue,
+            missed,
+        )
+        .await;
+        sync_events(&http, &reminders, &guild_settings).await;
+
+        loop {
+            // Sleep until the earliest reminder is due
+            // 睡眠直到最早的提醒到期
+            let wait = match queue.peek() {
+                Some(Reverse(entry)) => (entry.fire_at - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+                    .min(MAX_SLEEP),
+                None => MAX_SLEEP,
+            };
+
+            tokio::select! {
+                _ = sleep(wait) => {
+                    fire_due(&http, &reminders, &guild_settings, &delivery_log, &mut queue).await;
+                    // Move the events of fired reminders to their next occurrence
+                    // 將已觸發提醒的活動移至下一次觸發
+                    sync_events(&http, &reminders, &guild_settings).await;
+                }
+                _ = notify.notified() => {
+                    // Send anything already due before the queue is rebuilt
+                    // 在重建佇列之前，先發送已到期的提醒
+                    fire_due(&http, &reminders, &guild_settings, &delivery_log, &mut queue).await;
+                    break;
                 }
             }
-            _ = notify.notified() => {
-                // Process reminders immediately when a new reminder is added
-                // 當有新的提醒被添加時，立即處理
-                process_reminders(&reminders, &reminder_store).await;
-
-                // Start 2-minute check if there are reminders within 30 minutes
-                // 如果30分鐘內有提醒，開始2分鐘檢查
-                if !reminder_store.reminders_30_min.read().await.is_empty()
-                    && !*reminder_store.two_min_checking.read().await
-                {
-                    *reminder_store.two_min_checking.write().await = true;
-                    tokio::spawn(check_2min_remind(
-                        Arc::clone(&http),
-                        Arc::clone(&reminder_store),
-                    ));
+        }
+    }
+}
+
+// Give every reminder a next fire instant and rebuild the queue from them;
+// occurrences that passed without being delivered are handled by their catch-up policy
+// 為每個提醒計算下一次觸發時間並重建佇列；
+// 未發送就已過期的提醒依其補發策略處理
+async fn refresh_schedule(
+    reminders: &Reminders,
+    guild_settings: &GuildSettingsMap,
+) -> (ReminderQueue, Vec<Delivery>) {
+    let now = Utc::now();
+    let settings = guild_settings.read().await;
+    let mut guild_reminders_map = reminders.write().await;
+    let mut queue = ReminderQueue::new();
+    let mut missed = Vec::new();
+
+    for (guild_id, reminders_map) in guild_reminders_map.iter_mut() {
+        let tz = guild_timezone(&settings, guild_id);
+        let holidays = guild_holidays(&settings, guild_id);
+        let allow_everyone = guild_allows_everyone(&settings, guild_id);
+        for (channel_id, reminders) in reminders_map.iter_mut() {
+            for reminder in reminders.iter_mut() {
+                // Paused reminders are not scheduled until resumed
+                // 暫停的提醒在恢復前不會被排程
+                if reminder.paused {
+                    reminder.next_due = None;
+                    reminder.retry_at = None;
+                    continue;
                 }
+
+                // An occurrence waiting to be sent again keeps its place until the retry
+                // 等待再次發送的提醒保留原本的觸發時間，直到重試為止
+                match reminder.retry_at {
+                    Some(retry_at) if retry_at > now && reminder.next_due.is_some() => {
+                        queue_retry(&mut queue, retry_at, *guild_id, *channel_id);
+                        continue;
+                    }
+                    _ => reminder.retry_at = None,
+                }
+
+                // One-shot reminders from before delivery tracking have no next_due yet
+                // 在發送追蹤之前建立的單次提醒尚未有 next_due
+                let pending = reminder.next_due.or_else(|| match reminder.recurrence {
+                    Recurrence::Once { date } if reminder.last_sent.is_none() => {
+                        Some(local_to_utc(&tz, date, reminder.time))
+                    }
+                    _ => None,
+                });
+
+                match pending {
+                    Some(due) if due <= now => {
+                        let entry = ScheduledEntry {
+                            fire_at: due,
+                            guild_id: *guild_id,
+                            channel_id: *channel_id,
+                        };
+                        if should_catch_up(reminder.catch_up, due, now) {
+                            // Delivery moves next_due forward once it is sent
+                            // 發送後才會更新 next_due
+                            reminder.next_due = Some(due);
+                            missed.push(Delivery::new(
+                                entry,
+                                reminder,
+                                &tz,
+                                allow_everyone,
+                                Some(DELAYED_MARKER),
+                            ));
+                            continue;
+                        }
+                        println!(
+                            "{} Skipped missed reminder in channel {} due at {}",
+                            system_output(),
+                            channel_id,
+                            due
+                        );
+                        reminder.next_due = next_fire(reminder, &tz, holidays, now);
+                    }
+                    Some(due) => reminder.next_due = Some(due),
+                    None => reminder.next_due = next_fire(reminder, &tz, holidays, now),
+                }
+
+                queue_reminder(&mut queue, reminder, *guild_id, *channel_id, now);
             }
         }
     }
+
+    // Reminders that can no longer fire, such as past one-shots or ended rules, are dropped
+    // 移除不會再觸發的提醒，例如已過的單次提醒或已結束的週期規則
+    for reminders_map in guild_reminders_map.values_mut() {
+        for reminders in reminders_map.values_mut() {
+            reminders.retain(|r| r.paused || r.next_due.is_some());
+        }
+        reminders_map.retain(|_, reminders| !reminders.is_empty());
+    }
+    guild_reminders_map.retain(|_, reminders_map| !reminders_map.is_empty());
+
+    if let Err(err) = save_reminders(&guild_reminders_map) {
+        println!("{} Failed to save reminders: {}", error_output(), err);
+    }
+
+    (queue, missed)
 }
 
-// Process reminders function
-// 處理提醒的函數
-async fn process_reminders(reminders: &Reminders, reminder_store: &Arc<ReminderStore>) {
-    let now = Utc::now().with_timezone(&*TW);
-    let target_time = now + chrono::Duration::minutes(30);
-    let handler_reminder = Arc::clone(reminders);
+// Send every reminder whose fire instant has passed
+// 發送所有已到期的提醒
+async fn fire_due(
+    http: &Arc<Http>,
+    reminders: &Reminders,
+    guild_settings: &GuildSettingsMap,
+    delivery_log: &DeliveryLog,
+    queue: &mut ReminderQueue,
+) {
+    let now = Utc::now();
+    let mut due_entries = HashSet::new();
+    while let Some(Reverse(entry)) = queue.peek().copied() {
+        if entry.fire_at > now {
+            break;
+        }
+        queue.pop();
+        due_entries.insert(entry);
+    }
+    if due_entries.is_empty() {
+        return;
+    }
+
+    // Collect every reminder still waiting for one of the due instants
+    // 收集仍在等待這些到期時間的提醒
+    let mut deliveries = Vec::new();
     {
-        let mut guild_reminders_map = handler_reminder.write().await;
-        for (_guild_id, reminders_map) in guild_reminders_map.iter_mut() {
-            for (channel_id, reminders) in reminders_map.iter_mut() {
-                for reminder in reminders.iter_mut() {
-                    // Check if the reminder needs to be executed within the next 30 minutes
-                    // 檢查提醒是否在接下來的30分鐘內需要執行
-                    if reminder.weekdays.contains(&now.weekday())
-                        && reminder.time > now.time()
-                        && reminder.time <= target_time.time()
-                        && reminder.last_executed != Some(now.date_naive())
-                    {
-                        reminder.last_executed = Some(now.date_naive());
-                        let mut reminder_in_30min = reminder_store.reminders_30_min.write().await;
-                        reminder_in_30min.push((*channel_id, reminder.clone()));
+        let settings = guild_settings.read().await;
+        let guild_reminders_map = reminders.read().await;
+        for entry in due_entries {
+            let tz = guild_timezone(&settings, &entry.guild_id);
+            let allow_everyone = guild_allows_everyone(&settings, &entry.guild_id);
+            let Some(reminder_list) = guild_reminders_map
+                .get(&entry.guild_id)
+                .and_then(|guild_reminder| guild_reminder.get(&entry.channel_id))
+            else {
+                continue;
+            };
+            for reminder in reminder_list.iter().filter(|r| !r.paused) {
+                match (reminder.next_due, reminder.retry_at) {
+                    (Some(due), None) if due == entry.fire_at => {
+                        deliveries.push(Delivery::new(entry, reminder, &tz, allow_everyone, None));
+                    }
+                    // A retry is rendered and recorded for the occurrence it belongs to
+                    // 重試會以其所屬的觸發時間產生訊息並記錄
+                    (Some(due), Some(retry_at)) if retry_at == entry.fire_at => {
+                        let entry = ScheduledEntry {
+                            fire_at: due,
+                            ..entry
+                        };
+                        deliveries.push(Delivery::new(
+                            entry,
+                            reminder,
+                            &tz,
+                            allow_everyone,
+                            Some(DELAYED_MARKER),
+                        ));
                     }
+                    _ => {}
+                }
+                for (_, lead) in pending_leads(reminder).filter(|(at, _)| *at == entry.fire_at) {
+                    deliveries.push(Delivery::lead(entry, reminder, lead, &tz, allow_everyone));
                 }
             }
         }
-        save_reminders_to_file(&guild_reminders_map).expect("Failed to save reminders");
     }
+
+    deliver(
+        http,
+        reminders,
+        guild_settings,
+        delivery_log,
+        queue,
+        deliveries,
+    )
+    .await;
 }
 
-// Check reminders within 2 minutes
-// 檢查2分鐘內的提醒
-async fn check_2min_remind(http: Arc<Http>, remind_store: Arc<ReminderStore>) {
-    let mut wait_time = interval(Duration::from_secs(120));
-    loop {
-        wait_time.tick().await;
-        let now = Utc::now().with_timezone(&*TW);
-        let target_time = now + chrono::Duration::minutes(2);
-        let mut new_list = Vec::new();
-        {
-            let reminder_in_30min = remind_store.reminders_30_min.read().await;
-            for (channel_id, reminder) in reminder_in_30min.iter() {
-                if reminder.time > now.time() && reminder.time < target_time.time() {
-                    let mut reminder_in_2min = remind_store.reminders_2_min.write().await;
-                    reminder_in_2min.push((*channel_id, reminder.clone()));
-                } else {
-                    new_list.push((*channel_id, reminder.clone()));
+// Send reminder messages, then record the delivery and schedule the next occurrence
+// 發送提醒消息，然後記錄發送結果並排程下一次提醒
+async fn deliver(
+    http: &Arc<Http>,
+    reminders: &Reminders,
+    guild_settings: &GuildSettingsMap,
+    delivery_log: &DeliveryLog,
+    queue: &mut ReminderQueue,
+    deliveries: Vec<Delivery>,
+) {
+    if deliveries.is_empty() {
+        return;
+    }
+
+    // Send reminder messages without holding the reminder lock
+    // 在不持有提醒鎖的情況下發送提醒消息
+    let results = join_all(
+        deliveries
+            .iter()
+            .map(|delivery| send_delivery(http, delivery)),
+    )
+    .await;
+
+    // Every attempt goes into the delivery log of its guild
+    // 每一次嘗試都會記錄到所屬伺服器的發送紀錄
+    let mut outcomes = Vec::with_capacity(results.len());
+    {
+        let mut log = delivery_log.write().await;
+        for (delivery, (outcome, records)) in deliveries.iter().zip(results) {
+            for record in records {
+                push_record(&mut log, delivery.entry.guild_id, record);
+            }
+            outcomes.push(outcome);
+        }
+        if let Err(err) = save_delivery_log(&log) {
+            println!("{} Failed to save delivery log: {}", error_output(), err);
+        }
+    }
+
+    let now = Utc::now();
+    let mut disabled = Vec::new();
+    {
+        let settings = guild_settings.read().await;
+        let mut guild_reminders_map = reminders.write().await;
+        for (delivery, outcome) in deliveries.iter().zip(outcomes) {
+            let entry = delivery.entry;
+            let tz = guild_timezone(&settings, &entry.guild_id);
+            let holidays = guild_holidays(&settings, &entry.guild_id);
+            let Some(reminder_list) = guild_reminders_map
+                .get_mut(&entry.guild_id)
+                .and_then(|guild_reminder| guild_reminder.get_mut(&entry.channel_id))
+            else {
+                continue;
+            };
+
+            // Channel reminders of a deleted channel are paused instead of failing on every
+            // occurrence, personal reminders are sent by DM and keep running
+            // 已刪除頻道的頻道提醒會被暫停，而不是每次觸發都發送失敗，以私訊發送的個人提醒則繼續運作
+            if outcome == SendOutcome::ChannelGone {
+                for reminder in reminder_list
+                    .iter_mut()
+                    .filter(|r| !r.paused && r.dm_user.is_none())
+                {
+                    reminder.paused = true;
+                    reminder.next_due = None;
+                    reminder.retry_at = None;
+                    reminder.leads_sent.clear();
+                    disabled.push((
+                        entry.guild_id,
+                        entry.channel_id,
+                        reminder.id.clone(),
+                        reminder.creator,
+                    ));
+                }
+                continue;
+            }
+
+            // Lead alerts are recorded so they are not sent again after a restart or an edit
+            // 記錄已發送的預告，重新啟動或修改後不會再次發送
+            if delivery.lead {
+                if let Some(reminder) = reminder_list.iter_mut().find(|r| {
+                    r.id == delivery.id && pending_leads(r).any(|(at, _)| at == entry.fire_at)
+                }) {
+                    reminder.leads_sent.push(entry.fire_at);
+                }
+                continue;
+            }
+
+            // The reminder may have been edited or removed while sending
+            // 發送期間提醒可能已被修改或移除
+            let Some(position) = reminder_list
+                .iter()
+                .position(|r| r.id == delivery.id && r.next_due == Some(entry.fire_at))
+            else {
+                continue;
+            };
+
+            let reminder = &mut reminder_list[position];
+
+            // An occurrence that could not be sent yet is tried again later, without counting it
+            // 尚未能發送的提醒稍後再試一次，且不計入觸發次數
+            if outcome == SendOutcome::Retry {
+                if let Some(retry_at) = requeue_time(reminder, &tz, holidays, entry.fire_at, now) {
+                    reminder.retry_at = Some(retry_at);
+                    queue_retry(queue, retry_at, entry.guild_id, entry.channel_id);
+                    continue;
+                }
+                println!(
+                    "{} Gave up on reminder {} due at {}",
+                    error_output(),
+                    reminder.id,
+                    entry.fire_at
+                );
+            }
+
+            // Only delivered occurrences count towards the fire count and the remaining ones
+            // 只有成功發送的提醒才計入觸發次數與剩餘次數
+            reminder.last_executed = Some(entry.fire_at.with_timezone(&tz).date_naive());
+            if outcome == SendOutcome::Sent {
+                reminder.last_sent = Some(now);
+                reminder.fire_count = reminder.fire_count.saturating_add(1);
+                if let Some(remaining) = reminder.remaining.as_mut() {
+                    *remaining = remaining.saturating_sub(1);
                 }
             }
+            reminder.retry_at = None;
+            reminder.leads_sent.clear();
+            reminder.next_due = next_fire(reminder, &tz, holidays, now);
+
+            // Finished reminders are removed, recurring ones are queued again
+            // 已結束的提醒會被移除，重複提醒則重新加入佇列
+            match reminder.next_due {
+                Some(_) => queue_reminder(queue, reminder, entry.guild_id, entry.channel_id, now),
+                None => {
+                    reminder_list.remove(position);
+                }
+            }
+        }
+        for reminders_map in guild_reminders_map.values_mut() {
+            reminders_map.retain(|_, reminders| !reminders.is_empty());
         }
-        *remind_store.reminders_30_min.write().await = new_list;
+        guild_reminders_map.retain(|_, reminders_map| !reminders_map.is_empty());
 
-        // Start 1-second check if there are reminders within 2 minutes
-        // 如果有2分鐘內的提醒，啟動1秒檢查
-        if !remind_store.reminders_2_min.read().await.is_empty()
-            && !*remind_store.one_secs_checking.read().await
-        {
-            *remind_store.one_secs_checking.write().await = true;
-            tokio::spawn(check_1secs_remind(
-                Arc::clone(&http),
-                Arc::clone(&remind_store),
-            ));
+        if let Err(err) = save_reminders(&guild_reminders_map) {
+            println!("{} Failed to save reminders: {}", error_output(), err);
         }
+    }
 
-        if remind_store.reminders_30_min.read().await.is_empty() {
-            *remind_store.two_min_checking.write().await = false;
+    notify_disabled(http, disabled).await;
+}
+
+// Send one delivery, retrying errors that may be transient with an increasing delay;
+// returns the outcome along with a record of every attempt
+// 發送一則提醒，可能是暫時性的錯誤會以遞增的間隔重試；
+// 返回發送結果以及每一次嘗試的紀錄
+async fn send_delivery(http: &Http, delivery: &Delivery) -> (SendOutcome, Vec<DeliveryRecord>) {
+    let mut records = Vec::new();
+    let mut delay = RETRY_BASE_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let sent = post(http, delivery).await;
+        records.push(DeliveryRecord {
+            at: Utc::now(),
+            channel_id: delivery.entry.channel_id,
+            reminder_id: delivery.id.clone(),
+            lead: delivery.lead,
+            dm: delivery.dm_user.is_some(),
+            attempt,
+            error: sent.as_ref().err().map(|err| err.to_string()),
+        });
+        let err = match sent {
+            Ok(_) => return (SendOutcome::Sent, records),
+            Err(err) => err,
+        };
+        println!(
+            "{} sending reminder {} (attempt {}): {:?}",
+            error_output(),
+            delivery.id,
+            attempt,
+            err
+        );
+        if delivery.dm_user.is_none() && is_unknown_channel(&err) {
+            return (SendOutcome::ChannelGone, records);
+        }
+        if !is_transient(&err) {
+            return (SendOutcome::Failed, records);
+        }
+        if attempt == MAX_ATTEMPTS {
             break;
         }
+        sleep(delay).await;
+        delay *= 2;
     }
+    (SendOutcome::Retry, records)
 }
 
-// Check reminders within 1 second and send them
-// 檢查1秒內的提醒並發送
-async fn check_1secs_remind(http: Arc<Http>, remind_store: Arc<ReminderStore>) {
-    let mut wait_time = interval(Duration::from_secs(1));
-    loop {
-        wait_time.tick().await;
-        let now = Utc::now().with_timezone(&*TW);
-        let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), now.second()).unwrap();
-        let mut new_list = Vec::new();
+// Post a delivery to where it belongs: a DM, a new thread or forum post, or the channel
+// 將提醒發送到對應的位置：私訊、新的討論串或論壇貼文，或是頻道
+async fn post(http: &Http, delivery: &Delivery) -> serenity::Result<()> {
+    let channel_id = delivery.entry.channel_id;
+    let message = delivery.message.clone();
+    // Personal reminders go to the member's DMs instead of the channel
+    // 個人提醒以私訊發送給成員，而不是發送到頻道
+    if let Some(user_id) = delivery.dm_user {
+        return user_id.direct_message(http, message).await.map(|_| ());
+    }
+    let Some(thread) = &delivery.thread else {
+        return channel_id.send_message(http, message).await.map(|_| ());
+    };
+    if thread.forum {
+        let post =
+            CreateForumPost::new(&thread.name, message).auto_archive_duration(thread.auto_archive);
+        return channel_id.create_forum_post(http, post).await.map(|_| ());
+    }
+
+    // The thread is started from the reminder message; once the message is sent the
+    // reminder counts as delivered, so a failed thread is logged instead of retried
+    // 討論串從提醒訊息建立；訊息發送後即視為已送達，建立討論串失敗時只記錄而不重試
+    let sent = channel_id.send_message(http, message).await?;
+    let builder = CreateThread::new(&thread.name).auto_archive_duration(thread.auto_archive);
+    if let Err(err) = channel_id
+        .create_thread_from_message(http, sent.id, builder)
+        .await
+    {
+        println!(
+            "{} Failed to start thread of reminder {}: {}",
+            error_output(),
+            delivery.id,
+            err
+        );
+    }
+    Ok(())
+}
+
+// Tell the creators of reminders disabled because their channel was deleted
+// 通知因頻道被刪除而停用的提醒的建立者
+async fn notify_disabled(http: &Http, disabled: Vec<(GuildId, ChannelId, String, Option<UserId>)>) {
+    for (guild_id, channel_id, id, creator) in disabled {
+        println!(
+            "{} Disabled reminder {} of deleted channel {}",
+            system_output(),
+            id,
+            channel_id
+        );
+        let Some(creator) = creator else {
+            continue;
+        };
+        let guild_name = match guild_id.to_partial_guild(http).await {
+            Ok(guild) => guild.name,
+            Err(_) => guild_id.to_string(),
+        };
+        let content = format!(
+            ">> 你在伺服器 {} 設定的提醒 {} 已停用，因為頻道 {} 已被刪除，可使用 /rm_remind 移除",
+            guild_name, id, channel_id
+        );
+        if let Err(err) = creator
+            .direct_message(http, CreateMessage::new().content(content))
+            .await
         {
-            let reminder_in_2min = remind_store.reminders_2_min.read().await;
-            for (channel_id, reminder) in reminder_in_2min.iter() {
-                if reminder.time == time {
-                    // Send reminder message
-                    // 發送提醒消息
-                    if let Err(err) = channel_id.say(&http, &reminder.message).await {
-                        println!("{} sending message: {:?}", error_output(), err);
-                    }
-                } else {
-                    new_list.push((*channel_id, reminder.clone()));
+            println!(
+                "{} Failed to notify creator of reminder {}: {}",
+                error_output(),
+                id,
+                err
+            );
+        }
+    }
+}
+
+// Queue the next occurrence of a reminder along with its lead alerts still to come;
+// lead alerts whose time passed, such as while the bot was offline, are not sent late
+// 將提醒的下一次觸發與尚未到來的預告加入佇列；
+// 已過時間的預告（例如機器人離線期間）不會補發
+fn queue_reminder(
+    queue: &mut ReminderQueue,
+    reminder: &Reminder,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    now: DateTime<Utc>,
+) {
+    let Some(next_due) = reminder.next_due else {
+        return;
+    };
+    let leads = pending_leads(reminder)
+        .map(|(at, _)| at)
+        .filter(|at| *at > now);
+    for fire_at in std::iter::once(next_due).chain(leads) {
+        queue.push(Reverse(ScheduledEntry {
+            fire_at,
+            guild_id,
+            channel_id,
+        }));
+    }
+}
+
+// Queue the next attempt at an occurrence whose delivery failed, keyed by its retry instant
+// 將發送失敗的提醒的下一次嘗試加入佇列，以重試時間為鍵
+fn queue_retry(
+    queue: &mut ReminderQueue,
+    retry_at: DateTime<Utc>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) {
+    queue.push(Reverse(ScheduledEntry {
+        fire_at: retry_at,
+        guild_id,
+        channel_id,
+    }));
+}
+
+// Lead alerts of the upcoming occurrence that have not been sent, with their fire instants
+// 下一次觸發尚未發送的預告及其發送時間
+fn pending_leads(reminder: &Reminder) -> impl Iterator<Item = (DateTime<Utc>, &LeadAlert)> {
+    reminder
+        .leads
+        .iter()
+        .filter_map(move |lead| {
+            let next_due = reminder.next_due?;
+            Some((next_due - TimeDelta::minutes(i64::from(lead.minutes)), lead))
+        })
+        .filter(|(at, _)| !reminder.leads_sent.contains(at))
+}
+
+// Generate a reminder ID that is not used by any reminder of the guild
+// 產生伺服器內未被使用的提醒 ID
+pub fn new_reminder_id(guild_reminder: Option<&HashMap<ChannelId, Vec<Reminder>>>) -> String {
+    let mut rng = rand::rng();
+    loop {
+        let id: String = (0..ID_LENGTH)
+            .map(|_| ID_ALPHABET[rng.random_range(0..ID_ALPHABET.len())] as char)
+            .collect();
+        let taken =
+            guild_reminder.is_some_and(|channels| channels.values().flatten().any(|r| r.id == id));
+        if !taken {
+            return id;
+        }
+    }
+}
+
+// Give an ID to every reminder stored before IDs existed
+// 為在 ID 功能之前儲存的提醒補上 ID
+pub fn assign_missing_ids(reminders: &mut HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>) {
+    for guild_reminder in reminders.values_mut() {
+        let mut missing = Vec::new();
+        for (channel_id, reminder_list) in guild_reminder.iter() {
+            for (index, reminder) in reminder_list.iter().enumerate() {
+                if reminder.id.is_empty() {
+                    missing.push((*channel_id, index));
                 }
             }
         }
-        *remind_store.reminders_2_min.write().await = new_list;
+        for (channel_id, index) in missing {
+            let id = new_reminder_id(Some(guild_reminder));
+            if let Some(reminder) = guild_reminder
+                .get_mut(&channel_id)
+                .and_then(|reminder_list| reminder_list.get_mut(index))
+            {
+                reminder.id = id;
+            }
+        }
+    }
+}
 
-        if remind_store.reminders_2_min.read().await.is_empty() {
-            *remind_store.one_secs_checking.write().await = false;
-            break;
+// Find a reminder of a guild by its ID, returning the channel it belongs to
+// 依 ID 尋找伺服器中的提醒，並返回其所屬頻道
+pub fn find_reminder_mut<'a>(
+    guild_reminder: &'a mut HashMap<ChannelId, Vec<Reminder>>,
+    id: &str,
+) -> Option<(ChannelId, &'a mut Reminder)> {
+    let id = id.trim().to_lowercase();
+    guild_reminder
+        .iter_mut()
+        .find_map(|(channel_id, reminder_list)| {
+            reminder_list
+                .iter_mut()
+                .find(|r| r.id == id)
+                .map(|reminder| (*channel_id, reminder))
+        })
+}
+
+// Check whether a member owns a reminder: its creator, or the member a personal reminder is for
+// 檢查成員是否擁有提醒：提醒的建立者，或個人提醒的對象
+pub fn owns_reminder(reminder: &Reminder, user_id: UserId) -> bool {
+    reminder.creator.or(reminder.dm_user) == Some(user_id)
+}
+
+// Pick when to send an occurrence again after its delivery failed, waiting as long as has passed
+// since its fire time so the gaps keep doubling; gives up once that would be outside the
+// catch-up window, at or past the next occurrence, or too long after the fire time
+// 決定發送失敗的提醒何時再次發送，等待自觸發以來經過的時間，使間隔持續加倍；
+// 若超出補發範圍、到達下一次觸發時間或距觸發時間太久則放棄
+fn requeue_time(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    due: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let retry_at = now + (now - due).clamp(MIN_REQUEUE_DELAY, MAX_REQUEUE_DELAY);
+    let in_window =
+        should_catch_up(reminder.catch_up, due, retry_at) && retry_at - due <= MAX_REQUEUE_AGE;
+    let before_next = next_fire(reminder, tz, holidays, due).is_none_or(|next| retry_at < next);
+    (in_window && before_next).then_some(retry_at)
+}
+
+// Decide whether a missed occurrence is still sent according to its policy
+// 依補發策略決定是否仍要發送錯過的提醒
+fn should_catch_up(policy: CatchUpPolicy, due: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    match policy {
+        CatchUpPolicy::Late => true,
+        CatchUpPolicy::Skip => false,
+        CatchUpPolicy::Within { minutes } => now - due <= chrono::Duration::minutes(minutes.into()),
+    }
+}
+
+// Compute the first fire instant of a reminder strictly after the given instant;
+// recurring reminders leave out the guild holidays and their own skip dates
+// 計算提醒在指定時間點之後的第一次觸發時間；
+// 週期提醒會略過伺服器的假日與提醒本身的跳過日期
+pub fn next_fire(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if reminder.remaining == Some(0) {
+        return None;
+    }
+    let within_end = |date: &NaiveDate| reminder.until.is_none_or(|until| *date <= until);
+    let skipped =
+        |date: &NaiveDate| reminder.skip_dates.contains(date) || holidays.contains_key(date);
+
+    match &reminder.recurrence {
+        Recurrence::Once { date } => {
+            let fire_at = local_to_utc(tz, *date, reminder.time);
+            return (fire_at > after && within_end(date)).then_some(fire_at);
+        }
+        Recurrence::Cron { expr } => {
+            return match CronSchedule::parse(expr) {
+                Ok(cron) => cron.next_after(tz, after, reminder.until, skipped),
+                Err(err) => {
+                    println!(
+                        "{} Invalid cron expression of reminder {}: {}",
+                        error_output(),
+                        reminder.id,
+                        err
+                    );
+                    None
+                }
+            };
+        }
+        _ => {}
+    }
+
+    // Scan far enough ahead to reach the next occurrence of the rule, including today's
+    // time already passed; every skipped date may hide one more occurrence
+    // 往後掃描到足以涵蓋下一次觸發的天數，包含今天已過的時間；
+    // 每個跳過的日期都可能再少一次觸發
+    let start = after.with_timezone(tz).date_naive();
+    let skipped_ahead =
+        reminder.skip_dates.range(start..).count() + holidays.range(start..).count();
+    let days = (scan_days(&reminder.recurrence) * (skipped_ahead as u64 + 1)).min(MAX_SCAN_DAYS);
+    (0..=days)
+        .filter_map(|offset| start.checked_add_days(Days::new(offset)))
+        .take_while(within_end)
+        .filter(|date| occurs_on(&reminder.recurrence, *date) && !skipped(date))
+        .map(|date| local_to_utc(tz, date, reminder.time))
+        .find(|fire_at| *fire_at > after)
+}
+
+// Compute up to `count` upcoming fire instants of a reminder after the given instant
+// 計算提醒在指定時間點之後最多 `count` 次的觸發時間
+pub fn upcoming_fires(
+    reminder: &Reminder,
+    tz: &Tz,
+    holidays: &BTreeMap<NaiveDate, String>,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Vec<DateTime<Utc>> {
+    let count = reminder
+        .remaining
+        .map_or(count, |remaining| count.min(remaining as usize));
+    let mut fires: Vec<DateTime<Utc>> = Vec::with_capacity(count);
+    while fires.len() < count {
+        let after = fires.last().copied().unwrap_or(after);
+        match next_fire(reminder, tz, holidays, after) {
+            Some(fire_at) => fires.push(fire_at),
+            None => break,
+        }
+    }
+    fires
+}
+
+// Check whether a recurrence rule fires on the given local date
+// 檢查週期規則是否在指定的本地日期觸發
+pub fn occurs_on(recurrence: &Recurrence, date: NaiveDate) -> bool {
+    match recurrence {
+        Recurrence::Once { date: once } => *once == date,
+        Recurrence::Weekly {
+            weekdays,
+            interval,
+            anchor,
+        } => {
+            let in_week = match anchor {
+                Some(anchor) if *interval > 1 => {
+                    let weeks = (week_start(date) - week_start(*anchor)).num_weeks();
+                    weeks.rem_euclid(i64::from(*interval)) == 0
+                }
+                _ => true,
+            };
+            in_week && weekdays.contains(&date.weekday())
+        }
+        Recurrence::Daily { interval, anchor } => {
+            let days = (date - *anchor).num_days();
+            days >= 0 && days % i64::from((*interval).max(1)) == 0
+        }
+        Recurrence::MonthlyDay { day } => date.day() == (*day).min(days_in_month(date)),
+        Recurrence::Cron { expr } => {
+            CronSchedule::parse(expr).is_ok_and(|cron| cron.matches_date(date))
+        }
+        Recurrence::MonthlyWeekday { nth, weekday } => {
+            if date.weekday() != *weekday {
+                return false;
+            }
+            // Count the weekday from the start of the month, or from its end when nth is negative
+            // 從月初計算第幾個星期，nth 為負數時從月底計算
+            let from_start = (date.day() - 1) / 7 + 1;
+            let from_end = (days_in_month(date) - date.day()) / 7 + 1;
+            match *nth {
+                n if n > 0 => from_start == n as u32,
+                n => from_end == n.unsigned_abs() as u32,
+            }
+        }
+    }
+}
+
+// Number of days to scan so the next occurrence of a rule is always found
+// 為確保找到下一次觸發所需掃描的天數
+fn scan_days(recurrence: &Recurrence) -> u64 {
+    match recurrence {
+        Recurrence::Once { .. } | Recurrence::Cron { .. } => 0,
+        Recurrence::Weekly { interval, .. } => 7 * u64::from((*interval).max(1)) + 7,
+        Recurrence::Daily { interval, .. } => u64::from((*interval).max(1)),
+        Recurrence::MonthlyDay { .. } => 62,
+        // A fifth weekday can be missing from several months in a row
+        // 第五個星期可能連續好幾個月都不存在
+        Recurrence::MonthlyWeekday { .. } => 400,
+    }
+}
+
+// Monday of the week a date belongs to
+// 日期所在週的星期一
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+// Number of days in the month a date belongs to
+// 日期所在月份的天數
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap();
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+// Get the holidays configured for a guild
+// 取得伺服器設定的假日
+pub fn guild_holidays<'a>(
+    settings: &'a HashMap<GuildId, GuildSettings>,
+    guild_id: &GuildId,
+) -> &'a BTreeMap<NaiveDate, String> {
+    settings.get(guild_id).map_or(&NO_HOLIDAYS, |s| &s.holidays)
+}
+
+// Get the timezone configured for a guild, falling back to Taipei
+// 取得伺服器設定的時區，未設定時使用台北時區
+pub fn guild_timezone(settings: &HashMap<GuildId, GuildSettings>, guild_id: &GuildId) -> Tz {
+    settings
+        .get(guild_id)
+        .and_then(|s| s.timezone.as_deref())
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(*TW)
+}
+
+// Check whether an administrator allowed reminders of the guild to ping @everyone
+// 檢查管理員是否允許伺服器的提醒提及 @everyone
+pub fn guild_allows_everyone(
+    settings: &HashMap<GuildId, GuildSettings>,
+    guild_id: &GuildId,
+) -> bool {
+    settings.get(guild_id).is_some_and(|s| s.allow_everyone)
+}
+
+// Convert a local date and time in the given timezone to a UTC instant
+// 將指定時區的本地日期與時間轉換為 UTC 時間點
+pub fn local_to_utc(tz: &Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let mut local = date.and_time(time);
+    // A time skipped by a DST jump does not exist, so move forward until it does;
+    // a repeated time during fall-back fires on its first occurrence
+    // 夏令時跳過的時間不存在，往後移動直到存在；回撥時重複的時間取第一次出現
+    loop {
+        if let Some(dt) = tz.from_local_datetime(&local).earliest() {
+            return dt.with_timezone(&Utc);
         }
+        local += chrono::Duration::minutes(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        date(y, m, d).and_time(time(h, min)).and_utc()
+    }
+
+    fn tz(name: &str) -> Tz {
+        name.parse().unwrap()
+    }
+
+    // A reminder at 09:00 with the given rule
+    // 以指定規則在 09:00 觸發的提醒
+    fn reminder(recurrence: Recurrence) -> Reminder {
+        Reminder {
+            time: time(9, 0),
+            recurrence,
+            ..Default::default()
+        }
+    }
+
+    fn fires(reminder: &Reminder, after: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        upcoming_fires(reminder, &tz("UTC"), &NO_HOLIDAYS, after, count)
+    }
+
+    #[test]
+    fn daily_interval_counts_from_the_anchor() {
+        let every_third_day = Recurrence::Daily {
+            interval: 3,
+            anchor: date(2025, 3, 1),
+        };
+        assert!(occurs_on(&every_third_day, date(2025, 3, 1)));
+        assert!(occurs_on(&every_third_day, date(2025, 3, 4)));
+        assert!(!occurs_on(&every_third_day, date(2025, 3, 5)));
+        assert!(!occurs_on(&every_third_day, date(2025, 2, 26)));
+        assert_eq!(
+            fires(&reminder(every_third_day), utc(2025, 3, 1, 9, 0), 2),
+            vec![utc(2025, 3, 4, 9, 0), utc(2025, 3, 7, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn weekly_interval_skips_weeks_from_the_anchor() {
+        let biweekly = Recurrence::Weekly {
+            weekdays: vec![Weekday::Tue],
+            interval: 2,
+            anchor: Some(date(2025, 3, 5)),
+        };
+        assert!(occurs_on(&biweekly, date(2025, 3, 4)));
+        assert!(!occurs_on(&biweekly, date(2025, 3, 11)));
+        assert!(occurs_on(&biweekly, date(2025, 3, 18)));
+        assert_eq!(
+            fires(&reminder(biweekly), utc(2025, 3, 4, 9, 0), 2),
+            vec![utc(2025, 3, 18, 9, 0), utc(2025, 4, 1, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn monthly_day_uses_the_last_day_of_short_months() {
+        let day_31 = Recurrence::MonthlyDay { day: 31 };
+        assert!(occurs_on(&day_31, date(2025, 2, 28)));
+        assert!(occurs_on(&day_31, date(2024, 2, 29)));
+        assert!(!occurs_on(&day_31, date(2024, 2, 28)));
+        assert!(occurs_on(&day_31, date(2025, 4, 30)));
+        assert!(!occurs_on(&day_31, date(2025, 3, 30)));
+        assert_eq!(
+            fires(&reminder(day_31), utc(2025, 1, 31, 9, 0), 3),
+            vec![
+                utc(2025, 2, 28, 9, 0),
+                utc(2025, 3, 31, 9, 0),
+                utc(2025, 4, 30, 9, 0)
+            ]
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn nth_and_last_weekday_of_the_month() {
+        let second_tuesday = Recurrence::MonthlyWeekday {
+            nth: 2,
+            weekday: Weekday::Tue,
+        };
+        assert!(occurs_on(&second_tuesday, date(2025, 3, 11)));
+        assert!(!occurs_on(&second_tuesday, date(2025, 3, 4)));
+        assert!(!occurs_on(&second_tuesday, date(2025, 3, 12)));
+
+        let last_friday = Recurrence::MonthlyWeekday {
+            nth: -1,
+            weekday: Weekday::Fri,
+        };
+        assert!(occurs_on(&last_friday, date(2025, 2, 28)));
+        assert!(!occurs_on(&last_friday, date(2025, 2, 21)));
+        assert!(occurs_on(&last_friday, date(2025, 3, 28)));
+    }
+
+    #[test]
+    fn fifth_weekday_skips_months_without_one() {
+        let fifth_monday = reminder(Recurrence::MonthlyWeekday {
+            nth: 5,
+            weekday: Weekday::Mon,
+        });
+        assert_eq!(
+            fires(&fifth_monday, utc(2025, 3, 31, 9, 0), 2),
+            vec![utc(2025, 6, 30, 9, 0), utc(2025, 9, 29, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn skip_dates_holidays_and_limits() {
+        let mut daily = reminder(Recurrence::Daily {
+            interval: 1,
+            anchor: date(2025, 3, 1),
+        });
+        daily.skip_dates.insert(date(2025, 3, 6));
+        let holidays = BTreeMap::from([(date(2025, 3, 7), "holiday".to_string())]);
+        let after = utc(2025, 3, 5, 9, 0);
+        assert_eq!(
+            next_fire(&daily, &tz("UTC"), &holidays, after),
+            Some(utc(2025, 3, 8, 9, 0))
+        );
+
+        daily.until = Some(date(2025, 3, 7));
+        assert_eq!(next_fire(&daily, &tz("UTC"), &holidays, after), None);
+
+        daily.until = None;
+        daily.remaining = Some(0);
+        assert_eq!(next_fire(&daily, &tz("UTC"), &holidays, after), None);
+    }
+
+    #[test]
+    fn once_fires_only_in_the_future() {
+        let once = reminder(Recurrence::Once {
+            date: date(2025, 3, 5),
+        });
+        assert_eq!(
+            next_fire(&once, &tz("UTC"), &NO_HOLIDAYS, utc(2025, 3, 5, 8, 0)),
+            Some(utc(2025, 3, 5, 9, 0))
+        );
+        assert_eq!(
+            next_fire(&once, &tz("UTC"), &NO_HOLIDAYS, utc(2025, 3, 5, 9, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn local_times_follow_the_timezone() {
+        let taipei = tz("Asia/Taipei");
+        assert_eq!(
+            local_to_utc(&taipei, date(2025, 3, 5), time(9, 0)),
+            utc(2025, 3, 5, 1, 0)
+        );
+    }
+
+    #[test]
+    fn times_skipped_by_dst_move_forward() {
+        // Berlin jumps from 02:00 to 03:00 on 2025-03-30
+        // 柏林在 2025-03-30 從 02:00 跳到 03:00
+        let berlin = tz("Europe/Berlin");
+        assert_eq!(
+            local_to_utc(&berlin, date(2025, 3, 30), time(2, 30)),
+            utc(2025, 3, 30, 1, 0)
+        );
+        assert_eq!(
+            local_to_utc(&berlin, date(2025, 3, 30), time(3, 30)),
+            utc(2025, 3, 30, 1, 30)
+        );
+    }
+
+    #[test]
+    fn repeated_times_fire_on_the_first_occurrence() {
+        // Berlin repeats 02:00-03:00 on 2025-10-26, first in summer time
+        // 柏林在 2025-10-26 重複 02:00-03:00，第一次為夏令時間
+        let berlin = tz("Europe/Berlin");
+        assert_eq!(
+            local_to_utc(&berlin, date(2025, 10, 26), time(2, 30)),
+            utc(2025, 10, 26, 0, 30)
+        );
+    }
+
+    #[test]
+    fn daily_reminders_keep_local_time_across_dst() {
+        let daily = reminder(Recurrence::Daily {
+            interval: 1,
+            anchor: date(2025, 3, 1),
+        });
+        let berlin = tz("Europe/Berlin");
+        assert_eq!(
+            upcoming_fires(&daily, &berlin, &NO_HOLIDAYS, utc(2025, 3, 29, 9, 0), 2),
+            vec![utc(2025, 3, 30, 7, 0), utc(2025, 3, 31, 7, 0)]
+        );
+    }
+}
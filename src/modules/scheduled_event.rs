@@ -0,0 +1,206 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use serenity::{
+    all::{
+        ChannelId, CreateScheduledEvent, EditScheduledEvent, GuildId, Http, ScheduledEventId,
+        ScheduledEventType,
+    },
+    futures::future::join_all,
+};
+
+use super::func::{error_output, save_reminders};
+use super::reminder::guild_timezone;
+use super::template::{fill_placeholders, truncate};
+use crate::{EventOccurrence, GuildSettingsMap, Reminder, ReminderEvent, Reminders};
+
+// Length of an event when none is given, in minutes
+// 未指定時活動的長度（分鐘）
+pub const DEFAULT_EVENT_MINUTES: u32 = 60;
+
+// Longest event that can be set, one day
+// 可設定的最長活動長度，一天
+pub const MAX_EVENT_MINUTES: u32 = 1440;
+
+// Length limits of the event name and description set by Discord
+// Discord 對活動名稱與描述的長度限制
+const MAX_NAME_CHARS: usize = 100;
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+
+// A change to apply to the event of one reminder
+// 要套用到單一提醒活動的變更
+struct EventSync {
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    id: String,
+    event_id: Option<ScheduledEventId>,
+    // The occurrence to show, empty when the event should be removed
+    // 要顯示的觸發內容，為空表示應移除活動
+    target: Option<EventOccurrence>,
+    description: String,
+}
+
+// Create, move or delete the events of reminders whose next occurrence changed.
+// Failed requests are logged and not retried until the occurrence changes again
+// 為下一次觸發已改變的提醒建立、移動或刪除活動。
+// 失敗的請求會被記錄，直到觸發內容再次改變前不會重試
+pub async fn sync_events(http: &Http, reminders: &Reminders, guild_settings: &GuildSettingsMap) {
+    let now = Utc::now();
+    let pending = {
+        let settings = guild_settings.read().await;
+        let guild_reminders_map = reminders.read().await;
+        let mut pending = Vec::new();
+        for (guild_id, reminders_map) in guild_reminders_map.iter() {
+            let tz = guild_timezone(&settings, guild_id);
+            for (channel_id, reminders) in reminders_map.iter() {
+                for reminder in reminders.iter() {
+                    let Some(event) = &reminder.event else {
+                        continue;
+                    };
+                    // An occurrence waiting to be sent keeps its event until it rolls forward
+                    // 等待發送的觸發在推進到下一次之前保留原本的活動
+                    if !reminder.paused && reminder.next_due.is_some_and(|due| due <= now) {
+                        continue;
+                    }
+                    let target = occurrence(reminder, event, &tz, now);
+                    if target == event.synced {
+                        continue;
+                    }
+                    pending.push(EventSync {
+                        guild_id: *guild_id,
+                        channel_id: *channel_id,
+                        id: reminder.id.clone(),
+                        event_id: event.id,
+                        target,
+                        description: truncate(&reminder.message, MAX_DESCRIPTION_CHARS),
+                    });
+                }
+            }
+        }
+        pending
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    // Send the requests without holding the reminder lock
+    // 在不持有提醒鎖的情況下發送請求
+    let results = join_all(pending.iter().map(|sync| apply(http, sync))).await;
+
+    let mut guild_reminders_map = reminders.write().await;
+    for (sync, event_id) in pending.into_iter().zip(results) {
+        // The reminder may have been edited or removed in the meantime
+        // 這段期間提醒可能已被修改或移除
+        let Some(event) = guild_reminders_map
+            .get_mut(&sync.guild_id)
+            .and_then(|guild_reminder| guild_reminder.get_mut(&sync.channel_id))
+            .and_then(|reminders| reminders.iter_mut().find(|r| r.id == sync.id))
+            .and_then(|reminder| reminder.event.as_mut())
+        else {
+            continue;
+        };
+        event.id = event_id;
+        event.synced = sync.target;
+    }
+    if let Err(err) = save_reminders(&guild_reminders_map) {
+        println!("{} Failed to save reminders: {}", error_output(), err);
+    }
+}
+
+// Delete the event of a reminder, such as when the reminder is removed
+// 刪除提醒的活動，例如提醒被移除時
+pub async fn delete_event(http: &Http, guild_id: GuildId, event_id: ScheduledEventId) {
+    if let Err(err) = guild_id.delete_scheduled_event(http, event_id).await {
+        println!(
+            "{} Failed to delete scheduled event {}: {}",
+            error_output(),
+            event_id,
+            err
+        );
+    }
+}
+
+// The occurrence an event should show: the next one of an active channel reminder
+// 活動應顯示的觸發內容：進行中的頻道提醒的下一次觸發
+fn occurrence(
+    reminder: &Reminder,
+    event: &ReminderEvent,
+    tz: &Tz,
+    now: DateTime<Utc>,
+) -> Option<EventOccurrence> {
+    if reminder.paused || reminder.dm_user.is_some() {
+        return None;
+    }
+    let start = reminder.next_due.filter(|due| *due > now)?;
+    let name = fill_placeholders(
+        &reminder.message,
+        start.with_timezone(tz),
+        reminder.fire_count + 1,
+    );
+    Some(EventOccurrence {
+        name: truncate(
+            name.trim().lines().next().unwrap_or_default(),
+            MAX_NAME_CHARS,
+        ),
+        start,
+        end: start + TimeDelta::minutes(i64::from(event.duration.max(1))),
+    })
+}
+
+// Bring the event in line with the target, returning the ID of the event that now exists
+// 使活動符合要顯示的內容，並返回目前存在的活動 ID
+async fn apply(http: &Http, sync: &EventSync) -> Option<ScheduledEventId> {
+    let Some(target) = &sync.target else {
+        if let Some(event_id) = sync.event_id {
+            delete_event(http, sync.guild_id, event_id).await;
+        }
+        return None;
+    };
+
+    if let Some(event_id) = sync.event_id {
+        let builder = EditScheduledEvent::new()
+            .name(&target.name)
+            .description(&sync.description)
+            .start_time(target.start)
+            .end_time(target.end);
+        match sync
+            .guild_id
+            .edit_scheduled_event(http, event_id, builder)
+            .await
+        {
+            Ok(_) => return Some(event_id),
+            // The event may have been deleted or already ended, so a new one is created
+            // 活動可能已被刪除或已結束，因此建立新的活動
+            Err(err) => println!(
+                "{} Failed to edit scheduled event of reminder {}, creating a new one: {}",
+                error_output(),
+                sync.id,
+                err
+            ),
+        }
+    }
+
+    // External events show a link to the reminder channel as their location
+    // 外部活動以提醒頻道的連結作為地點
+    let builder =
+        CreateScheduledEvent::new(ScheduledEventType::External, &target.name, target.start)
+            .end_time(target.end)
+            .description(&sync.description)
+            .location(format!(
+                "https://discord.com/channels/{}/{}",
+                sync.guild_id, sync.channel_id
+            ));
+    match sync.guild_id.create_scheduled_event(http, builder).await {
+        Ok(event) => Some(event.id),
+        Err(err) => {
+            println!(
+                "{} Failed to create scheduled event of reminder {}: {}",
+                error_output(),
+                sync.id,
+                err
+            );
+            None
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    process,
+};
+
+use chrono::NaiveTime;
+use colored::*;
+use once_cell::sync::OnceCell;
+use serenity::all::{ChannelId, GuildId, UserId};
+
+use super::{
+    config::config,
+    func::{error_output, system_output},
+};
+use crate::{Cash, DeliveryRecord, GuildSettings, Reminder};
+
+mod backup;
+mod json;
+mod schema;
+mod sqlite;
+
+pub use backup::backup_dir;
+pub use json::JsonStorage;
+pub use sqlite::SqliteStorage;
+
+// Result of a storage operation
+// 存儲操作的結果
+pub type StorageResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+// Reminders of every guild and channel
+// 所有伺服器和頻道的提醒
+pub type ReminderMap = HashMap<GuildId, HashMap<ChannelId, Vec<Reminder>>>;
+
+// Debts of every guild
+// 所有伺服器的欠款
+pub type CashMap = HashMap<GuildId, Vec<Cash>>;
+
+// The backend chosen at startup
+// 啟動時選擇的存儲後端
+static STORAGE: OnceCell<Box<dyn Storage>> = OnceCell::new();
+
+// Everything the bot persists goes through a storage backend.
+// Each store is loaded and saved as a whole, a missing store loads as empty
+// 機器人保存的所有資料都經由存儲後端。
+// 每個存儲都整份加載與保存，不存在的存儲加載為空
+pub trait Storage: Send + Sync {
+    // Name of the backend shown in the log
+    // 顯示在日誌中的後端名稱
+    fn name(&self) -> &'static str;
+
+    fn load_reminders(&self) -> StorageResult<ReminderMap>;
+    fn save_reminders(&self, reminders: &ReminderMap) -> StorageResult<()>;
+
+    fn load_guild_settings(&self) -> StorageResult<HashMap<GuildId, GuildSettings>>;
+    fn save_guild_settings(&self, settings: &HashMap<GuildId, GuildSettings>) -> StorageResult<()>;
+
+    fn load_delivery_log(&self) -> StorageResult<HashMap<GuildId, VecDeque<DeliveryRecord>>>;
+    fn save_delivery_log(
+        &self,
+        log: &HashMap<GuildId, VecDeque<DeliveryRecord>>,
+    ) -> StorageResult<()>;
+
+    fn load_cash(&self) -> StorageResult<CashMap>;
+    fn save_cash(&self, cash: &CashMap) -> StorageResult<()>;
+
+    fn load_bans(&self) -> StorageResult<Vec<(UserId, NaiveTime)>>;
+    fn save_bans(&self, bans: &[(UserId, NaiveTime)]) -> StorageResult<()>;
+
+    fn load_fraud_list(&self) -> StorageResult<HashSet<UserId>>;
+    fn save_fraud_list(&self, users: &HashSet<UserId>) -> StorageResult<()>;
+
+    fn load_refuse_messages(&self) -> StorageResult<Vec<String>>;
+    fn save_refuse_messages(&self, messages: &[String]) -> StorageResult<()>;
+
+    // Guilds that registered the slash commands with !register
+    // 以 !register 註冊斜線命令的伺服器
+    fn load_guild_ids(&self) -> StorageResult<Vec<GuildId>>;
+    fn add_guild_id(&self, guild_id: GuildId) -> StorageResult<()>;
+}
+
+// Open the backend chosen by `storage.backend` ("json" by default, or "sqlite").
+// The first time the SQLite backend is opened, the existing files are copied into it
+// 開啟 `storage.backend` 選擇的後端（預設為 "json"，或 "sqlite"）。
+// 第一次開啟 SQLite 後端時，會將現有的文件複製到資料庫中
+pub fn init() -> StorageResult<()> {
+    let config = config();
+    let storage: Box<dyn Storage> = match config.storage.backend.as_str() {
+        "json" => Box::new(JsonStorage),
+        "sqlite" => {
+            let sqlite = SqliteStorage::open(&config.sqlite_path())?;
+            migrate_from_json(&sqlite)?;
+            Box::new(sqlite)
+        }
+        other => return Err(format!("unknown storage backend \"{}\"", other).into()),
+    };
+    println!(
+        "{} {} {}",
+        system_output(),
+        "Using storage backend:".green(),
+        storage.name()
+    );
+    STORAGE
+        .set(storage)
+        .map_err(|_| "storage is already initialised")?;
+    Ok(())
+}
+
+// The storage backend opened by `init`
+// 由 `init` 開啟的存儲後端
+pub fn storage() -> &'static dyn Storage {
+    STORAGE.get().expect("storage is not initialised").as_ref()
+}
+
+// Unwrap the result of opening or loading a store at startup, or stop the bot with a
+// clear error. A store that cannot be read is never replaced by an empty one, since
+// the next save would overwrite it
+// 取得啟動時開啟或加載存儲的結果，失敗時以明確的錯誤停止機器人。
+// 無法讀取的存儲絕不會以空值取代，否則下一次保存會覆蓋它
+pub fn exit_on_error<T>(what: &str, result: StorageResult<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            println!("{} Failed to {}: {}", error_output(), what, err);
+            println!(
+                "{} The bot will not start so the store is not overwritten. \
+                 Repair it or restore a backup from {}",
+                error_output(),
+                backup_dir()
+            );
+            process::exit(1);
+        }
+    }
+}
+
+// Copy every store from the JSON files into the database, once.
+// The files are left in place; if any of them fails to load, nothing is marked as
+// migrated so the next start tries again
+// 將所有存儲從 JSON 文件複製到資料庫中，只執行一次。
+// 文件會保留；若任何文件加載失敗，則不會標記為已遷移，下次啟動時會再次嘗試
+fn migrate_from_json(sqlite: &SqliteStorage) -> StorageResult<()> {
+    if sqlite.is_migrated()? {
+        return Ok(());
+    }
+    let json = JsonStorage;
+    sqlite.save_reminders(&json.load_reminders()?)?;
+    sqlite.save_guild_settings(&json.load_guild_settings()?)?;
+    sqlite.save_delivery_log(&json.load_delivery_log()?)?;
+    sqlite.save_cash(&json.load_cash()?)?;
+    sqlite.save_bans(&json.load_bans()?)?;
+    sqlite.save_fraud_list(&json.load_fraud_list()?)?;
+    sqlite.save_refuse_messages(&json.load_refuse_messages()?)?;
+    for guild_id in json.load_guild_ids()? {
+        sqlite.add_guild_id(guild_id)?;
+    }
+    sqlite.mark_migrated()?;
+    println!(
+        "{} {}",
+        system_output(),
+        "Migrated the JSON files into the SQLite database".green()
+    );
+    Ok(())
+}
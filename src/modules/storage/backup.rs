@@ -0,0 +1,96 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+
+use super::StorageResult;
+use crate::modules::config::config;
+
+// Backups kept per store, the oldest ones are removed first
+// 每個存儲保留的備份數量，最舊的備份會先被移除
+const KEEP_BACKUPS: usize = 10;
+
+// A store is backed up on its first save after startup, then at most once per interval
+// 存儲在啟動後第一次保存時備份，之後每段間隔最多備份一次
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// When each store was last backed up
+// 每個存儲上次備份的時間
+static LAST_BACKUP: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Whether the store named `name` should be backed up now, remembering that it was
+// 存儲 `name` 現在是否應該備份，並記錄已備份
+pub fn is_due(name: &str) -> bool {
+    let mut last_backup = LAST_BACKUP
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let now = Instant::now();
+    match last_backup.get(name) {
+        Some(at) if now.duration_since(*at) < BACKUP_INTERVAL => false,
+        _ => {
+            last_backup.insert(name.to_string(), now);
+            true
+        }
+    }
+}
+
+// Folder keeping the backups of every store, "backups" in the data folder
+// 保存所有存儲備份的資料夾，位於資料夾中的 "backups"
+pub fn backup_dir() -> String {
+    config().data_path("backups")
+}
+
+// Path of a new timestamped backup of the store named `name`, e.g.
+// "assets/backups/reminders.json.20250301T083000"
+// 存儲 `name` 新的帶時間戳記的備份路徑，例如 "assets/backups/reminders.json.20250301T083000"
+pub fn backup_path(name: &str) -> StorageResult<PathBuf> {
+    let backup_dir = backup_dir();
+    fs::create_dir_all(&backup_dir)?;
+    Ok(Path::new(&backup_dir).join(format!("{}.{}", name, Utc::now().format("%Y%m%dT%H%M%S"))))
+}
+
+// Copy a file into the backups if it exists and a backup is due
+// 若文件存在且應該備份，則將文件複製到備份中
+pub fn backup_file(path: &str) -> StorageResult<()> {
+    let Some(name) = Path::new(path).file_name().and_then(|name| name.to_str()) else {
+        return Ok(());
+    };
+    if !Path::new(path).exists() || !is_due(name) {
+        return Ok(());
+    }
+    fs::copy(path, backup_path(name)?)?;
+    prune(name)
+}
+
+// Remove the oldest backups of the store named `name` beyond the limit
+// 移除存儲 `name` 超過上限的最舊備份
+pub fn prune(name: &str) -> StorageResult<()> {
+    let prefix = format!("{}.", name);
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(|file_name| file_name.strip_prefix(&prefix))
+                .is_some_and(|stamp| stamp.chars().all(|c| c.is_ascii_alphanumeric()))
+        })
+        .collect();
+    // Timestamps sort in the order they were taken
+    // 時間戳記的排序即為備份的先後順序
+    backups.sort();
+    let excess = backups.len().saturating_sub(KEEP_BACKUPS);
+    for path in backups.into_iter().take(excess) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
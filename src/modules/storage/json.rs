@@ -0,0 +1,261 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use chrono::NaiveTime;
+use serde::{de::DeserializeOwned, Serialize};
+use serenity::all::{GuildId, UserId};
+
+use super::{
+    backup,
+    schema::{self, Store},
+    CashMap, ReminderMap, Storage, StorageResult,
+};
+use crate::{
+    modules::{
+        config::config,
+        func::{error_output, system_output},
+    },
+    DeliveryRecord, GuildSettings,
+};
+
+// Files of each store in the data folder, the layout the bot has always used. The JSON files
+// are saved in a versioned envelope, the refuse messages and guild IDs stay plain text with
+// one per line
+// 各個存儲在資料夾中的文件，與機器人一直以來使用的配置相同。JSON 文件以帶有版本的外層保存，
+// 拒絕訊息與伺服器 ID 仍為每行一筆的純文字
+const REMINDERS_FILE: &str = "reminders.json";
+const GUILD_SETTINGS_FILE: &str = "guild_settings.json";
+const DELIVERY_LOG_FILE: &str = "delivery_log.json";
+const BAN_LIST_FILE: &str = "ban_list.json";
+const FRAUD_LIST_FILE: &str = "fraud_list.json";
+const REFUSE_MSG_FILE: &str = "tiktok_refuse_msg.txt";
+const GUILD_IDS_FILE: &str = "guild_id.txt";
+
+// Counter making the name of each temporary file unique within the process
+// 讓行程內每個暫存文件名稱唯一的計數器
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Backend keeping each store in its own JSON or text file
+// 將每個存儲保存在各自的 JSON 或文字文件中的後端
+pub struct JsonStorage;
+
+impl Storage for JsonStorage {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn load_reminders(&self) -> StorageResult<ReminderMap> {
+        read_store(Store::Reminders, &data_path(REMINDERS_FILE))
+    }
+
+    fn save_reminders(&self, reminders: &ReminderMap) -> StorageResult<()> {
+        write_store(Store::Reminders, &data_path(REMINDERS_FILE), reminders)
+    }
+
+    fn load_guild_settings(&self) -> StorageResult<HashMap<GuildId, GuildSettings>> {
+        read_store(Store::GuildSettings, &data_path(GUILD_SETTINGS_FILE))
+    }
+
+    fn save_guild_settings(&self, settings: &HashMap<GuildId, GuildSettings>) -> StorageResult<()> {
+        write_store(
+            Store::GuildSettings,
+            &data_path(GUILD_SETTINGS_FILE),
+            settings,
+        )
+    }
+
+    fn load_delivery_log(&self) -> StorageResult<HashMap<GuildId, VecDeque<DeliveryRecord>>> {
+        read_store(Store::DeliveryLog, &data_path(DELIVERY_LOG_FILE))
+    }
+
+    fn save_delivery_log(
+        &self,
+        log: &HashMap<GuildId, VecDeque<DeliveryRecord>>,
+    ) -> StorageResult<()> {
+        write_store(Store::DeliveryLog, &data_path(DELIVERY_LOG_FILE), log)
+    }
+
+    fn load_cash(&self) -> StorageResult<CashMap> {
+        read_store(Store::Cash, &config().paths.cash_file)
+    }
+
+    fn save_cash(&self, cash: &CashMap) -> StorageResult<()> {
+        write_store(Store::Cash, &config().paths.cash_file, cash)
+    }
+
+    fn load_bans(&self) -> StorageResult<Vec<(UserId, NaiveTime)>> {
+        read_store(Store::Bans, &data_path(BAN_LIST_FILE))
+    }
+
+    fn save_bans(&self, bans: &[(UserId, NaiveTime)]) -> StorageResult<()> {
+        write_store(Store::Bans, &data_path(BAN_LIST_FILE), bans)
+    }
+
+    fn load_fraud_list(&self) -> StorageResult<HashSet<UserId>> {
+        read_store(Store::FraudList, &data_path(FRAUD_LIST_FILE))
+    }
+
+    fn save_fraud_list(&self, users: &HashSet<UserId>) -> StorageResult<()> {
+        write_store(Store::FraudList, &data_path(FRAUD_LIST_FILE), users)
+    }
+
+    // One message per line
+    // 每行一則訊息
+    fn load_refuse_messages(&self) -> StorageResult<Vec<String>> {
+        let content = read_text(&data_path(REFUSE_MSG_FILE))?;
+        Ok(content.lines().map(str::to_string).collect())
+    }
+
+    fn save_refuse_messages(&self, messages: &[String]) -> StorageResult<()> {
+        let content: String = messages.iter().map(|msg| format!("{}\n", msg)).collect();
+        write_atomic(&data_path(REFUSE_MSG_FILE), content.as_bytes())
+    }
+
+    // One guild ID per line, lines that are not IDs are skipped
+    // 每行一個伺服器 ID，略過不是 ID 的行
+    fn load_guild_ids(&self) -> StorageResult<Vec<GuildId>> {
+        let content = read_text(&data_path(GUILD_IDS_FILE))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| line.trim().parse::<u64>().ok())
+            .filter(|id| *id != 0)
+            .map(GuildId::new)
+            .collect())
+    }
+
+    fn add_guild_id(&self, guild_id: GuildId) -> StorageResult<()> {
+        let path = data_path(GUILD_IDS_FILE);
+        let mut content = read_text(&path)?;
+        let id = guild_id.to_string();
+        if content.lines().any(|line| line.trim() == id) {
+            return Ok(());
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("{}\n", id));
+        write_atomic(&path, content.as_bytes())
+    }
+}
+
+// Path of a store's file in the data folder
+// 存儲文件在資料夾中的路徑
+fn data_path(file: &str) -> String {
+    config().data_path(file)
+}
+
+// Read a text file, a missing file reads as empty
+// 讀取文字文件，不存在的文件視為空
+fn read_text(path: &str) -> StorageResult<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(format!("{}: {}", path, err).into()),
+    }
+}
+
+// Read the JSON file of a store, a missing file reads as the empty value.
+// A file that fails to parse is an error and is never treated as empty.
+// A file saved by an older version is upgraded and saved again right away
+// 讀取存儲的 JSON 文件，不存在的文件視為空值。
+// 無法解析的文件會返回錯誤，絕不視為空值。
+// 舊版本保存的文件會被升級並立即重新保存
+fn read_store<T>(store: Store, path: &str) -> StorageResult<T>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(format!("{}: {}", path, err).into()),
+    };
+    let (data, version) =
+        schema::decode::<T>(store, &content).map_err(|err| format!("{}: {}", path, err))?;
+    if version < store.version() {
+        write_store(store, path, &data)?;
+        println!(
+            "{} Upgraded {} from version {} to {}",
+            system_output(),
+            path,
+            version,
+            store.version()
+        );
+    }
+    Ok(data)
+}
+
+// Save the data of a store to its JSON file
+// 將存儲的資料保存到 JSON 文件
+fn write_store<T: Serialize + ?Sized>(store: Store, path: &str, data: &T) -> StorageResult<()> {
+    let json_content = schema::encode(store, data)?;
+    write_atomic(path, json_content.as_bytes())
+}
+
+// Replace a file without ever leaving it half written: the content goes to a temporary
+// file of its own that is flushed to disk and then renamed over the old one, and the
+// folder is flushed so the rename survives a crash. The old file is backed up first when
+// a backup is due
+// 以不會留下寫到一半的文件的方式替換文件：內容先寫入專屬的暫存文件並寫入磁碟，再重新命名覆蓋
+// 舊文件，並將資料夾寫入磁碟，讓重新命名在當機後仍然保留。需要備份時會先備份舊文件
+fn write_atomic(path: &str, content: &[u8]) -> StorageResult<()> {
+    create_parent(path)?;
+    // A failed backup is logged but does not stop the save
+    // 備份失敗會被記錄，但不會中止保存
+    if let Err(err) = backup::backup_file(path) {
+        println!("{} Failed to back up {}: {}", error_output(), path, err);
+    }
+    // Saves running at the same time each write their own temporary file in the same folder
+    // 同時進行的保存各自在同一資料夾中寫入自己的暫存文件
+    let temp_path = format!(
+        "{}.{}.{}.tmp",
+        path,
+        process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
+    sync_parent(path)?;
+    Ok(())
+}
+
+// Flush the folder of a file to disk so a rename in it is not lost on a crash
+// 將文件所在的資料夾寫入磁碟，讓其中的重新命名不會因當機而遺失
+#[cfg(unix)]
+fn sync_parent(path: &str) -> io::Result<()> {
+    let parent = match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+// Folders cannot be opened as files on other platforms, the rename is left to the file system
+// 其他平台無法將資料夾當作文件開啟，重新命名交由文件系統處理
+#[cfg(not(unix))]
+fn sync_parent(_path: &str) -> io::Result<()> {
+    Ok(())
+}
+
+// Ensure the folder of a file exists
+// 確保文件所在的資料夾存在
+fn create_parent(path: &str) -> io::Result<()> {
+    match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,403 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use super::StorageResult;
+
+// Stores kept as JSON, each saved in an envelope with the version of its shape:
+// {"version": 1, "data": ...}
+// 以 JSON 保存的存儲，每個都以帶有資料格式版本的外層保存：{"version": 1, "data": ...}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Store {
+    Reminders,
+    GuildSettings,
+    DeliveryLog,
+    Cash,
+    Bans,
+    FraudList,
+}
+
+// Upgrade the data of a store by one version
+// 將存儲的資料升級一個版本
+type Migration = fn(Value) -> StorageResult<Value>;
+
+impl Store {
+    // Name of the store shown in errors and used as its key in the database
+    // 顯示在錯誤中的存儲名稱，也是資料庫中使用的鍵
+    pub fn name(self) -> &'static str {
+        match self {
+            Store::Reminders => "reminders",
+            Store::GuildSettings => "guild_settings",
+            Store::DeliveryLog => "delivery_log",
+            Store::Cash => "cash",
+            Store::Bans => "ban_list",
+            Store::FraudList => "fraud_list",
+        }
+    }
+
+    // Migrations in order, the one at index n upgrades version n to n + 1.
+    // Version 0 is the bare data written before stores had a version.
+    // Add a migration here whenever the shape of a store changes
+    // 依序排列的遷移，索引 n 的遷移將版本 n 升級為 n + 1。
+    // 版本 0 是存儲加上版本之前寫入的資料。
+    // 每當存儲的資料格式改變時，在此加入遷移
+    fn migrations(self) -> &'static [Migration] {
+        match self {
+            Store::Reminders => &[reminders_v1],
+            Store::GuildSettings
+            | Store::DeliveryLog
+            | Store::Cash
+            | Store::Bans
+            | Store::FraudList => &[unchanged],
+        }
+    }
+
+    // Version of the shape the store is saved with
+    // 存儲保存時使用的資料格式版本
+    pub fn version(self) -> u32 {
+        self.migrations().len() as u32
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T: ?Sized> {
+    version: u32,
+    data: &'a T,
+}
+
+// Serialize the data of a store in an envelope with the current version
+// 將存儲的資料以帶有目前版本的外層序列化
+pub fn encode<T: Serialize + ?Sized>(store: Store, data: &T) -> StorageResult<String> {
+    Ok(serde_json::to_string(&Envelope {
+        version: store.version(),
+        data,
+    })?)
+}
+
+// Parse a store, upgrading data saved by older versions of the bot.
+// Returns the data and the version it was saved with
+// 解析存儲，並升級舊版機器人保存的資料。返回資料與保存時的版本
+pub fn decode<T: DeserializeOwned>(store: Store, content: &str) -> StorageResult<(T, u32)> {
+    let (version, data) = split_envelope(serde_json::from_str(content)?);
+    let data = upgrade(store, version, data)?;
+    Ok((serde_json::from_value(data)?, version))
+}
+
+// Upgrade the data of a store from `version` to the current version.
+// Data saved by a newer version of the bot is refused rather than read partially
+// 將存儲的資料從 `version` 升級為目前版本。
+// 新版機器人保存的資料會被拒絕，而不是只讀取部分內容
+pub fn upgrade(store: Store, version: u32, data: Value) -> StorageResult<Value> {
+    let migrations = store.migrations();
+    if version > store.version() {
+        return Err(format!(
+            "{} was saved by a newer version of the bot (version {}, this build reads up to version {})",
+            store.name(),
+            version,
+            store.version()
+        )
+        .into());
+    }
+    migrations[version as usize..]
+        .iter()
+        .try_fold(data, |data, migrate| migrate(data))
+}
+
+// Split an envelope into its version and data, anything else is bare data of version 0
+// 將外層拆分為版本與資料，其他內容視為版本 0 的資料
+fn split_envelope(value: Value) -> (u32, Value) {
+    match value {
+        Value::Object(mut map) if map.len() == 2 && map.contains_key("data") => {
+            match map
+                .get("version")
+                .and_then(Value::as_u64)
+                .and_then(|version| u32::try_from(version).ok())
+            {
+                Some(version) => (version, map.remove("data").unwrap_or_default()),
+                None => (0, Value::Object(map)),
+            }
+        }
+        value => (0, value),
+    }
+}
+
+// Version 0 to 1 of stores whose data did not change, only the envelope is added
+// 資料未改變的存儲從版本 0 升級為 1，只加上外層
+fn unchanged(data: Value) -> StorageResult<Value> {
+    Ok(data)
+}
+
+// Version 0 to 1 of reminders: reminders saved before recurrence rules keep their
+// schedule in `weekdays` or the `date` of a one-shot reminder, which become a rule
+// 提醒從版本 0 升級為 1：在週期規則之前保存的提醒將排程存放在 `weekdays` 或單次提醒的
+// `date` 中，這些會轉換為週期規則
+fn reminders_v1(mut data: Value) -> StorageResult<Value> {
+    let reminders = data
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|guilds| guilds.values_mut())
+        .filter_map(Value::as_object_mut)
+        .flat_map(|channels| channels.values_mut())
+        .filter_map(Value::as_array_mut)
+        .flatten()
+        .filter_map(Value::as_object_mut);
+    for reminder in reminders {
+        let weekdays = reminder
+            .remove("weekdays")
+            .filter(|weekdays| weekdays.as_array().is_some_and(|w| !w.is_empty()));
+        let date = reminder.remove("date").filter(|date| !date.is_null());
+        let recurrence = match (date, weekdays) {
+            (Some(date), _) => json!({ "kind": "once", "date": date }),
+            (None, Some(weekdays)) => {
+                json!({ "kind": "weekly", "weekdays": weekdays, "interval": 1, "anchor": null })
+            }
+            (None, None) => continue,
+        };
+        reminder.insert("recurrence".to_string(), recurrence);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    use chrono::{NaiveDate, NaiveTime, Weekday};
+    use serenity::all::{ChannelId, GuildId, UserId};
+
+    use super::*;
+    use crate::{
+        modules::storage::{CashMap, ReminderMap},
+        DeliveryRecord, GuildSettings, Recurrence, Reminder,
+    };
+
+    // Files saved by each historical version, kept as they were written
+    // 各個歷史版本保存的文件，保持寫入時的原樣
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/storage/",
+                $name
+            ))
+        };
+    }
+
+    const GUILD: GuildId = GuildId::new(1143403544599334992);
+    const CHANNEL: ChannelId = ChannelId::new(1368144725520945182);
+    const USER: UserId = UserId::new(412803325768237066);
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(h: u32, m: u32, s: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, s).unwrap()
+    }
+
+    fn reminders(content: &str) -> (Vec<Reminder>, u32) {
+        let (mut reminders, version) = decode::<ReminderMap>(Store::Reminders, content).unwrap();
+        let list = reminders
+            .get_mut(&GUILD)
+            .and_then(|channels| channels.remove(&CHANNEL))
+            .unwrap();
+        (list, version)
+    }
+
+    #[test]
+    fn reminders_v0_baseline_weekdays_become_weekly_rule() {
+        let (list, version) = reminders(fixture!("reminders.v0.baseline.json"));
+        assert_eq!(version, 0);
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list[0].recurrence,
+            Recurrence::Weekly {
+                weekdays: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+                interval: 1,
+                anchor: None,
+            }
+        );
+        assert_eq!(list[0].time, time(8, 30, 0));
+        assert_eq!(list[0].message, "Stand-up");
+        assert_eq!(list[0].last_executed, Some(date(2025, 3, 3)));
+        // No weekdays and no date left the rule at its default
+        // 沒有星期也沒有日期時，週期規則維持預設值
+        assert_eq!(list[1].recurrence, Recurrence::default());
+        assert_eq!(list[1].last_executed, None);
+    }
+
+    #[test]
+    fn reminders_v0_date_becomes_one_shot_rule() {
+        let (list, version) = reminders(fixture!("reminders.v0.once.json"));
+        assert_eq!(version, 0);
+        assert_eq!(list[0].id, "a1b2c3");
+        assert_eq!(
+            list[0].recurrence,
+            Recurrence::Once {
+                date: date(2025, 4, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn reminders_v0_with_rule_keeps_it() {
+        let (list, version) = reminders(fixture!("reminders.v0.recurrence.json"));
+        assert_eq!(version, 0);
+        let reminder = &list[0];
+        assert_eq!(reminder.recurrence, Recurrence::MonthlyDay { day: 31 });
+        assert_eq!(reminder.creator, Some(USER));
+        assert_eq!(reminder.fire_count, 2);
+        assert!(reminder.paused);
+    }
+
+    #[test]
+    fn reminders_v1_is_read_as_is() {
+        let (list, version) = reminders(fixture!("reminders.v1.json"));
+        assert_eq!(version, 1);
+        assert_eq!(
+            list[0].recurrence,
+            Recurrence::Weekly {
+                weekdays: vec![Weekday::Tue],
+                interval: 2,
+                anchor: Some(date(2025, 1, 7)),
+            }
+        );
+        assert_eq!(list[0].time, time(18, 15, 0));
+    }
+
+    #[test]
+    fn guild_settings_v0() {
+        let (settings, version) = decode::<HashMap<GuildId, GuildSettings>>(
+            Store::GuildSettings,
+            fixture!("guild_settings.v0.json"),
+        )
+        .unwrap();
+        assert_eq!(version, 0);
+        let settings = &settings[&GUILD];
+        assert_eq!(settings.timezone.as_deref(), Some("Asia/Taipei"));
+        assert_eq!(
+            settings.holidays.get(&date(2025, 1, 1)).map(String::as_str),
+            Some("New Year")
+        );
+        assert!(!settings.allow_everyone);
+        assert_eq!(settings.manager_role, None);
+        assert_eq!(settings.honeypot_channel, None);
+        assert_eq!(settings.voice_log_channel, None);
+        assert_eq!(settings.voice_ban_channel, None);
+        assert!(!settings.tiktok_refuse);
+        assert_eq!(settings.ban_protected_role, None);
+        assert_eq!(settings.ban_protected_user, None);
+    }
+
+    #[test]
+    fn guild_settings_v1_is_read_as_is() {
+        let (settings, version) = decode::<HashMap<GuildId, GuildSettings>>(
+            Store::GuildSettings,
+            fixture!("guild_settings.v1.json"),
+        )
+        .unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(settings.len(), 2);
+        let settings = &settings[&GUILD];
+        assert_eq!(
+            settings.honeypot_channel,
+            Some(ChannelId::new(1470829664527192999))
+        );
+        assert_eq!(settings.voice_log_channel, None);
+        assert!(!settings.tiktok_refuse);
+        assert_eq!(settings.ban_protected_user, None);
+    }
+
+    #[test]
+    fn delivery_log_v0() {
+        let (log, version) = decode::<HashMap<GuildId, VecDeque<DeliveryRecord>>>(
+            Store::DeliveryLog,
+            fixture!("delivery_log.v0.json"),
+        )
+        .unwrap();
+        assert_eq!(version, 0);
+        let records = &log[&GUILD];
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].channel_id, CHANNEL);
+        assert_eq!(records[0].reminder_id, "a1b2c3");
+        assert_eq!(records[0].error, None);
+        assert_eq!(records[1].attempt, 3);
+        assert_eq!(records[1].error.as_deref(), Some("Missing Access"));
+    }
+
+    #[test]
+    fn cash_v0_and_v1() {
+        for (content, expected_version) in
+            [(fixture!("cash.v0.json"), 0), (fixture!("cash.v1.json"), 1)]
+        {
+            let (cash, version) = decode::<CashMap>(Store::Cash, content).unwrap();
+            assert_eq!(version, expected_version);
+            let debts = &cash[&GUILD];
+            assert_eq!(debts.len(), 1);
+            assert_eq!(debts[0].creator, USER);
+            assert_eq!(debts[0].debtor, "Alice");
+            assert_eq!(debts[0].creditor, "Bob");
+            assert_eq!(debts[0].debt, 250);
+            assert_eq!(debts[0].ps, "Lunch");
+        }
+    }
+
+    #[test]
+    fn ban_list_v0_and_v1() {
+        let (bans, version) =
+            decode::<Vec<(UserId, NaiveTime)>>(Store::Bans, fixture!("ban_list.v0.json")).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(
+            bans,
+            vec![
+                (USER, time(23, 59, 0)),
+                (
+                    UserId::new(1470829664527192167),
+                    NaiveTime::from_hms_milli_opt(6, 30, 15, 250).unwrap()
+                ),
+            ]
+        );
+        let (bans, version) =
+            decode::<Vec<(UserId, NaiveTime)>>(Store::Bans, fixture!("ban_list.v1.json")).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(bans, vec![(USER, time(23, 59, 0))]);
+    }
+
+    #[test]
+    fn fraud_list_v0() {
+        let (users, version) =
+            decode::<HashSet<UserId>>(Store::FraudList, fixture!("fraud_list.v0.json")).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(
+            users,
+            HashSet::from([USER, UserId::new(1470833491779256442)])
+        );
+    }
+
+    #[test]
+    fn encoded_stores_decode_at_current_version() {
+        let (list, _) = reminders(fixture!("reminders.v0.baseline.json"));
+        let reminders: ReminderMap = HashMap::from([(GUILD, HashMap::from([(CHANNEL, list)]))]);
+        let content = encode(Store::Reminders, &reminders).unwrap();
+        let (decoded, version) = decode::<ReminderMap>(Store::Reminders, &content).unwrap();
+        assert_eq!(version, Store::Reminders.version());
+        assert_eq!(
+            decoded[&GUILD][&CHANNEL][0].recurrence,
+            reminders[&GUILD][&CHANNEL][0].recurrence
+        );
+        // Upgraded reminders are saved without the old fields
+        // 升級後的提醒保存時不包含舊欄位
+        let saved: Value = serde_json::from_str(&content).unwrap();
+        let saved = &saved["data"][GUILD.to_string()][CHANNEL.to_string()][0];
+        assert!(saved.get("weekdays").is_none());
+        assert!(saved.get("date").is_none());
+    }
+
+    #[test]
+    fn newer_version_is_refused() {
+        let content = format!("{{\"version\":{},\"data\":[]}}", Store::Bans.version() + 1);
+        let err = decode::<Vec<(UserId, NaiveTime)>>(Store::Bans, &content).unwrap_err();
+        assert!(err.to_string().contains("newer version"));
+    }
+}
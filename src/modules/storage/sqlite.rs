@@ -0,0 +1,508 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use chrono::NaiveTime;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde_json::Value;
+use serenity::all::{GuildId, UserId};
+
+use super::{
+    backup,
+    schema::{self, Store},
+    CashMap, ReminderMap, Storage, StorageResult,
+};
+use crate::{modules::func::error_output, Cash, DeliveryRecord, GuildSettings};
+
+// Tables of the database. Reminders, settings and log records are kept as JSON in a
+// `data` column since their fields keep growing; `position` keeps the order of lists
+// 資料庫的資料表。提醒、設定與發送紀錄的欄位會持續增加，因此以 JSON 保存在 `data` 欄位；
+// `position` 保留列表的順序
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS reminders (
+        guild_id INTEGER NOT NULL,
+        channel_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (guild_id, channel_id, position)
+    );
+    CREATE TABLE IF NOT EXISTS guild_settings (
+        guild_id INTEGER PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS delivery_log (
+        guild_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (guild_id, position)
+    );
+    CREATE TABLE IF NOT EXISTS cash (
+        guild_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        creator INTEGER NOT NULL,
+        debtor TEXT NOT NULL,
+        creditor TEXT NOT NULL,
+        debt INTEGER NOT NULL,
+        ps TEXT NOT NULL,
+        PRIMARY KEY (guild_id, position)
+    );
+    CREATE TABLE IF NOT EXISTS bans (
+        position INTEGER PRIMARY KEY,
+        user_id INTEGER NOT NULL,
+        until TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS fraud_list (
+        user_id INTEGER PRIMARY KEY
+    );
+    CREATE TABLE IF NOT EXISTS refuse_messages (
+        position INTEGER PRIMARY KEY,
+        message TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS guild_ids (
+        guild_id INTEGER PRIMARY KEY
+    );
+";
+
+// Statements upgrading the tables, those at index n upgrade schema version n + 1 to n + 2.
+// Add statements here whenever a table changes
+// 升級資料表的語句，索引 n 的語句將資料表版本 n + 1 升級為 n + 2。
+// 每當資料表改變時，在此加入語句
+const SCHEMA_MIGRATIONS: &[&str] = &[];
+
+// Key in `meta` holding the version of the tables
+// `meta` 中保存資料表版本的鍵
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+// Key in `meta` set once the JSON files have been copied into the database
+// JSON 文件複製到資料庫後在 `meta` 中設定的鍵
+const MIGRATED_KEY: &str = "migrated_from_json";
+
+// Version of rows in the `data` columns saved before their version was recorded
+// 在記錄版本之前保存的 `data` 欄位資料的版本
+const UNVERSIONED_DATA: u32 = 1;
+
+// Backend keeping every store in one embedded SQLite database
+// 將所有存儲保存在一個內嵌 SQLite 資料庫中的後端
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    // File name of the database, used to name its backups
+    // 資料庫的文件名稱，用於命名備份
+    name: String,
+}
+
+impl SqliteStorage {
+    // Open the database, creating it and its tables if needed
+    // 開啟資料庫，必要時建立資料庫與資料表
+    pub fn open(path: &str) -> StorageResult<Self> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        migrate_schema(&conn)?;
+        let name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "storage.db".to_string());
+        Ok(Self {
+            conn: Mutex::new(conn),
+            name,
+        })
+    }
+
+    // Whether the JSON files were already copied into the database
+    // JSON 文件是否已複製到資料庫
+    pub fn is_migrated(&self) -> StorageResult<bool> {
+        Ok(get_meta(&self.conn(), MIGRATED_KEY)?.is_some())
+    }
+
+    // Remember that the JSON files were copied into the database
+    // 記錄 JSON 文件已複製到資料庫
+    pub fn mark_migrated(&self) -> StorageResult<()> {
+        set_meta(&self.conn(), MIGRATED_KEY, &chrono::Utc::now().to_rfc3339())
+    }
+
+    // A panic while holding the lock leaves the connection usable, so a poisoned lock is taken over
+    // 持有鎖時發生 panic 不會影響連線，因此直接接手被污染的鎖
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Run `write` in one transaction, so a crash leaves either the old or the new rows.
+    // The database is backed up first when a backup is due
+    // 在單一交易中執行 `write`，發生崩潰時只會留下舊的或新的資料。
+    // 需要備份時會先備份資料庫
+    fn write<F>(&self, write: F) -> StorageResult<()>
+    where
+        F: FnOnce(&Transaction) -> StorageResult<()>,
+    {
+        let mut conn = self.conn();
+        self.backup(&conn);
+        let tx = conn.transaction()?;
+        write(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Copy the database into the backups, a failed backup is logged but does not stop the save
+    // 將資料庫複製到備份中，備份失敗會被記錄，但不會中止保存
+    fn backup(&self, conn: &Connection) {
+        if !backup::is_due(&self.name) {
+            return;
+        }
+        let result = backup::backup_path(&self.name).and_then(|path| {
+            conn.execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
+            backup::prune(&self.name)
+        });
+        if let Err(err) = result {
+            println!(
+                "{} Failed to back up {}: {}",
+                error_output(),
+                self.name,
+                err
+            );
+        }
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn load_reminders(&self) -> StorageResult<ReminderMap> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT guild_id, channel_id, data FROM reminders
+             ORDER BY guild_id, channel_id, position",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut reminders: HashMap<String, HashMap<String, Vec<Value>>> = HashMap::new();
+        for row in rows {
+            let (guild_id, channel_id, data) = row?;
+            reminders
+                .entry(guild_id.to_string())
+                .or_default()
+                .entry(channel_id.to_string())
+                .or_default()
+                .push(serde_json::from_str(&data)?);
+        }
+        load_data(&conn, Store::Reminders, serde_json::to_value(reminders)?)
+    }
+
+    fn save_reminders(&self, reminders: &ReminderMap) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM reminders", [])?;
+            set_data_version(tx, Store::Reminders)?;
+            let mut stmt = tx.prepare(
+                "INSERT INTO reminders (guild_id, channel_id, position, data)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (guild_id, channels) in reminders {
+                for (channel_id, list) in channels {
+                    for (position, reminder) in list.iter().enumerate() {
+                        stmt.execute(params![
+                            guild_id.get() as i64,
+                            channel_id.get() as i64,
+                            position as i64,
+                            serde_json::to_string(reminder)?,
+                        ])?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn load_guild_settings(&self) -> StorageResult<HashMap<GuildId, GuildSettings>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT guild_id, data FROM guild_settings")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut settings: HashMap<String, Value> = HashMap::new();
+        for row in rows {
+            let (guild_id, data) = row?;
+            settings.insert(guild_id.to_string(), serde_json::from_str(&data)?);
+        }
+        load_data(&conn, Store::GuildSettings, serde_json::to_value(settings)?)
+    }
+
+    fn save_guild_settings(&self, settings: &HashMap<GuildId, GuildSettings>) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM guild_settings", [])?;
+            set_data_version(tx, Store::GuildSettings)?;
+            let mut stmt =
+                tx.prepare("INSERT INTO guild_settings (guild_id, data) VALUES (?1, ?2)")?;
+            for (guild_id, guild_settings) in settings {
+                stmt.execute(params![
+                    guild_id.get() as i64,
+                    serde_json::to_string(guild_settings)?,
+                ])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_delivery_log(&self) -> StorageResult<HashMap<GuildId, VecDeque<DeliveryRecord>>> {
+        let conn = self.conn();
+        let mut stmt =
+            conn.prepare("SELECT guild_id, data FROM delivery_log ORDER BY guild_id, position")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut log: HashMap<String, Vec<Value>> = HashMap::new();
+        for row in rows {
+            let (guild_id, data) = row?;
+            log.entry(guild_id.to_string())
+                .or_default()
+                .push(serde_json::from_str(&data)?);
+        }
+        load_data(&conn, Store::DeliveryLog, serde_json::to_value(log)?)
+    }
+
+    fn save_delivery_log(
+        &self,
+        log: &HashMap<GuildId, VecDeque<DeliveryRecord>>,
+    ) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM delivery_log", [])?;
+            set_data_version(tx, Store::DeliveryLog)?;
+            let mut stmt = tx.prepare(
+                "INSERT INTO delivery_log (guild_id, position, data) VALUES (?1, ?2, ?3)",
+            )?;
+            for (guild_id, records) in log {
+                for (position, record) in records.iter().enumerate() {
+                    stmt.execute(params![
+                        guild_id.get() as i64,
+                        position as i64,
+                        serde_json::to_string(record)?,
+                    ])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn load_cash(&self) -> StorageResult<CashMap> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT guild_id, creator, debtor, creditor, debt, ps FROM cash
+             ORDER BY guild_id, position",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let guild_id = GuildId::new(row.get::<_, i64>(0)? as u64);
+            let cash = Cash {
+                creator: UserId::new(row.get::<_, i64>(1)? as u64),
+                debtor: row.get(2)?,
+                creditor: row.get(3)?,
+                debt: row.get::<_, i64>(4)? as usize,
+                ps: row.get(5)?,
+            };
+            Ok((guild_id, cash))
+        })?;
+        let mut cash = CashMap::new();
+        for row in rows {
+            let (guild_id, debt) = row?;
+            cash.entry(guild_id).or_default().push(debt);
+        }
+        Ok(cash)
+    }
+
+    fn save_cash(&self, cash: &CashMap) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM cash", [])?;
+            let mut stmt = tx.prepare(
+                "INSERT INTO cash (guild_id, position, creator, debtor, creditor, debt, ps)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (guild_id, list) in cash {
+                for (position, debt) in list.iter().enumerate() {
+                    stmt.execute(params![
+                        guild_id.get() as i64,
+                        position as i64,
+                        debt.creator.get() as i64,
+                        debt.debtor,
+                        debt.creditor,
+                        debt.debt as i64,
+                        debt.ps,
+                    ])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn load_bans(&self) -> StorageResult<Vec<(UserId, NaiveTime)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT user_id, until FROM bans ORDER BY position")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut bans = Vec::new();
+        for row in rows {
+            let (user_id, until) = row?;
+            bans.push((UserId::new(user_id as u64), until.parse::<NaiveTime>()?));
+        }
+        Ok(bans)
+    }
+
+    fn save_bans(&self, bans: &[(UserId, NaiveTime)]) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM bans", [])?;
+            let mut stmt =
+                tx.prepare("INSERT INTO bans (position, user_id, until) VALUES (?1, ?2, ?3)")?;
+            for (position, (user_id, until)) in bans.iter().enumerate() {
+                stmt.execute(params![
+                    position as i64,
+                    user_id.get() as i64,
+                    until.to_string(),
+                ])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_fraud_list(&self) -> StorageResult<HashSet<UserId>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT user_id FROM fraud_list")?;
+        let rows = stmt.query_map([], |row| row.get::<_, i64>(0))?;
+        let mut users = HashSet::new();
+        for row in rows {
+            users.insert(UserId::new(row? as u64));
+        }
+        Ok(users)
+    }
+
+    fn save_fraud_list(&self, users: &HashSet<UserId>) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM fraud_list", [])?;
+            let mut stmt = tx.prepare("INSERT INTO fraud_list (user_id) VALUES (?1)")?;
+            for user_id in users {
+                stmt.execute(params![user_id.get() as i64])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_refuse_messages(&self) -> StorageResult<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT message FROM refuse_messages ORDER BY position")?;
+        let messages = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(messages)
+    }
+
+    fn save_refuse_messages(&self, messages: &[String]) -> StorageResult<()> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM refuse_messages", [])?;
+            let mut stmt =
+                tx.prepare("INSERT INTO refuse_messages (position, message) VALUES (?1, ?2)")?;
+            for (position, message) in messages.iter().enumerate() {
+                stmt.execute(params![position as i64, message])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_guild_ids(&self) -> StorageResult<Vec<GuildId>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT guild_id FROM guild_ids ORDER BY guild_id")?;
+        let guild_ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .map(|row| row.map(|id| GuildId::new(id as u64)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(guild_ids)
+    }
+
+    fn add_guild_id(&self, guild_id: GuildId) -> StorageResult<()> {
+        self.conn().execute(
+            "INSERT OR IGNORE INTO guild_ids (guild_id) VALUES (?1)",
+            params![guild_id.get() as i64],
+        )?;
+        Ok(())
+    }
+}
+
+// Bring the tables up to the current schema version. Databases made before the version
+// was recorded have the first version; a database from a newer version is refused
+// 將資料表升級為目前的版本。在記錄版本之前建立的資料庫為第一個版本；
+// 新版機器人建立的資料庫會被拒絕
+fn migrate_schema(conn: &Connection) -> StorageResult<()> {
+    let current = SCHEMA_MIGRATIONS.len() as u32 + 1;
+    let version = match get_meta(conn, SCHEMA_VERSION_KEY)? {
+        Some(version) => version.parse::<u32>()?.max(1),
+        None => 1,
+    };
+    if version > current {
+        return Err(format!(
+            "the database was saved by a newer version of the bot (schema version {}, this build reads up to version {})",
+            version, current
+        )
+        .into());
+    }
+    let tx = conn.unchecked_transaction()?;
+    for statements in &SCHEMA_MIGRATIONS[(version - 1) as usize..] {
+        tx.execute_batch(statements)?;
+    }
+    set_meta(&tx, SCHEMA_VERSION_KEY, &current.to_string())?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Upgrade the rows of a store kept in `data` columns and parse them
+// 升級保存在 `data` 欄位中的存儲資料並解析
+fn load_data<T: serde::de::DeserializeOwned>(
+    conn: &Connection,
+    store: Store,
+    data: Value,
+) -> StorageResult<T> {
+    let key = format!("version:{}", store.name());
+    let version = match get_meta(conn, &key)? {
+        Some(version) => version.parse::<u32>()?,
+        None => UNVERSIONED_DATA,
+    };
+    let data = schema::upgrade(store, version, data)?;
+    Ok(serde_json::from_value(data)?)
+}
+
+// Record that the rows of a store are saved with the current version
+// 記錄存儲的資料以目前版本保存
+fn set_data_version(conn: &Connection, store: Store) -> StorageResult<()> {
+    let key = format!("version:{}", store.name());
+    set_meta(conn, &key, &store.version().to_string())
+}
+
+fn get_meta(conn: &Connection, key: &str) -> StorageResult<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> StorageResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
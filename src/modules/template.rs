@@ -0,0 +1,185 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use chrono::{DateTime, Datelike, Weekday};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::all::{CreateAllowedMentions, CreateEmbed, CreateMessage, GuildId, RoleId, UserId};
+
+use crate::{Reminder, ReminderMentions};
+
+// Matches a role mention such as "<@&123>"
+// 符合身分組提及，例如「<@&123>」
+static ROLE_MENTION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<@&(\d+)>$").unwrap());
+
+// Matches a member mention such as "<@123>" or "<@!123>"
+// 符合成員提及，例如「<@123>」或「<@!123>」
+static USER_MENTION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<@!?(\d+)>$").unwrap());
+
+// Build the message sent for one occurrence of a reminder;
+// `prefix` is put in front of the content, such as the delayed marker
+// 建立提醒單次觸發時發送的訊息；
+// `prefix` 會放在內容前方，例如延遲標記
+pub fn render_message(
+    reminder: &Reminder,
+    fire_at: DateTime<Tz>,
+    allow_everyone: bool,
+    prefix: Option<&str>,
+) -> CreateMessage {
+    let count = reminder.fire_count + 1;
+    let text = fill_placeholders(&reminder.message, fire_at, count);
+    let everyone = allow_everyone && reminder.mentions.everyone;
+
+    let mut content: Vec<String> = prefix.map(str::to_string).into_iter().collect();
+    content.extend(
+        reminder
+            .mentions
+            .roles
+            .iter()
+            .map(|role| format!("<@&{}>", role)),
+    );
+    content.extend(
+        reminder
+            .mentions
+            .users
+            .iter()
+            .map(|user| format!("<@{}>", user)),
+    );
+    if everyone {
+        content.push("@everyone".to_string());
+    }
+
+    // Roles and members written in the message still ping, @everyone and @here only when allowed
+    // 訊息中的身分組與成員仍會被提及，@everyone 與 @here 只有在允許時才會提及
+    let allowed_mentions = CreateAllowedMentions::new()
+        .all_roles(true)
+        .all_users(true)
+        .everyone(everyone);
+    let mut builder = CreateMessage::new().allowed_mentions(allowed_mentions);
+
+    match &reminder.embed {
+        Some(embed) => {
+            let mut create_embed = CreateEmbed::new().description(text);
+            if let Some(title) = &embed.title {
+                create_embed = create_embed.title(fill_placeholders(title, fire_at, count));
+            }
+            if let Some(color) = embed.color {
+                create_embed = create_embed.color(color);
+            }
+            if let Some(image_url) = &embed.image_url {
+                create_embed = create_embed.image(image_url);
+            }
+            builder = builder.embed(create_embed);
+        }
+        None => content.push(text),
+    }
+
+    if !content.is_empty() {
+        builder = builder.content(content.join(" "));
+    }
+    builder
+}
+
+// Replace {date}, {weekday}, {time} and {count} with the values of this occurrence
+// 將 {date}、{weekday}、{time} 與 {count} 替換為本次觸發的值
+pub fn fill_placeholders(template: &str, fire_at: DateTime<Tz>, count: u32) -> String {
+    template
+        .replace("{date}", &fire_at.format("%Y-%m-%d").to_string())
+        .replace("{weekday}", weekday_name(fire_at.weekday()))
+        .replace("{time}", &fire_at.format("%H:%M").to_string())
+        .replace("{count}", &count.to_string())
+}
+
+// Parse mentions separated by spaces or commas, such as "<@&123> <@456> @everyone"
+// 解析以空白或逗號分隔的提及，例如「<@&123> <@456> @everyone」
+pub fn parse_mentions(input: &str, guild_id: GuildId) -> Result<ReminderMentions, String> {
+    let mut mentions = ReminderMentions::default();
+    for token in input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+    {
+        if matches!(
+            token.to_lowercase().as_str(),
+            "@everyone" | "@here" | "everyone" | "here"
+        ) {
+            mentions.everyone = true;
+        } else if let Some(caps) = ROLE_MENTION_RE.captures(token) {
+            let role = caps[1].parse::<u64>().map_err(|_| invalid_mention(token))?;
+            // The @everyone role has the same ID as the guild
+            // @everyone 身分組的 ID 與伺服器相同
+            if role == guild_id.get() {
+                mentions.everyone = true;
+            } else if !mentions.roles.contains(&RoleId::new(role)) {
+                mentions.roles.push(RoleId::new(role));
+            }
+        } else if let Some(caps) = USER_MENTION_RE.captures(token) {
+            let user = caps[1].parse::<u64>().map_err(|_| invalid_mention(token))?;
+            if !mentions.users.contains(&UserId::new(user)) {
+                mentions.users.push(UserId::new(user));
+            }
+        } else {
+            return Err(invalid_mention(token));
+        }
+    }
+    Ok(mentions)
+}
+
+// Parse an embed colour written as "#ff8800", "0xff8800" or "ff8800"
+// 解析嵌入訊息的顏色，格式為「#ff8800」、「0xff8800」或「ff8800」
+pub fn parse_color(input: &str) -> Result<u32, String> {
+    let hex = input.trim();
+    let hex = hex
+        .strip_prefix('#')
+        .or_else(|| hex.strip_prefix("0x"))
+        .unwrap_or(hex);
+    match u32::from_str_radix(hex, 16) {
+        Ok(color) if hex.len() == 6 => Ok(color),
+        _ => Err(format!("無效的顏色：'{}'，ex. #ff8800", input.trim())),
+    }
+}
+
+// Format how long before an occurrence a lead alert is sent, such as "1 小時 30 分鐘"
+// 格式化預告在觸發前多久發送，例如「1 小時 30 分鐘」
+pub fn format_lead_time(minutes: u32) -> String {
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{} 天", days));
+    }
+    if hours > 0 {
+        parts.push(format!("{} 小時", hours));
+    }
+    if minutes > 0 || parts.is_empty() {
+        parts.push(format!("{} 分鐘", minutes));
+    }
+    parts.join(" ")
+}
+
+// Chinese name of a weekday
+// 星期的中文名稱
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "週一",
+        Weekday::Tue => "週二",
+        Weekday::Wed => "週三",
+        Weekday::Thu => "週四",
+        Weekday::Fri => "週五",
+        Weekday::Sat => "週六",
+        Weekday::Sun => "週日",
+    }
+}
+
+// Cut text to at most `max` characters
+// 將文字截斷為最多 `max` 個字元
+pub fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => text[..index].to_string(),
+        None => text.to_string(),
+    }
+}
+
+// Error message for a token that is not a mention
+// 無法辨識為提及時的錯誤訊息
+fn invalid_mention(token: &str) -> String {
+    format!("無法辨識的提及：'{}'，請使用 @身分組 或 @成員", token)
+}
@@ -0,0 +1,869 @@
+// Import necessary modules and dependencies
+// 導入必要的模組和依賴
+use std::cmp::Reverse;
+
+use chrono::{Datelike, Days, Duration, NaiveDate, NaiveTime, Weekday};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::{LeadAlert, Recurrence};
+
+// Matches a time of day: Chinese "晚上八點半", 12-hour "9:30pm", 24-hour "21:00", or "noon"
+// 匹配一天中的時間：中文「晚上八點半」、12 小時制「9:30pm」、24 小時制「21:00」或「noon」
+static TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    // Chinese numbers are spelled out so that "每週三三點" splits into "每週三" and "三點"
+    // 明確列出中文數字的寫法，讓「每週三三點」能分成「每週三」與「三點」
+    let cn_number =
+        "(?:[一二兩两三四五六七八九]?十[一二三四五六七八九]?|[零〇一二兩两三四五六七八九])";
+    Regex::new(&format!(
+        r"(?x)
+        (?P<cn>(?P<cn_period>早上|上午|清晨|中午|下午|傍晚|晚上|凌晨|半夜|深夜|早|晚)?
+            (?P<cn_h>\d{{1,2}}|{cn})[點点時时]
+            (?:(?P<cn_half>半)|(?P<cn_m>\d{{1,2}}|零?{cn})分?)?)
+        |(?P<en>(?P<en_h>\d{{1,2}})(?::(?P<en_m>\d{{2}}))?\s*(?P<en_ampm>am|pm))
+        |(?P<hm>(?P<hm_h>\d{{1,2}}):(?P<hm_m>\d{{2}}))
+        |(?P<word>noon|midnight)",
+        cn = cn_number
+    ))
+    .unwrap()
+});
+
+// Matches one part of a duration such as "2h", "30 minutes" or "二十分鐘"
+// 匹配時長的其中一段，例如「2h」、「30 minutes」或「二十分鐘」
+static DURATION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+        (?P<n>\d+|[零〇一二兩两三四五六七八九十百]+)\s*
+        (?P<unit>days?|d|hours?|hrs?|h|minutes?|mins?|m|天|日|個?小時|个?小时|個?鐘頭|分鐘|分钟|分)",
+    )
+    .unwrap()
+});
+
+// Matches an absolute date such as 2026-11-03 or 2026/11/03
+// 匹配絕對日期，例如 2026-11-03 或 2026/11/03
+static DATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d{4})[-/](\d{1,2})[-/](\d{1,2})$").unwrap());
+
+// Matches "every 3 days", "every other day", "每3天" or "隔天"
+// 匹配「every 3 days」、「every other day」、「每3天」或「隔天」
+static EVERY_DAYS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:every (?P<n>\d+|other) days?|每(?P<cn>\d+|[一二兩两三四五六七八九十]+)天|隔天)$",
+    )
+    .unwrap()
+});
+
+// Matches "every 2 weeks tue", "every other tue", "biweekly mon,thu", "隔週二" or "每兩週的週二"
+// 匹配「every 2 weeks tue」、「every other tue」、「biweekly mon,thu」、「隔週二」或「每兩週的週二」
+static EVERY_WEEKS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)^(?:
+            every\ (?P<n>\d+|other)\ weeks?|every\ other|biweekly|fortnightly|隔[週周]
+            |每(?P<cn>\d+|[一二兩两三四五六七八九十]+)個?[週周]的?
+        )\s*(?P<days>.+)$",
+    )
+    .unwrap()
+});
+
+// Matches a day of the month: "monthly 1st", "the 15th of every month" or "每月1號"
+// 匹配每月的日期：「monthly 1st」、「the 15th of every month」或「每月1號」
+static MONTHLY_DAY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)^(?:
+            (?:every\ month|each\ month|monthly)(?:\ the)?\ (?P<a>\d{1,2})(?:st|nd|rd|th)?
+            |(?:the\ )?(?P<b>\d{1,2})(?:st|nd|rd|th)?\ of\ (?:every|each)\ month
+            |每個?月的?(?P<cn>\d{1,2}|[一二三四五六七八九十]+)[號号日]
+        )$",
+    )
+    .unwrap()
+});
+
+// Matches the nth weekday of the month: "last fri of the month", "monthly 1st mon" or "每月第一個週一"
+// 匹配每月第幾個星期：「last fri of the month」、「monthly 1st mon」或「每月第一個週一」
+static MONTHLY_WEEKDAY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)^(?:
+            (?P<prefix>(?:every\ month|each\ month|monthly)\ )?(?:the\ )?
+            (?P<nth>first|1st|second|2nd|third|3rd|fourth|4th|fifth|5th|last)\ (?P<day>[a-z]+)
+            (?P<suffix>\ of\ (?:the|every|each)\ month)?
+            |每個?月的?(?:(?P<last>最後一個|最后一个)|第(?P<cn>\d|[一二三四五])個?)(?P<cn_day>.+)
+        )$",
+    )
+    .unwrap()
+});
+
+// English words that may sit between the day and the time without meaning anything
+// 位於日期與時間之間、沒有實際意義的英文字詞
+const FILLER_WORDS: &[&str] = &["at", "on"];
+
+// Limits on the lead alerts of a reminder
+// 提醒預告的數量與時間上限
+const MAX_LEADS: usize = 5;
+const MAX_LEAD_DAYS: i64 = 7;
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+// A schedule described by the user
+// 使用者描述的提醒時間
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    // Repeats according to a recurrence rule
+    // 依週期規則重複
+    Recurring {
+        recurrence: Recurrence,
+        time: NaiveTime,
+    },
+    // Fires once on a local date and time
+    // 在本地日期與時間觸發一次
+    Once {
+        date: NaiveDate,
+        time: NaiveTime,
+    },
+    // Fires once after the given duration
+    // 經過指定時長後觸發一次
+    After(Duration),
+}
+
+// What the day part of an input refers to
+// 輸入中日期部分所代表的意思
+enum DaySpec {
+    Recurring(Recurrence),
+    Date(NaiveDate),
+}
+
+// Parse a full schedule such as "every weekday 08:30", "1-5 07:30", "明天晚上八點" or "in 20 minutes";
+// relative dates are resolved against `today`, the local date of the guild
+// 解析完整的提醒時間，例如「every weekday 08:30」、「1-5 07:30」、「明天晚上八點」或「in 20 minutes」；
+// 相對日期以伺服器當地日期 `today` 為基準
+pub fn parse_schedule(input: &str, today: NaiveDate) -> Result<Schedule, String> {
+    let input = normalize(input);
+    if input.is_empty() {
+        return Err(
+            "請輸入提醒時間：ex. every weekday 08:30、明天晚上八點、in 20 minutes".to_string(),
+        );
+    }
+
+    if is_relative(&input) {
+        return parse_duration(&input).map(Schedule::After);
+    }
+
+    let (time, day) = split_time(&input)?;
+    match parse_day(&day, today)? {
+        DaySpec::Recurring(recurrence) => Ok(Schedule::Recurring { recurrence, time }),
+        DaySpec::Date(date) => Ok(Schedule::Once { date, time }),
+    }
+}
+
+// Parse weekdays only, such as "1,4,7", "mon,wed,fri", "1-5", "weekdays" or "每週一三五"
+// 只解析星期，例如「1,4,7」、「mon,wed,fri」、「1-5」、「weekdays」或「每週一三五」
+pub fn parse_weekdays(input: &str) -> Result<Vec<Weekday>, String> {
+    let input = normalize(input);
+    if input.is_empty() {
+        return Err("請輸入星期：ex. 1,4,7、mon-fri、每週三".to_string());
+    }
+    parse_weekday_list(&input)
+}
+
+// Parse a time of day only, such as "21:00", "9pm" or "下午三點"
+// 只解析一天中的時間，例如「21:00」、「9pm」或「下午三點」
+pub fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    let input = normalize(input);
+    let (time, rest) = split_time(&input)?;
+    if !rest.is_empty() {
+        return Err(format!(
+            "無法辨識的時間：'{}'，ex. 08:30、9pm、晚上八點",
+            input
+        ));
+    }
+    Ok(time)
+}
+
+// Parse a duration such as "45m", "2h30m", "in 1 hour 30 minutes" or "二十分鐘後"
+// 解析時長，例如「45m」、「2h30m」、「in 1 hour 30 minutes」或「二十分鐘後」
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let original = normalize(input);
+    let mut text = original.as_str();
+    text = text.strip_prefix("in ").unwrap_or(text);
+    text = text.strip_suffix("later").unwrap_or(text);
+    text = text.trim_end_matches(['後', '后', '以']).trim();
+    let text = text
+        .replace("個半小時", "小時30分鐘")
+        .replace("个半小时", "小时30分钟")
+        .replace("半小時", "30分鐘")
+        .replace("半小时", "30分钟")
+        .replace("half an hour", "30 minutes");
+
+    let invalid = || {
+        format!(
+            "無法辨識的時長：'{}'，ex. 45m、2h30m、in 20 minutes、20分鐘後",
+            original
+        )
+    };
+
+    let mut total = Duration::zero();
+    let mut last_end = 0;
+    let mut found = false;
+    for caps in DURATION_RE.captures_iter(&text) {
+        let whole = caps.get(0).unwrap();
+        // Only separators may sit between the parts, e.g. "1 hour and 20 minutes"
+        // 各段之間只能是分隔字詞，例如「1 hour and 20 minutes」
+        if !is_separator(&text[last_end..whole.start()]) {
+            return Err(invalid());
+        }
+        last_end = whole.end();
+        found = true;
+
+        let value = parse_number(&caps["n"]).ok_or_else(invalid)?;
+        let value = i64::from(value);
+        let part = match &caps["unit"] {
+            "d" | "day" | "days" | "天" | "日" => Duration::try_days(value),
+            "m" | "min" | "mins" | "minute" | "minutes" | "分鐘" | "分钟" | "分" => {
+                Duration::try_minutes(value)
+            }
+            _ => Duration::try_hours(value),
+        };
+        total = part
+            .and_then(|part| total.checked_add(&part))
+            .ok_or_else(|| format!("時長過長：'{}'", original))?;
+    }
+    if !found || !is_separator(&text[last_end..]) {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+// Parse lead alerts separated by ";", each a duration followed by an optional message,
+// such as "30m 準備集合; 5m 即將開始" or "1h; 10分鐘前"
+// 解析以「;」分隔的預告，每項為時長加上可省略的訊息，
+// 例如「30m 準備集合; 5m 即將開始」或「1h; 10分鐘前」
+pub fn parse_leads(input: &str) -> Result<Vec<LeadAlert>, String> {
+    let mut leads: Vec<LeadAlert> = Vec::new();
+    for entry in input.split([';', '；']).map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (duration, message) = entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+        let duration = parse_duration(duration.trim_end_matches('前'))?;
+        if duration < Duration::minutes(1) || duration > Duration::days(MAX_LEAD_DAYS) {
+            return Err(format!(
+                "預告時間需介於 1 分鐘與 {} 天之間：'{}'",
+                MAX_LEAD_DAYS, entry
+            ));
+        }
+        let minutes = duration.num_minutes() as u32;
+        if leads.iter().any(|lead| lead.minutes == minutes) {
+            return Err(format!("重複的預告時間：'{}'", entry));
+        }
+        leads.push(LeadAlert {
+            minutes,
+            message: message.trim().to_string(),
+        });
+    }
+    if leads.len() > MAX_LEADS {
+        return Err(format!("最多只能設定 {} 個預告", MAX_LEADS));
+    }
+    // Earliest alert first
+    // 最早的預告排在前面
+    leads.sort_by_key(|lead| Reverse(lead.minutes));
+    Ok(leads)
+}
+
+// Lowercase the input and turn full-width characters into their ASCII forms
+// 將輸入轉為小寫，並把全形字元轉為 ASCII
+fn normalize(input: &str) -> String {
+    input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+            '：' => ':',
+            '，' => ',',
+            '～' => '~',
+            '－' => '-',
+            '／' => '/',
+            '　' => ' ',
+            _ => c,
+        })
+        .collect::<String>()
+        .to_lowercase()
+}
+
+// A relative schedule starts with "in" or ends with "later" / "後"
+// 相對時間以「in」開頭，或以「later」/「後」結尾
+fn is_relative(input: &str) -> bool {
+    input.starts_with("in ")
+        || input.ends_with("later")
+        || input.ends_with('後')
+        || input.ends_with('后')
+}
+
+// Text allowed between the parts of a duration
+// 時長各段之間允許的文字
+fn is_separator(text: &str) -> bool {
+    matches!(text.trim(), "" | "," | "and" | "又" | "零")
+}
+
+// Find the single time of day in the input, returning it with the remaining text
+// 找出輸入中唯一的時間，並返回時間與剩下的文字
+fn split_time(input: &str) -> Result<(NaiveTime, String), String> {
+    let matches: Vec<Captures> = TIME_RE
+        .captures_iter(input)
+        .filter(|caps| {
+            // A time must not be glued to other digits, e.g. "123:45"
+            // 時間前後不能緊接其他數字，例如「123:45」
+            let whole = caps.get(0).unwrap();
+            let before = input[..whole.start()].chars().next_back();
+            let after = input[whole.end()..].chars().next();
+            !before.is_some_and(|c| c.is_ascii_digit() || c == ':')
+                && !after.is_some_and(|c| c.is_ascii_alphanumeric() || c == ':')
+        })
+        .collect();
+
+    let caps = match matches.as_slice() {
+        [] => {
+            return Err(format!(
+                "找不到時間：'{}'，請加上時間，ex. 08:30、9pm、晚上八點",
+                input
+            ))
+        }
+        [caps] => caps,
+        _ => {
+            let found: Vec<&str> = matches.iter().map(|c| c.get(0).unwrap().as_str()).collect();
+            return Err(format!("只能指定一個時間，但找到了：{}", found.join("、")));
+        }
+    };
+
+    let whole = caps.get(0).unwrap();
+    let time = time_from_captures(caps)?;
+    let rest = format!("{} {}", &input[..whole.start()], &input[whole.end()..]);
+    let rest = rest
+        .split_whitespace()
+        .filter(|word| !FILLER_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let rest = rest.trim_matches(['的', '在']).trim().to_string();
+    Ok((time, rest))
+}
+
+// Build the time of day from a match of TIME_RE, checking the hour and minute ranges
+// 從 TIME_RE 的匹配結果建立時間，並檢查小時與分鐘的範圍
+fn time_from_captures(caps: &Captures) -> Result<NaiveTime, String> {
+    let text = caps.get(0).unwrap().as_str();
+    let invalid_number = || format!("無法辨識的時間：'{}'", text);
+
+    let (hour, minute) = if caps.name("cn").is_some() {
+        let mut hour = parse_number(&caps["cn_h"]).ok_or_else(invalid_number)?;
+        let minute = match (caps.name("cn_half"), caps.name("cn_m")) {
+            (Some(_), _) => 30,
+            (None, Some(m)) => parse_number(m.as_str()).ok_or_else(invalid_number)?,
+            (None, None) => 0,
+        };
+        if let Some(period) = caps.name("cn_period") {
+            hour = match (period.as_str(), hour) {
+                ("早上" | "上午" | "清晨" | "早" | "凌晨", h) if h > 12 => {
+                    return Err(format!(
+                        "無效的時間：'{}'，{}的小時需為 0-12",
+                        text,
+                        period.as_str()
+                    ))
+                }
+                ("下午" | "傍晚" | "晚上" | "晚", h) if h < 12 => h + 12,
+                ("中午", h) if h < 6 => h + 12,
+                ("半夜" | "深夜", h) if (9..12).contains(&h) => h + 12,
+                ("晚上" | "晚" | "凌晨" | "半夜" | "深夜", 12) => 0,
+                (_, h) => h,
+            };
+        }
+        (hour, minute)
+    } else if caps.name("en").is_some() {
+        let hour: u32 = caps["en_h"].parse().map_err(|_| invalid_number())?;
+        let minute: u32 = match caps.name("en_m") {
+            Some(m) => m.as_str().parse().map_err(|_| invalid_number())?,
+            None => 0,
+        };
+        if !(1..=12).contains(&hour) {
+            return Err(format!("無效的時間：'{}'，12 小時制的小時需為 1-12", text));
+        }
+        let hour = match &caps["en_ampm"] {
+            "am" => hour % 12,
+            _ => hour % 12 + 12,
+        };
+        (hour, minute)
+    } else if caps.name("hm").is_some() {
+        let hour: u32 = caps["hm_h"].parse().map_err(|_| invalid_number())?;
+        let minute: u32 = caps["hm_m"].parse().map_err(|_| invalid_number())?;
+        (hour, minute)
+    } else {
+        match &caps["word"] {
+            "noon" => (12, 0),
+            _ => (0, 0),
+        }
+    };
+
+    if hour > 23 {
+        return Err(format!("無效的時間：'{}'，小時需為 0-23", text));
+    }
+    if minute > 59 {
+        return Err(format!("無效的時間：'{}'，分鐘需為 0-59", text));
+    }
+    Ok(NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+}
+
+// Parse the day part of a schedule: a relative or absolute date, or a set of weekdays
+// 解析提醒時間中的日期部分：相對或絕對日期，或一組星期
+fn parse_day(day: &str, today: NaiveDate) -> Result<DaySpec, String> {
+    let offset = match day {
+        "" => return Err("缺少日期或星期：ex. every day 08:30、1-5 07:30、明天 20:00".to_string()),
+        "today" | "tonight" | "今天" | "今日" | "今" => Some(0),
+        "tomorrow" | "明天" | "明日" | "明" => Some(1),
+        "後天" | "后天" => Some(2),
+        "大後天" | "大后天" => Some(3),
+        _ => None,
+    };
+    if let Some(offset) = offset {
+        return Ok(DaySpec::Date(today + Days::new(offset)));
+    }
+
+    if let Some(caps) = DATE_RE.captures(day) {
+        let parts: Vec<u32> = (1..=3).filter_map(|i| caps[i].parse().ok()).collect();
+        return match NaiveDate::from_ymd_opt(parts[0] as i32, parts[1], parts[2]) {
+            Some(date) => Ok(DaySpec::Date(date)),
+            None => Err(format!("無效的日期：'{}'", day)),
+        };
+    }
+
+    parse_recurrence(day, today)
+        .unwrap_or_else(|| {
+            parse_weekday_list(day).map(|weekdays| Recurrence::Weekly {
+                weekdays,
+                interval: 1,
+                anchor: None,
+            })
+        })
+        .map(DaySpec::Recurring)
+}
+
+// Parse the recurrence rules beyond plain weekdays, returning None when the text is none of them;
+// intervals are counted from `today`
+// 解析一般星期以外的週期規則，不屬於這些規則時返回 None；間隔從 `today` 開始計算
+fn parse_recurrence(day: &str, today: NaiveDate) -> Option<Result<Recurrence, String>> {
+    let interval = |caps: &Captures| -> Result<u32, String> {
+        let interval = match (caps.name("n"), caps.name("cn")) {
+            (Some(n), _) if n.as_str() == "other" => Some(2),
+            (Some(n), _) | (None, Some(n)) => parse_number(n.as_str()),
+            (None, None) => Some(2),
+        };
+        match interval {
+            Some(n @ 1..=365) => Ok(n),
+            _ => Err(format!("無效的間隔：'{}'，需為 1-365", day)),
+        }
+    };
+
+    if let Some(caps) = EVERY_DAYS_RE.captures(day) {
+        return Some(interval(&caps).map(|interval| Recurrence::Daily {
+            interval,
+            anchor: today,
+        }));
+    }
+
+    if let Some(caps) = EVERY_WEEKS_RE.captures(day) {
+        return Some(interval(&caps).and_then(|interval| {
+            let weekdays = parse_weekday_list(&caps["days"])?;
+            // Count weeks from the first upcoming day so "every other tue" starts this week
+            // 從第一個即將到來的日期開始計算週數，讓「every other tue」從本週開始
+            let anchor = (0..7)
+                .map(|offset| today + Days::new(offset))
+                .find(|date| weekdays.contains(&date.weekday()));
+            Ok(Recurrence::Weekly {
+                weekdays,
+                interval,
+                anchor,
+            })
+        }));
+    }
+
+    if let Some(caps) = MONTHLY_DAY_RE.captures(day) {
+        let text = caps.name("a").or(caps.name("b")).or(caps.name("cn"))?;
+        return Some(match parse_number(text.as_str()) {
+            Some(day @ 1..=31) => Ok(Recurrence::MonthlyDay { day }),
+            _ => Err(format!(
+                "無效的日期：'{}'，每月的日期需為 1-31",
+                text.as_str()
+            )),
+        });
+    }
+
+    if let Some(caps) = MONTHLY_WEEKDAY_RE.captures(day) {
+        // English forms need "monthly" or "of the month", so "last fri" alone is not monthly
+        // 英文寫法需要有「monthly」或「of the month」，單獨的「last fri」不算每月規則
+        if caps.name("nth").is_some()
+            && caps.name("prefix").is_none()
+            && caps.name("suffix").is_none()
+        {
+            return None;
+        }
+        let nth = match (caps.name("nth"), caps.name("last"), caps.name("cn")) {
+            (Some(n), _, _) => match n.as_str() {
+                "first" | "1st" => 1,
+                "second" | "2nd" => 2,
+                "third" | "3rd" => 3,
+                "fourth" | "4th" => 4,
+                "fifth" | "5th" => 5,
+                _ => -1,
+            },
+            (None, Some(_), _) => -1,
+            (None, None, Some(n)) => parse_number(n.as_str()).map_or(0, |n| n as i8),
+            (None, None, None) => return None,
+        };
+        if !(-1..=5).contains(&nth) || nth == 0 {
+            return Some(Err(format!(
+                "無效的星期順序：'{}'，需為第 1-5 個或最後一個",
+                day
+            )));
+        }
+        let weekday = caps.name("day").or(caps.name("cn_day"))?;
+        return Some(
+            parse_single_weekday(weekday.as_str())
+                .map(|weekday| Recurrence::MonthlyWeekday { nth, weekday }),
+        );
+    }
+
+    None
+}
+
+// Parse a list of weekdays with ranges, names in English or Chinese, or the numbers 1-7
+// 解析星期列表，可包含範圍、中英文名稱或數字 1-7
+fn parse_weekday_list(input: &str) -> Result<Vec<Weekday>, String> {
+    let mut text = input.trim();
+    for prefix in ["every ", "each ", "每個", "每个", "每"] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            text = rest.trim();
+            break;
+        }
+    }
+
+    // Named groups of days
+    // 具名的星期組合
+    match text {
+        "day" | "days" | "daily" | "everyday" | "天" | "日" | "一天" => {
+            return Ok(WEEK.to_vec())
+        }
+        "weekday" | "weekdays" | "平日" | "工作日" | "週間" | "周间" => {
+            return Ok(WEEK[..5].to_vec())
+        }
+        "weekend" | "weekends" | "週末" | "周末" => return Ok(WEEK[5..].to_vec()),
+        _ => {}
+    }
+
+    let text = text
+        .replace(" to ", "-")
+        .replace(['~', '到', '至'], "-")
+        .replace(" - ", "-");
+    let mut weekdays = Vec::new();
+    for item in text
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '、' | '/' | '&' | '和' | '及'))
+        .filter(|item| !item.is_empty() && *item != "and")
+    {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_single_weekday(start)?;
+                let end = parse_single_weekday(end)?;
+                // Ranges may wrap around the week, e.g. "fri-mon"
+                // 範圍可以跨週，例如「fri-mon」
+                let mut day = start;
+                loop {
+                    weekdays.push(day);
+                    if day == end {
+                        break;
+                    }
+                    day = day.succ();
+                }
+            }
+            None => weekdays.extend(parse_weekday_item(item)?),
+        }
+    }
+
+    if weekdays.is_empty() {
+        return Err(format!("無法辨識的星期：'{}'", input));
+    }
+    weekdays.sort_by_key(|day| day.num_days_from_monday());
+    weekdays.dedup();
+    Ok(weekdays)
+}
+
+// Parse one list item, which may hold several Chinese days at once such as "一三五"
+// 解析列表中的一項，可同時包含多個中文星期，例如「一三五」
+fn parse_weekday_item(item: &str) -> Result<Vec<Weekday>, String> {
+    let name = strip_week_prefix(item);
+    if name.chars().count() > 1 && name.chars().all(|c| chinese_weekday(c).is_some()) {
+        return Ok(name.chars().filter_map(chinese_weekday).collect());
+    }
+    parse_single_weekday(item).map(|day| vec![day])
+}
+
+// Parse exactly one weekday, never falling back to a default day
+// 解析單一星期，無法辨識時不會使用預設值
+fn parse_single_weekday(item: &str) -> Result<Weekday, String> {
+    let name = strip_week_prefix(item.trim());
+    let invalid = || {
+        format!(
+            "無法辨識的星期：'{}'，請使用 1-7、mon-sun 或 一-日",
+            item.trim()
+        )
+    };
+
+    if let Ok(number) = name.parse::<usize>() {
+        return match number {
+            1..=7 => Ok(WEEK[number - 1]),
+            _ => Err(format!(
+                "無效的星期：'{}'，數字需為 1 (星期一) 到 7 (星期日)",
+                number
+            )),
+        };
+    }
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return chinese_weekday(c).ok_or_else(invalid);
+    }
+
+    let day = match name {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "weds" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return Err(invalid()),
+    };
+    Ok(day)
+}
+
+// Remove a leading "週" / "星期" / "禮拜" from a Chinese weekday
+// 移除中文星期前方的「週」、「星期」或「禮拜」
+fn strip_week_prefix(item: &str) -> &str {
+    for prefix in ["星期", "禮拜", "礼拜", "週", "周"] {
+        if let Some(rest) = item.strip_prefix(prefix) {
+            return rest;
+        }
+    }
+    item
+}
+
+// Map a Chinese weekday character to its weekday
+// 將中文星期字元轉換為星期
+fn chinese_weekday(c: char) -> Option<Weekday> {
+    match c {
+        '一' => Some(Weekday::Mon),
+        '二' => Some(Weekday::Tue),
+        '三' => Some(Weekday::Wed),
+        '四' => Some(Weekday::Thu),
+        '五' => Some(Weekday::Fri),
+        '六' => Some(Weekday::Sat),
+        '日' | '天' | '七' => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// Parse an Arabic or Chinese number below one thousand, e.g. "15", "十五" or "二十"
+// 解析一千以下的阿拉伯或中文數字，例如「15」、「十五」或「二十」
+fn parse_number(text: &str) -> Option<u32> {
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        return text.parse().ok();
+    }
+
+    let digit = |c: char| match c {
+        '零' | '〇' => Some(0),
+        '一' => Some(1),
+        '二' | '兩' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    };
+
+    let mut total = 0;
+    let mut current: Option<u32> = None;
+    let mut last_unit = u32::MAX;
+    for c in text.chars() {
+        let unit = match c {
+            '百' => 100,
+            '十' => 10,
+            _ => {
+                // Two digits in a row such as "三三" are not a number
+                // 連續兩個數字（例如「三三」）不是有效的數字
+                if current.is_some_and(|n| n != 0) {
+                    return None;
+                }
+                current = Some(digit(c)?);
+                continue;
+            }
+        };
+        if unit >= last_unit {
+            return None;
+        }
+        last_unit = unit;
+        total += current.take().unwrap_or(1) * unit;
+    }
+    Some(total + current.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday, used as the guild's local date
+    // 星期三，作為伺服器的當地日期
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 5).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn weekly(weekdays: &[Weekday], h: u32, m: u32) -> Schedule {
+        Schedule::Recurring {
+            recurrence: Recurrence::Weekly {
+                weekdays: weekdays.to_vec(),
+                interval: 1,
+                anchor: None,
+            },
+            time: time(h, m),
+        }
+    }
+
+    fn schedule_error(input: &str) -> String {
+        parse_schedule(input, today()).unwrap_err()
+    }
+
+    #[test]
+    fn every_weekday() {
+        assert_eq!(
+            parse_schedule("every weekday 08:30", today()),
+            Ok(weekly(&WEEK[..5], 8, 30))
+        );
+    }
+
+    #[test]
+    fn weekday_names() {
+        assert_eq!(
+            parse_schedule("mon,wed,fri 21:00", today()),
+            Ok(weekly(&[Weekday::Mon, Weekday::Wed, Weekday::Fri], 21, 0))
+        );
+    }
+
+    #[test]
+    fn weekday_number_range() {
+        assert_eq!(
+            parse_schedule("1-5 07:30", today()),
+            Ok(weekly(&WEEK[..5], 7, 30))
+        );
+    }
+
+    #[test]
+    fn chinese_tomorrow_evening() {
+        assert_eq!(
+            parse_schedule("明天晚上八點", today()),
+            Ok(Schedule::Once {
+                date: NaiveDate::from_ymd_opt(2025, 3, 6).unwrap(),
+                time: time(20, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn chinese_weekly_afternoon() {
+        assert_eq!(
+            parse_schedule("每週三下午三點", today()),
+            Ok(weekly(&[Weekday::Wed], 15, 0))
+        );
+    }
+
+    #[test]
+    fn in_minutes() {
+        assert_eq!(
+            parse_schedule("in 20 minutes", today()),
+            Ok(Schedule::After(Duration::minutes(20)))
+        );
+    }
+
+    #[test]
+    fn weekday_digit_out_of_range_is_an_error() {
+        // 8 must never fall back to Monday
+        // 8 絕不能被當作星期一
+        let err = schedule_error("8 08:00");
+        assert!(
+            err.contains("'8'") && err.contains("1 (星期一) 到 7 (星期日)"),
+            "{err}"
+        );
+        let err = parse_weekdays("1,8").unwrap_err();
+        assert!(err.contains("1 (星期一) 到 7 (星期日)"), "{err}");
+        assert!(parse_weekdays("0").is_err());
+    }
+
+    #[test]
+    fn unknown_weekday_is_an_error() {
+        let err = parse_weekdays("mon,funday").unwrap_err();
+        assert!(err.contains("'funday'"), "{err}");
+    }
+
+    #[test]
+    fn bad_hour_or_minute_is_an_error() {
+        assert!(schedule_error("mon 25:00").contains("小時需為 0-23"));
+        assert!(schedule_error("mon 08:75").contains("分鐘需為 0-59"));
+        assert!(schedule_error("mon 13pm").contains("12 小時制的小時需為 1-12"));
+        assert!(schedule_error("明天早上十五點").contains("的小時需為 0-12"));
+        assert!(parse_time("24:00").is_err());
+    }
+
+    #[test]
+    fn reversed_range_wraps_around_the_week() {
+        assert_eq!(
+            parse_weekdays("5-1"),
+            Ok(vec![Weekday::Mon, Weekday::Fri, Weekday::Sat, Weekday::Sun])
+        );
+        assert_eq!(
+            parse_weekdays("sat-tue"),
+            Ok(vec![Weekday::Mon, Weekday::Tue, Weekday::Sat, Weekday::Sun])
+        );
+        // A reversed range with an invalid end is still an error
+        // 結尾無效的反向範圍仍是錯誤
+        assert!(parse_weekdays("5-9").is_err());
+    }
+
+    #[test]
+    fn empty_input_is_an_error() {
+        assert!(schedule_error("").contains("請輸入提醒時間"));
+        assert!(schedule_error("   ").contains("請輸入提醒時間"));
+        assert!(parse_weekdays("").unwrap_err().contains("請輸入星期"));
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn missing_or_repeated_time_is_an_error() {
+        assert!(schedule_error("every weekday").contains("找不到時間"));
+        assert!(schedule_error("mon 08:00 09:00").contains("只能指定一個時間"));
+        assert!(schedule_error("08:30").contains("缺少日期或星期"));
+    }
+
+    #[test]
+    fn bad_duration_is_an_error() {
+        assert!(schedule_error("in 20 bananas").contains("無法辨識的時長"));
+        assert_eq!(parse_duration("2h30m"), Ok(Duration::minutes(150)));
+        assert_eq!(parse_duration("二十分鐘後"), Ok(Duration::minutes(20)));
+    }
+}